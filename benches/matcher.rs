@@ -0,0 +1,78 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use ipstuff::{Ipv4Addr, Ipv4Mask, Ipv4Matcher, MaskedIpv4};
+use std::hint::black_box;
+
+/// A small xorshift generator, so the benchmark needs no extra dependencies.
+fn xorshift(state: &mut u32) -> u32 {
+    *state ^= *state << 13;
+    *state ^= *state >> 17;
+    *state ^= *state << 5;
+    *state
+}
+
+fn prefixes(count: usize) -> Vec<MaskedIpv4> {
+    let mut state = 0x1234_5678;
+    (0..count)
+        .map(|_| {
+            let bits = xorshift(&mut state);
+            let len = 8 + (xorshift(&mut state) % 25) as u8;
+            MaskedIpv4::new(Ipv4Addr::from_bits(bits), Ipv4Mask::new(len)).network()
+        })
+        .collect()
+}
+
+fn addresses(nets: &[MaskedIpv4], count: usize) -> Vec<Ipv4Addr> {
+    let mut state = 0x9abc_def0;
+    (0..count)
+        .map(|i| {
+            let bits = xorshift(&mut state);
+            // Half of the addresses fall inside a listed network.
+            if i % 2 == 0 {
+                let net = nets[bits as usize % nets.len()];
+                Ipv4Addr::from_bits(net.ip.to_bits() | (bits & !net.mask.to_bits()))
+            } else {
+                Ipv4Addr::from_bits(bits)
+            }
+        })
+        .collect()
+}
+
+fn linear(nets: &[MaskedIpv4], addr: Ipv4Addr) -> Option<usize> {
+    let mut best: Option<usize> = None;
+    for (i, net) in nets.iter().enumerate() {
+        if net.contains(addr) && best.is_none_or(|b| net.network_bits() > nets[b].network_bits()) {
+            best = Some(i);
+        }
+    }
+    best
+}
+
+fn bench(c: &mut Criterion) {
+    let nets = prefixes(300);
+    let addrs = addresses(&nets, 4096);
+    let matcher = Ipv4Matcher::new(nets.iter().copied());
+    let mut out = vec![None; addrs.len()];
+
+    let mut group = c.benchmark_group("ipv4_300_prefixes_4096_addrs");
+    group.bench_function("linear_contains", |b| {
+        b.iter(|| {
+            for (addr, out) in addrs.iter().zip(out.iter_mut()) {
+                *out = linear(black_box(&nets), *addr);
+            }
+        })
+    });
+    group.bench_function("matches", |b| {
+        b.iter(|| {
+            for (addr, out) in addrs.iter().zip(out.iter_mut()) {
+                *out = black_box(&matcher).matches(*addr);
+            }
+        })
+    });
+    group.bench_function("matches_batch", |b| {
+        b.iter(|| black_box(&matcher).matches_batch(black_box(&addrs), &mut out))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -0,0 +1,336 @@
+use crate::{IpAddr, MaskedIp};
+
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// What to do with a packet matched by a [`Rule`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Action {
+    /// Let the packet through.
+    Allow,
+    /// Drop the packet.
+    Deny,
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::Allow => "allow",
+            Self::Deny => "deny",
+        })
+    }
+}
+
+/// An IP protocol number, such as 6 for TCP.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Protocol(pub u8);
+
+impl Protocol {
+    /// Internet Control Message Protocol
+    pub const ICMP: Self = Self(1);
+    /// Transmission Control Protocol
+    pub const TCP: Self = Self(6);
+    /// User Datagram Protocol
+    pub const UDP: Self = Self(17);
+    /// ICMP for IPv6
+    pub const ICMPV6: Self = Self(58);
+}
+
+impl Display for Protocol {
+    /// Writes the protocol name for common protocols, otherwise the number.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            Self::ICMP => f.write_str("icmp"),
+            Self::TCP => f.write_str("tcp"),
+            Self::UDP => f.write_str("udp"),
+            Self::ICMPV6 => f.write_str("ipv6-icmp"),
+            Self(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// An inclusive range of ports.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PortRange {
+    start: u16,
+    end: u16,
+}
+
+impl PortRange {
+    /// Every port.
+    pub const ANY: Self = Self::new(0, u16::MAX);
+    /// Constructs a range from `start` to `end`, inclusive.
+    ///
+    /// # Panics
+    /// Panics if `start` is greater than `end`.
+    pub const fn new(start: u16, end: u16) -> Self {
+        assert!(start <= end, "port range start must be <= end");
+        Self { start, end }
+    }
+    /// Constructs a range of one port.
+    pub const fn single(port: u16) -> Self {
+        Self::new(port, port)
+    }
+    /// Returns the first port of this range.
+    pub const fn start(&self) -> u16 {
+        self.start
+    }
+    /// Returns the last port of this range.
+    pub const fn end(&self) -> u16 {
+        self.end
+    }
+    /// Returns true if `port` is within this range.
+    pub const fn contains(&self, port: u16) -> bool {
+        self.start <= port && port <= self.end
+    }
+    /// Returns true if every port of `other` is within this range.
+    pub const fn contains_range(&self, other: &PortRange) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+}
+
+impl Display for PortRange {
+    /// Writes a single port as `80`, and other ranges as `1024-65535`.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.start == self.end {
+            write!(f, "{}", self.start)
+        } else {
+            write!(f, "{}-{}", self.start, self.end)
+        }
+    }
+}
+
+/// The header fields of a packet which rules can match on.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Packet {
+    /// The source address
+    pub src: IpAddr,
+    /// The destination address
+    pub dst: IpAddr,
+    /// The IP protocol
+    pub protocol: Protocol,
+    /// The source port, or None for protocols without ports
+    pub src_port: Option<u16>,
+    /// The destination port, or None for protocols without ports
+    pub dst_port: Option<u16>,
+}
+
+impl Packet {
+    /// Constructs a packet of a protocol without ports, such as ICMP.
+    pub const fn new(src: IpAddr, dst: IpAddr, protocol: Protocol) -> Self {
+        Self {
+            src,
+            dst,
+            protocol,
+            src_port: None,
+            dst_port: None,
+        }
+    }
+    /// Constructs a TCP packet.
+    pub const fn tcp(src: IpAddr, src_port: u16, dst: IpAddr, dst_port: u16) -> Self {
+        Self::new(src, dst, Protocol::TCP).with_ports(src_port, dst_port)
+    }
+    /// Constructs a UDP packet.
+    pub const fn udp(src: IpAddr, src_port: u16, dst: IpAddr, dst_port: u16) -> Self {
+        Self::new(src, dst, Protocol::UDP).with_ports(src_port, dst_port)
+    }
+    /// Returns this packet with the provided ports.
+    pub const fn with_ports(mut self, src_port: u16, dst_port: u16) -> Self {
+        self.src_port = Some(src_port);
+        self.dst_port = Some(dst_port);
+        self
+    }
+}
+
+/// One rule of an [`Acl`]. A rule matches a packet when every one of its conditions does.
+///
+/// A port condition never matches a packet without ports. Source and destination networks never
+/// match addresses of the other family.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Rule {
+    /// The action taken for matching packets
+    pub action: Action,
+    /// The network containing matching source addresses
+    pub src: MaskedIp,
+    /// The network containing matching destination addresses
+    pub dst: MaskedIp,
+    /// The protocol of matching packets, or None for any protocol
+    pub protocol: Option<Protocol>,
+    /// The source ports of matching packets, or None for any port or no port
+    pub src_ports: Option<PortRange>,
+    /// The destination ports of matching packets, or None for any port or no port
+    pub dst_ports: Option<PortRange>,
+}
+
+/// Returns true if `outer` matches every value that `inner` does, where None matches anything.
+const fn covers_ports(outer: Option<PortRange>, inner: Option<PortRange>) -> bool {
+    match (outer, inner) {
+        (None, _) => true,
+        (Some(outer), Some(inner)) => outer.contains_range(&inner),
+        (Some(_), None) => false,
+    }
+}
+
+/// Returns true if `range` is None or contains `port`.
+const fn matches_port(range: Option<PortRange>, port: Option<u16>) -> bool {
+    match (range, port) {
+        (None, _) => true,
+        (Some(range), Some(port)) => range.contains(port),
+        (Some(_), None) => false,
+    }
+}
+
+impl Rule {
+    /// Constructs a rule matching any protocol and port between the provided networks.
+    pub const fn new(action: Action, src: MaskedIp, dst: MaskedIp) -> Self {
+        Self {
+            action,
+            src,
+            dst,
+            protocol: None,
+            src_ports: None,
+            dst_ports: None,
+        }
+    }
+    /// Returns this rule restricted to `protocol`.
+    pub const fn with_protocol(mut self, protocol: Protocol) -> Self {
+        self.protocol = Some(protocol);
+        self
+    }
+    /// Returns this rule restricted to source ports within `ports`.
+    pub const fn with_src_ports(mut self, ports: PortRange) -> Self {
+        self.src_ports = Some(ports);
+        self
+    }
+    /// Returns this rule restricted to destination ports within `ports`.
+    pub const fn with_dst_ports(mut self, ports: PortRange) -> Self {
+        self.dst_ports = Some(ports);
+        self
+    }
+    /// Returns true if this rule matches `packet`.
+    pub const fn matches(&self, packet: &Packet) -> bool {
+        let protocol = match self.protocol {
+            Some(Protocol(p)) => p == packet.protocol.0,
+            None => true,
+        };
+        protocol
+            && self.src.contains(packet.src)
+            && self.dst.contains(packet.dst)
+            && matches_port(self.src_ports, packet.src_port)
+            && matches_port(self.dst_ports, packet.dst_port)
+    }
+    /// Returns true if this rule matches every packet that `other` matches.
+    pub const fn covers(&self, other: &Rule) -> bool {
+        let protocol = match (self.protocol, other.protocol) {
+            (None, _) => true,
+            (Some(Protocol(a)), Some(Protocol(b))) => a == b,
+            (Some(_), None) => false,
+        };
+        protocol
+            && self.src.is_supernet_of(&other.src)
+            && self.dst.is_supernet_of(&other.dst)
+            && covers_ports(self.src_ports, other.src_ports)
+            && covers_ports(self.dst_ports, other.dst_ports)
+    }
+}
+
+/// A problem found by [`Acl::analyze`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum AclWarning {
+    /// The rule can never match, because an earlier rule with a different action matches every
+    /// packet it would.
+    Shadowed {
+        /// The index of the rule which never matches.
+        rule: usize,
+        /// The index of the earlier rule.
+        by: usize,
+    },
+    /// The rule can never match, because an earlier rule with the same action matches every
+    /// packet it would. Removing it does not change the result of the ACL.
+    Redundant {
+        /// The index of the rule which never matches.
+        rule: usize,
+        /// The index of the earlier rule.
+        by: usize,
+    },
+}
+
+impl Display for AclWarning {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Shadowed { rule, by } => {
+                write!(f, "rule {rule} is shadowed by rule {by} and never matches")
+            }
+            Self::Redundant { rule, by } => {
+                write!(
+                    f,
+                    "rule {rule} is redundant with rule {by} and never matches"
+                )
+            }
+        }
+    }
+}
+
+/// An ordered list of rules where the first matching rule decides the action for a packet.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Acl {
+    rules: Vec<Rule>,
+    default: Action,
+}
+
+impl Acl {
+    /// Constructs an empty ACL which takes the `default` action for packets no rule matches.
+    pub const fn new(default: Action) -> Self {
+        Self {
+            rules: Vec::new(),
+            default,
+        }
+    }
+    /// Adds a rule after every existing rule.
+    pub fn push(&mut self, rule: Rule) {
+        self.rules.push(rule);
+    }
+    /// Returns the rules in order.
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+    /// Returns the action for packets no rule matches.
+    pub fn default_action(&self) -> Action {
+        self.default
+    }
+    /// Returns the index of the first rule matching `packet`, or None if no rule does.
+    pub fn first_match(&self, packet: &Packet) -> Option<usize> {
+        self.rules.iter().position(|rule| rule.matches(packet))
+    }
+    /// Returns the action of the first rule matching `packet`, or the default action if no rule does.
+    pub fn evaluate(&self, packet: &Packet) -> Action {
+        match self.first_match(packet) {
+            Some(i) => self.rules[i].action,
+            None => self.default,
+        }
+    }
+    /// Returns a warning for every rule which an earlier rule covers entirely, in rule order.
+    ///
+    /// Only single covering rules are detected. A rule covered by the combination of several
+    /// earlier rules, such as two /25 rules before a /24 rule, is not reported.
+    pub fn analyze(&self) -> Vec<AclWarning> {
+        let mut warnings = Vec::new();
+        for (rule, later) in self.rules.iter().enumerate() {
+            let Some(by) = self.rules[..rule].iter().position(|e| e.covers(later)) else {
+                continue;
+            };
+            warnings.push(if self.rules[by].action == later.action {
+                AclWarning::Redundant { rule, by }
+            } else {
+                AclWarning::Shadowed { rule, by }
+            });
+        }
+        warnings
+    }
+}
+
+impl Extend<Rule> for Acl {
+    fn extend<I: IntoIterator<Item = Rule>>(&mut self, iter: I) {
+        self.rules.extend(iter);
+    }
+}
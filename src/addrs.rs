@@ -105,10 +105,6 @@ impl Ipv4Addr {
     pub const fn octets(&self) -> [u8; 4] {
         self.bytes
     }
-    #[cfg(feature = "nightly")]
-    pub fn parse_ascii(b: &[u8]) -> Result<Self, core::net::AddrParseError> {
-        Self::from_std(StdIpv4Addr::parse_ascii(b))
-    }
     pub const fn to_bits(self) -> u32 {
         self.to_std().to_bits()
     }
@@ -118,6 +114,10 @@ impl Ipv4Addr {
     pub const fn to_ipv6_mapped(&self) -> Ipv6Addr {
         Ipv6Addr::from_std(self.to_std().to_ipv6_mapped())
     }
+    /// Returns the number of leading bits which are the same in both addresses.
+    pub const fn common_prefix_len(self, other: Ipv4Addr) -> u8 {
+        (self.to_bits() ^ other.to_bits()).leading_zeros() as u8
+    }
 }
 
 impl Ipv6Addr {
@@ -185,16 +185,13 @@ impl Ipv6Addr {
     pub const fn multicast_scope(&self) -> Option<core::net::Ipv6MulticastScope> {
         self.to_std().multicast_scope()
     }
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(a: u16, b: u16, c: u16, d: u16, e: u16, f: u16, g: u16, h: u16) -> Self {
         Self::from_std(StdIpv6Addr::new(a, b, c, d, e, f, g, h))
     }
     pub const fn octets(&self) -> [u8; 16] {
         self.bytes
     }
-    #[cfg(feature = "nightly")]
-    pub fn parse_ascii(b: &[u8]) -> Result<Self, core::net::AddrParseError> {
-        Self::from_std(StdIpv6Addr::parse_ascii(b))
-    }
     pub const fn segments(&self) -> [u16; 8] {
         self.to_std().segments()
     }
@@ -216,6 +213,10 @@ impl Ipv6Addr {
             None => None,
         }
     }
+    /// Returns the number of leading bits which are the same in both addresses.
+    pub const fn common_prefix_len(self, other: Ipv6Addr) -> u8 {
+        (self.to_bits() ^ other.to_bits()).leading_zeros() as u8
+    }
 }
 
 impl IpAddr {
@@ -283,7 +284,7 @@ impl Ord for Ipv4Addr {
 
 impl PartialOrd for Ipv4Addr {
     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        PartialOrd::partial_cmp(&self.to_std(), &other.to_std())
+        Some(self.cmp(other))
     }
 }
 
@@ -301,7 +302,7 @@ impl Ord for Ipv6Addr {
 
 impl PartialOrd for Ipv6Addr {
     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        PartialOrd::partial_cmp(&self.to_std(), &other.to_std())
+        Some(self.cmp(other))
     }
 }
 
@@ -0,0 +1,69 @@
+use crate::ranges::CidrBlocks;
+use crate::{Ipv4Addr, Ipv6Addr, MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use alloc::vec::Vec;
+
+/// Sorts and merges overlapping or adjacent inclusive ranges in place.
+pub(crate) fn merge_ranges(ranges: &mut Vec<(u128, u128)>) {
+    ranges.sort_unstable();
+    let mut merged: Vec<(u128, u128)> = Vec::with_capacity(ranges.len());
+    for &(start, end) in ranges.iter() {
+        match merged.last_mut() {
+            Some((_, last_end)) if start <= last_end.saturating_add(1) => {
+                *last_end = (*last_end).max(end);
+            }
+            _ => merged.push((start, end)),
+        }
+    }
+    *ranges = merged;
+}
+
+pub(crate) fn v4_range(m: &MaskedIpv4) -> (u128, u128) {
+    let start = m.network_address().to_bits() as u128;
+    (start, m.broadcast_address().to_bits() as u128)
+}
+
+pub(crate) fn v6_range(m: &MaskedIpv6) -> (u128, u128) {
+    let start = m.network_address().to_bits();
+    let host_mask = u128::MAX.checked_shr(m.network_bits() as u32).unwrap_or(0);
+    (start, start | host_mask)
+}
+
+/// Returns the smallest list of networks which covers exactly the same addresses as `prefixes`.
+/// Overlapping and adjacent networks are merged. Host bits are ignored, and the result is sorted.
+pub fn aggregate_ipv4(prefixes: impl IntoIterator<Item = MaskedIpv4>) -> Vec<MaskedIpv4> {
+    let mut ranges: Vec<_> = prefixes.into_iter().map(|m| v4_range(&m)).collect();
+    merge_ranges(&mut ranges);
+    ranges
+        .into_iter()
+        .flat_map(|(start, end)| CidrBlocks::new(start, end, 32))
+        .map(|(addr, len)| MaskedIpv4::cidr(Ipv4Addr::from_bits(addr as u32), len))
+        .collect()
+}
+
+/// Returns the smallest list of networks which covers exactly the same addresses as `prefixes`.
+/// Overlapping and adjacent networks are merged. Host bits are ignored, and the result is sorted.
+pub fn aggregate_ipv6(prefixes: impl IntoIterator<Item = MaskedIpv6>) -> Vec<MaskedIpv6> {
+    let mut ranges: Vec<_> = prefixes.into_iter().map(|m| v6_range(&m)).collect();
+    merge_ranges(&mut ranges);
+    ranges
+        .into_iter()
+        .flat_map(|(start, end)| CidrBlocks::new(start, end, 128))
+        .map(|(addr, len)| MaskedIpv6::cidr(Ipv6Addr::from_bits(addr), len))
+        .collect()
+}
+
+/// Returns the smallest list of networks which covers exactly the same addresses as `prefixes`.
+/// IPv4 networks are listed before IPv6 networks. See [`aggregate_ipv4`] and [`aggregate_ipv6`].
+pub fn aggregate(prefixes: impl IntoIterator<Item = MaskedIp>) -> Vec<MaskedIp> {
+    let (mut v4, mut v6) = (Vec::new(), Vec::new());
+    for prefix in prefixes {
+        match prefix {
+            MaskedIp::V4(m) => v4.push(m),
+            MaskedIp::V6(m) => v6.push(m),
+        }
+    }
+    let v4 = aggregate_ipv4(v4).into_iter().map(MaskedIp::V4);
+    let v6 = aggregate_ipv6(v6).into_iter().map(MaskedIp::V6);
+    v4.chain(v6).collect()
+}
@@ -0,0 +1,298 @@
+use crate::{Ipv4Addr, Ipv6Addr, MaskedIpv4, MaskedIpv6};
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The strategy used by a subnet allocator to pick which free block a new subnet is carved from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub enum AllocPolicy {
+    /// Use the free block with the lowest address that is large enough.
+    #[default]
+    FirstFit,
+    /// Use the smallest free block that is large enough, preferring the lowest address among equals.
+    /// This keeps large blocks intact for as long as possible.
+    BestFit,
+}
+
+/// Error returned by [`Ipv4SubnetAllocator`] and [`Ipv6SubnetAllocator`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum AllocError {
+    /// The requested prefix length is shorter than the pool, or longer than the address.
+    InvalidLength,
+    /// There is no free block large enough to satisfy the request.
+    Exhausted,
+    /// The requested prefix is not inside the pool.
+    OutOfPool,
+    /// The requested prefix overlaps a prefix which is already allocated or reserved.
+    Unavailable,
+    /// The prefix being released was not handed out by this allocator.
+    NotAllocated,
+}
+
+impl Display for AllocError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::InvalidLength => "invalid prefix length for this pool",
+            Self::Exhausted => "no free block large enough",
+            Self::OutOfPool => "prefix is outside of the pool",
+            Self::Unavailable => "prefix overlaps an allocated prefix",
+            Self::NotAllocated => "prefix was not allocated from this pool",
+        })
+    }
+}
+
+/// Returns the mask for a prefix of `len` bits on an address `width` bits wide.
+const fn prefix_mask(width: u8, len: u8) -> u128 {
+    if len == 0 {
+        0
+    } else {
+        (u128::MAX << (128 - len as u32)) >> (128 - width as u32)
+    }
+}
+
+/// Buddy allocator shared by the v4 and v6 allocators. Addresses are stored as u128 in the
+/// low `width` bits and are always network addresses of their block.
+#[derive(Clone, Debug)]
+struct Buddy {
+    width: u8,
+    base: u128,
+    base_len: u8,
+    policy: AllocPolicy,
+    /// Free blocks, keyed by network address. Free blocks never overlap.
+    free: BTreeMap<u128, u8>,
+    /// Blocks which have been handed out or reserved, as (network address, length).
+    /// Allocated blocks never overlap, so this is also ordered by address.
+    allocated: BTreeSet<(u128, u8)>,
+}
+
+impl Buddy {
+    fn new(width: u8, base: u128, base_len: u8, policy: AllocPolicy) -> Self {
+        let base = base & prefix_mask(width, base_len);
+        let mut free = BTreeMap::new();
+        free.insert(base, base_len);
+        Self {
+            width,
+            base,
+            base_len,
+            policy,
+            free,
+            allocated: BTreeSet::new(),
+        }
+    }
+    fn contains(&self, addr: u128, len: u8) -> bool {
+        len >= self.base_len && addr & prefix_mask(self.width, self.base_len) == self.base
+    }
+    /// Splits the free block at `addr` down to `len`, keeping the half which contains `target`
+    /// and returning the other halves to the free list.
+    fn split(&mut self, addr: u128, mut block_len: u8, target: u128, len: u8) {
+        self.free.remove(&addr);
+        let mut addr = addr;
+        while block_len < len {
+            block_len += 1;
+            let half = 1u128 << (self.width - block_len);
+            if target & half == 0 {
+                self.free.insert(addr | half, block_len);
+            } else {
+                self.free.insert(addr, block_len);
+                addr |= half;
+            }
+        }
+        self.allocated.insert((addr, len));
+    }
+    fn allocate(&mut self, len: u8) -> Result<u128, AllocError> {
+        if len < self.base_len || len > self.width {
+            return Err(AllocError::InvalidLength);
+        }
+        let mut fits = self.free.iter().filter(|&(_, &block_len)| block_len <= len);
+        let found = match self.policy {
+            AllocPolicy::FirstFit => fits.next(),
+            // max_by_key returns the last maximum, so reverse to prefer the lowest address.
+            AllocPolicy::BestFit => fits.rev().max_by_key(|&(_, &block_len)| block_len),
+        };
+        let (addr, block_len) = found.map(|(&a, &l)| (a, l)).ok_or(AllocError::Exhausted)?;
+        self.split(addr, block_len, addr, len);
+        Ok(addr)
+    }
+    fn reserve(&mut self, addr: u128, len: u8) -> Result<(), AllocError> {
+        if len > self.width {
+            return Err(AllocError::InvalidLength);
+        }
+        let addr = addr & prefix_mask(self.width, len);
+        if !self.contains(addr, len) {
+            return Err(AllocError::OutOfPool);
+        }
+        let (block, block_len) = self
+            .free
+            .range(..=addr)
+            .next_back()
+            .map(|(&a, &l)| (a, l))
+            .ok_or(AllocError::Unavailable)?;
+        if block_len > len || addr & prefix_mask(self.width, block_len) != block {
+            return Err(AllocError::Unavailable);
+        }
+        self.split(block, block_len, addr, len);
+        Ok(())
+    }
+    fn release(&mut self, addr: u128, len: u8) -> Result<(), AllocError> {
+        if !self.allocated.remove(&(addr, len)) {
+            return Err(AllocError::NotAllocated);
+        }
+        let (mut addr, mut len) = (addr, len);
+        while len > self.base_len {
+            let buddy = addr ^ (1u128 << (self.width - len));
+            if self.free.get(&buddy) != Some(&len) {
+                break;
+            }
+            self.free.remove(&buddy);
+            addr &= !(1u128 << (self.width - len));
+            len -= 1;
+        }
+        self.free.insert(addr, len);
+        Ok(())
+    }
+    fn is_allocated(&self, addr: u128, len: u8) -> bool {
+        self.allocated.contains(&(addr, len))
+    }
+}
+
+/// A deterministic buddy allocator which carves aligned subnets out of a parent [`MaskedIpv4`].
+///
+/// Blocks are always split in halves, so every subnet handed out is aligned to its own size.
+/// Released subnets are merged with their buddy when the buddy is also free.
+#[derive(Clone, Debug)]
+pub struct Ipv4SubnetAllocator {
+    inner: Buddy,
+}
+
+/// A deterministic buddy allocator which carves aligned subnets out of a parent [`MaskedIpv6`].
+///
+/// Blocks are always split in halves, so every subnet handed out is aligned to its own size.
+/// Released subnets are merged with their buddy when the buddy is also free.
+#[derive(Clone, Debug)]
+pub struct Ipv6SubnetAllocator {
+    inner: Buddy,
+}
+
+impl Ipv4SubnetAllocator {
+    /// Constructs an allocator over the network of `pool` using [`AllocPolicy::FirstFit`].
+    pub fn new(pool: MaskedIpv4) -> Self {
+        Self::with_policy(pool, AllocPolicy::FirstFit)
+    }
+    /// Constructs an allocator over the network of `pool` using the provided policy.
+    pub fn with_policy(pool: MaskedIpv4, policy: AllocPolicy) -> Self {
+        let base = pool.ip.to_bits() as u128;
+        Self {
+            inner: Buddy::new(32, base, pool.mask.len(), policy),
+        }
+    }
+    /// Returns the network this allocator hands out subnets from.
+    pub fn pool(&self) -> MaskedIpv4 {
+        Self::prefix(self.inner.base, self.inner.base_len)
+    }
+    /// Returns the policy used to choose free blocks.
+    pub fn policy(&self) -> AllocPolicy {
+        self.inner.policy
+    }
+    /// Allocates a subnet with the provided mask length.
+    pub fn allocate(&mut self, len: u8) -> Result<MaskedIpv4, AllocError> {
+        let addr = self.inner.allocate(len)?;
+        Ok(Self::prefix(addr, len))
+    }
+    /// Marks the network of `prefix` as allocated. Fails if any part of it is already allocated.
+    pub fn reserve(&mut self, prefix: MaskedIpv4) -> Result<(), AllocError> {
+        self.inner
+            .reserve(prefix.ip.to_bits() as u128, prefix.mask.len())
+    }
+    /// Returns a previously allocated or reserved subnet to the pool.
+    pub fn release(&mut self, prefix: MaskedIpv4) -> Result<(), AllocError> {
+        let prefix = prefix.network();
+        self.inner
+            .release(prefix.ip.to_bits() as u128, prefix.mask.len())
+    }
+    /// Returns true if the network of `prefix` was allocated or reserved, and has not yet been released.
+    pub fn is_allocated(&self, prefix: MaskedIpv4) -> bool {
+        let prefix = prefix.network();
+        self.inner
+            .is_allocated(prefix.ip.to_bits() as u128, prefix.mask.len())
+    }
+    /// Returns all allocated and reserved subnets, ordered by address.
+    pub fn allocated(&self) -> Vec<MaskedIpv4> {
+        self.inner
+            .allocated
+            .iter()
+            .map(|&(addr, len)| Self::prefix(addr, len))
+            .collect()
+    }
+    /// Returns the free space in the pool as a list of subnets, ordered by address.
+    pub fn free_space(&self) -> Vec<MaskedIpv4> {
+        self.inner
+            .free
+            .iter()
+            .map(|(&addr, &len)| Self::prefix(addr, len))
+            .collect()
+    }
+    fn prefix(addr: u128, len: u8) -> MaskedIpv4 {
+        MaskedIpv4::cidr(Ipv4Addr::from_bits(addr as u32), len)
+    }
+}
+
+impl Ipv6SubnetAllocator {
+    /// Constructs an allocator over the network of `pool` using [`AllocPolicy::FirstFit`].
+    pub fn new(pool: MaskedIpv6) -> Self {
+        Self::with_policy(pool, AllocPolicy::FirstFit)
+    }
+    /// Constructs an allocator over the network of `pool` using the provided policy.
+    pub fn with_policy(pool: MaskedIpv6, policy: AllocPolicy) -> Self {
+        Self {
+            inner: Buddy::new(128, pool.ip.to_bits(), pool.mask.len(), policy),
+        }
+    }
+    /// Returns the network this allocator hands out subnets from.
+    pub fn pool(&self) -> MaskedIpv6 {
+        Self::prefix(self.inner.base, self.inner.base_len)
+    }
+    /// Returns the policy used to choose free blocks.
+    pub fn policy(&self) -> AllocPolicy {
+        self.inner.policy
+    }
+    /// Allocates a subnet with the provided mask length.
+    pub fn allocate(&mut self, len: u8) -> Result<MaskedIpv6, AllocError> {
+        let addr = self.inner.allocate(len)?;
+        Ok(Self::prefix(addr, len))
+    }
+    /// Marks the network of `prefix` as allocated. Fails if any part of it is already allocated.
+    pub fn reserve(&mut self, prefix: MaskedIpv6) -> Result<(), AllocError> {
+        self.inner.reserve(prefix.ip.to_bits(), prefix.mask.len())
+    }
+    /// Returns a previously allocated or reserved subnet to the pool.
+    pub fn release(&mut self, prefix: MaskedIpv6) -> Result<(), AllocError> {
+        let prefix = prefix.network();
+        self.inner.release(prefix.ip.to_bits(), prefix.mask.len())
+    }
+    /// Returns true if the network of `prefix` was allocated or reserved, and has not yet been released.
+    pub fn is_allocated(&self, prefix: MaskedIpv6) -> bool {
+        let prefix = prefix.network();
+        self.inner
+            .is_allocated(prefix.ip.to_bits(), prefix.mask.len())
+    }
+    /// Returns all allocated and reserved subnets, ordered by address.
+    pub fn allocated(&self) -> Vec<MaskedIpv6> {
+        self.inner
+            .allocated
+            .iter()
+            .map(|&(addr, len)| Self::prefix(addr, len))
+            .collect()
+    }
+    /// Returns the free space in the pool as a list of subnets, ordered by address.
+    pub fn free_space(&self) -> Vec<MaskedIpv6> {
+        self.inner
+            .free
+            .iter()
+            .map(|(&addr, &len)| Self::prefix(addr, len))
+            .collect()
+    }
+    fn prefix(addr: u128, len: u8) -> MaskedIpv6 {
+        MaskedIpv6::cidr(Ipv6Addr::from_bits(addr), len)
+    }
+}
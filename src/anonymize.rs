@@ -0,0 +1,119 @@
+use crate::{IpAddr, Ipv4Addr, Ipv4Mask, Ipv6Addr, Ipv6Mask};
+
+/// A keyed pseudo-random function over 128-bit blocks, such as AES-128 encryption.
+///
+/// This crate does not depend on any cryptography crates. Implement this trait for the block
+/// cipher of your choice to use [`CryptoPan`]. Any `Fn([u8; 16]) -> [u8; 16]` also implements it.
+pub trait BlockCipher {
+    /// Encrypts a single block.
+    fn encrypt_block(&self, block: [u8; 16]) -> [u8; 16];
+}
+
+impl<F: Fn([u8; 16]) -> [u8; 16]> BlockCipher for F {
+    fn encrypt_block(&self, block: [u8; 16]) -> [u8; 16] {
+        self(block)
+    }
+}
+
+/// A method of anonymizing IP addresses.
+pub trait Anonymizer {
+    /// Anonymizes an IPv4 address.
+    fn anonymize_ipv4(&self, ip: Ipv4Addr) -> Ipv4Addr;
+    /// Anonymizes an IPv6 address.
+    fn anonymize_ipv6(&self, ip: Ipv6Addr) -> Ipv6Addr;
+    /// Anonymizes an IP address of either family.
+    fn anonymize(&self, ip: IpAddr) -> IpAddr {
+        match ip {
+            IpAddr::V4(ip) => IpAddr::V4(self.anonymize_ipv4(ip)),
+            IpAddr::V6(ip) => IpAddr::V6(self.anonymize_ipv6(ip)),
+        }
+    }
+}
+
+/// Prefix-preserving anonymization, as described by Fan, Xu, Ammar and Moon in
+/// "Prefix-Preserving IP Address Anonymization" (Crypto-PAn).
+///
+/// Two addresses which share an n-bit prefix will share an n-bit prefix after anonymization,
+/// so subnet structure survives while the addresses themselves do not.
+///
+/// With AES-128 keyed with the first 16 bytes of a 32-byte Crypto-PAn key, and the last 16 bytes
+/// as the `secret`, this produces the same output as the reference implementation.
+#[derive(Clone, Debug)]
+pub struct CryptoPan<C> {
+    cipher: C,
+    pad: [u8; 16],
+}
+
+impl<C: BlockCipher> CryptoPan<C> {
+    /// Constructs a new anonymizer. The one-time pad is derived by encrypting `secret`.
+    pub fn new(cipher: C, secret: [u8; 16]) -> Self {
+        let pad = cipher.encrypt_block(secret);
+        Self { cipher, pad }
+    }
+    /// Returns the most significant bit of the cipher output for `block`.
+    fn prf_bit(&self, block: [u8; 16]) -> u8 {
+        self.cipher.encrypt_block(block)[0] >> 7
+    }
+}
+
+impl<C: BlockCipher> Anonymizer for CryptoPan<C> {
+    fn anonymize_ipv4(&self, ip: Ipv4Addr) -> Ipv4Addr {
+        let orig = ip.to_bits();
+        let pad = u32::from_be_bytes([self.pad[0], self.pad[1], self.pad[2], self.pad[3]]);
+        let mut otp = 0u32;
+        for pos in 0..32 {
+            // The first `pos` bits of the original address, followed by the pad.
+            let prefix = Ipv4Mask::new(pos as u8).to_bits();
+            let input = (orig & prefix) | (pad & !prefix);
+            let mut block = self.pad;
+            block[..4].copy_from_slice(&input.to_be_bytes());
+            otp |= (self.prf_bit(block) as u32) << (31 - pos);
+        }
+        Ipv4Addr::from_bits(orig ^ otp)
+    }
+    fn anonymize_ipv6(&self, ip: Ipv6Addr) -> Ipv6Addr {
+        let orig = ip.to_bits();
+        let pad = u128::from_be_bytes(self.pad);
+        let mut otp = 0u128;
+        for pos in 0..128 {
+            let prefix = Ipv6Mask::new(pos as u8).to_bits();
+            let input = (orig & prefix) | (pad & !prefix);
+            otp |= (self.prf_bit(input.to_be_bytes()) as u128) << (127 - pos);
+        }
+        Ipv6Addr::from_bits(orig ^ otp)
+    }
+}
+
+/// Anonymization by truncation, which sets all bits outside of a mask to 0. For example, a /24
+/// for IPv4 and a /48 for IPv6 are common choices for GDPR-style IP truncation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Truncate {
+    /// The mask applied to IPv4 addresses.
+    pub v4: Ipv4Mask,
+    /// The mask applied to IPv6 addresses.
+    pub v6: Ipv6Mask,
+}
+
+impl Truncate {
+    /// Constructs a truncating anonymizer which keeps the first `v4_len` bits of IPv4 addresses
+    /// and the first `v6_len` bits of IPv6 addresses.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `v4_len` > 32 or `v6_len` > 128
+    pub const fn new(v4_len: u8, v6_len: u8) -> Self {
+        Self {
+            v4: Ipv4Mask::new(v4_len),
+            v6: Ipv6Mask::new(v6_len),
+        }
+    }
+}
+
+impl Anonymizer for Truncate {
+    fn anonymize_ipv4(&self, ip: Ipv4Addr) -> Ipv4Addr {
+        ip & self.v4
+    }
+    fn anonymize_ipv6(&self, ip: Ipv6Addr) -> Ipv6Addr {
+        ip & self.v6
+    }
+}
@@ -0,0 +1,91 @@
+//! [`arbitrary`] implementations, for fuzzing. Like the [`proptest`](crate::strategy) strategies,
+//! these are biased towards special-purpose ranges and edge mask lengths.
+
+use crate::special::{EDGE_LENS_V4, EDGE_LENS_V6, SPECIAL_V4, SPECIAL_V6, host_in_v4, host_in_v6};
+use crate::{IpAddr, Ipv4Addr, Ipv4Mask, Ipv6Addr, Ipv6Mask, MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+impl<'a> Arbitrary<'a> for Ipv4Addr {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let bits = u32::arbitrary(u)?;
+        if u.ratio(1u8, 4)? {
+            Ok(host_in_v4(*u.choose(&SPECIAL_V4)?, bits))
+        } else {
+            Ok(Self::from_bits(bits))
+        }
+    }
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        let (lo, _) = u32::size_hint(depth);
+        (lo, Some(lo + 3))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Ipv6Addr {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let bits = u128::arbitrary(u)?;
+        if u.ratio(1u8, 4)? {
+            Ok(host_in_v6(*u.choose(&SPECIAL_V6)?, bits))
+        } else {
+            Ok(Self::from_bits(bits))
+        }
+    }
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        let (lo, _) = u128::size_hint(depth);
+        (lo, Some(lo + 3))
+    }
+}
+
+impl<'a> Arbitrary<'a> for IpAddr {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        if u.arbitrary()? {
+            Ok(Self::V4(u.arbitrary()?))
+        } else {
+            Ok(Self::V6(u.arbitrary()?))
+        }
+    }
+}
+
+impl<'a> Arbitrary<'a> for Ipv4Mask {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = if u.ratio(1u8, 4)? {
+            *u.choose(&EDGE_LENS_V4)?
+        } else {
+            u.int_in_range(0..=32)?
+        };
+        Ok(Self::new(len))
+    }
+}
+
+impl<'a> Arbitrary<'a> for Ipv6Mask {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let len = if u.ratio(1u8, 4)? {
+            *u.choose(&EDGE_LENS_V6)?
+        } else {
+            u.int_in_range(0..=128)?
+        };
+        Ok(Self::new(len))
+    }
+}
+
+impl<'a> Arbitrary<'a> for MaskedIpv4 {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::new(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for MaskedIpv6 {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        Ok(Self::new(u.arbitrary()?, u.arbitrary()?))
+    }
+}
+
+impl<'a> Arbitrary<'a> for MaskedIp {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        if u.arbitrary()? {
+            Ok(Self::V4(u.arbitrary()?))
+        } else {
+            Ok(Self::V6(u.arbitrary()?))
+        }
+    }
+}
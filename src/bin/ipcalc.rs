@@ -0,0 +1,339 @@
+//! An `ipcalc`-style calculator built on [`ipstuff::MaskedIp`].
+//!
+//! ```text
+//! ipcalc [--json] <network>                    describe a network
+//! ipcalc [--json] split <network> <length>     list the subnets of a network
+//! ipcalc [--json] aggregate                    aggregate networks read from stdin
+//! ipcalc [--json] contains <network> <ip>...   test whether addresses are in a network
+//! ```
+//!
+//! A network may be written in any syntax accepted by `MaskedIp::from_str` (`10.0.0.1/8` or
+//! `10.0.0.1 255.0.0.0`), as an IPv4 address and wildcard mask (`10.0.0.1 0.255.255.255`), or as
+//! a bare address, which is treated as a /32 or /128.
+
+use ipstuff::{IpAddr, Ipv4Mask, MaskedIp, MaskedIpv4, aggregate};
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: ipcalc [--json] <network>
+       ipcalc [--json] split <network> <length>
+       ipcalc [--json] aggregate < networks.txt
+       ipcalc [--json] contains <network> <ip>...";
+
+fn main() -> ExitCode {
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let json = match args.iter().position(|a| a == "--json" || a == "-j") {
+        Some(index) => {
+            args.remove(index);
+            true
+        }
+        None => false,
+    };
+    let result = match args.first().map(String::as_str) {
+        None | Some("-h" | "--help") => {
+            println!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Some("split") => split(&args[1..], json),
+        Some("aggregate") => aggregate_stdin(json),
+        Some("contains") => contains(&args[1..], json),
+        Some(_) => info(&args.join(" "), json),
+    };
+    match result {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("ipcalc: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// Parses a network in any of the supported syntaxes.
+fn parse_network(s: &str) -> Result<MaskedIp, String> {
+    let s = s.trim();
+    if let Ok(masked) = s.parse::<MaskedIp>() {
+        return Ok(masked);
+    }
+    if let Some((ip, wildcard)) = s.split_once(' ') {
+        let ip = ip.parse();
+        let wildcard = wildcard.trim().parse::<ipstuff::Ipv4Addr>();
+        if let (Ok(ip), Ok(wildcard)) = (ip, wildcard)
+            && let Some(mask) = Ipv4Mask::from_wildcard(wildcard.octets())
+        {
+            return Ok(MaskedIp::V4(MaskedIpv4::new(ip, mask)));
+        }
+    }
+    s.parse::<IpAddr>()
+        .map(MaskedIp::from)
+        .map_err(|_| format!("invalid network: {s:?}"))
+}
+
+fn parse_len(s: &str) -> Result<u8, String> {
+    s.trim_start_matches('/')
+        .parse()
+        .map_err(|_| format!("invalid prefix length: {s:?}"))
+}
+
+/// Returns the first and last usable host addresses.
+fn host_range(net: &MaskedIp) -> (IpAddr, IpAddr) {
+    match net {
+        MaskedIp::V4(m) => {
+            let (first, last) = (
+                m.network_address().to_bits(),
+                m.broadcast_address().to_bits(),
+            );
+            let (first, last) = if m.network_bits() <= 30 {
+                (first + 1, last - 1)
+            } else {
+                (first, last)
+            };
+            (IpAddr::V4(first.into()), IpAddr::V4(last.into()))
+        }
+        MaskedIp::V6(m) => {
+            let first = m.network_address().to_bits();
+            let last = first | u128::MAX.checked_shr(m.network_bits() as u32).unwrap_or(0);
+            let first = if m.network_bits() <= 126 {
+                first + 1
+            } else {
+                first
+            };
+            (IpAddr::V6(first.into()), IpAddr::V6(last.into()))
+        }
+    }
+}
+
+/// Returns the last address in the network. For IPv4 this is the broadcast address.
+fn last_address(net: &MaskedIp) -> IpAddr {
+    match net {
+        MaskedIp::V4(m) => IpAddr::V4(m.broadcast_address()),
+        MaskedIp::V6(m) => {
+            let host_mask = u128::MAX.checked_shr(m.network_bits() as u32).unwrap_or(0);
+            IpAddr::V6((m.network_address().to_bits() | host_mask).into())
+        }
+    }
+}
+
+/// Returns the number of usable host addresses as a string, since it may not fit in a u128.
+fn host_count(net: &MaskedIp) -> String {
+    match net {
+        MaskedIp::V4(m) => m.host_count_u64().to_string(),
+        MaskedIp::V6(m) if m.network_bits() == 0 => {
+            "340282366920938463463374607431768211455".into()
+        }
+        MaskedIp::V6(m) if m.network_bits() >= 127 => m.host_count().to_string(),
+        MaskedIp::V6(m) => (m.host_count() - 1).to_string(),
+    }
+}
+
+/// Describes the kind of address, from most to least specific.
+fn classify(ip: IpAddr) -> &'static str {
+    match ip {
+        IpAddr::V4(ip) => {
+            let octets = ip.octets();
+            if ip.is_unspecified() {
+                "unspecified"
+            } else if ip.is_broadcast() {
+                "limited broadcast"
+            } else if ip.is_loopback() {
+                "loopback"
+            } else if ip.is_private() {
+                "private (RFC 1918)"
+            } else if octets[0] == 100 && octets[1] & 0xC0 == 64 {
+                "shared address space (RFC 6598)"
+            } else if ip.is_link_local() {
+                "link-local"
+            } else if ip.is_documentation() {
+                "documentation"
+            } else if octets[0] == 198 && octets[1] & 0xFE == 18 {
+                "benchmarking"
+            } else if ip.is_multicast() {
+                "multicast"
+            } else if octets[0] >= 240 {
+                "reserved"
+            } else if octets[0] == 0 {
+                "this network"
+            } else {
+                "public"
+            }
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            if ip.is_unspecified() {
+                "unspecified"
+            } else if ip.is_loopback() {
+                "loopback"
+            } else if ip.to_ipv4_mapped().is_some() {
+                "IPv4-mapped"
+            } else if ip.is_multicast() {
+                "multicast"
+            } else if ip.is_unicast_link_local() {
+                "link-local"
+            } else if segments[0] & 0xFE00 == 0xFC00 {
+                "unique local"
+            } else if segments[0] == 0x2001 && segments[1] == 0xDB8 {
+                "documentation"
+            } else if segments[0] == 0x64 && segments[1] == 0xFF9B {
+                "IPv4/IPv6 translation"
+            } else if segments[0] == 0x2002 {
+                "6to4"
+            } else if segments[0] & 0xE000 == 0x2000 {
+                "global unicast"
+            } else {
+                "reserved"
+            }
+        }
+    }
+}
+
+fn mask_string(net: &MaskedIp) -> String {
+    match net {
+        MaskedIp::V4(m) => m.mask.to_string(),
+        MaskedIp::V6(m) => ipstuff::Ipv6Addr::from(m.mask.octets()).to_string(),
+    }
+}
+
+fn wildcard_string(net: &MaskedIp) -> String {
+    match net {
+        MaskedIp::V4(m) => ipstuff::Ipv4Addr::from(m.mask.wildcard()).to_string(),
+        MaskedIp::V6(m) => ipstuff::Ipv6Addr::from(!m.mask).to_string(),
+    }
+}
+
+/// Quotes a string for JSON output. Only ASCII without control characters is ever produced here.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn info(arg: &str, json: bool) -> Result<ExitCode, String> {
+    let net = parse_network(arg)?;
+    let (first, last) = host_range(&net);
+    let fields = [
+        ("address", net_ip(&net).to_string()),
+        ("network", format!("{:#}", net.network())),
+        ("netmask", mask_string(&net)),
+        ("wildcard", wildcard_string(&net)),
+        ("prefix_length", net.network_bits().to_string()),
+        (
+            match net {
+                MaskedIp::V4(_) => "broadcast",
+                MaskedIp::V6(_) => "last_address",
+            },
+            last_address(&net).to_string(),
+        ),
+        ("first_host", first.to_string()),
+        ("last_host", last.to_string()),
+        ("host_count", host_count(&net)),
+        ("reverse_zone", net.reverse_zone().to_string()),
+        ("type", classify(net_ip(&net)).to_string()),
+    ];
+    if json {
+        let mut out = String::from("{");
+        for (i, (key, value)) in fields.iter().enumerate() {
+            let value = if *key == "prefix_length" || *key == "host_count" {
+                value.clone()
+            } else {
+                quote(value)
+            };
+            let sep = if i == 0 { "" } else { "," };
+            write!(out, "{sep}{}:{value}", quote(key)).unwrap();
+        }
+        out.push('}');
+        println!("{out}");
+    } else {
+        for (key, value) in fields {
+            println!("{:<15}{value}", format!("{}:", key.replace('_', " ")));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn net_ip(net: &MaskedIp) -> IpAddr {
+    match net {
+        MaskedIp::V4(m) => IpAddr::V4(m.ip),
+        MaskedIp::V6(m) => IpAddr::V6(m.ip),
+    }
+}
+
+fn print_list(nets: impl Iterator<Item = MaskedIp>, json: bool) {
+    if json {
+        let items: Vec<_> = nets.map(|n| quote(&format!("{n:#}"))).collect();
+        println!("[{}]", items.join(","));
+    } else {
+        nets.for_each(|n| println!("{n:#}"));
+    }
+}
+
+fn split(args: &[String], json: bool) -> Result<ExitCode, String> {
+    let [network @ .., len] = args else {
+        return Err(USAGE.into());
+    };
+    if network.is_empty() {
+        return Err(USAGE.into());
+    }
+    let net = parse_network(&network.join(" "))?;
+    let len = parse_len(len)?;
+    if len < net.network_bits() || len > net.host_bits() + net.network_bits() {
+        return Err(format!("cannot split {net:#} into /{len} networks"));
+    }
+    match net {
+        MaskedIp::V4(m) => print_list(m.subnets(len).map(MaskedIp::V4), json),
+        MaskedIp::V6(m) => print_list(m.subnets(len).map(MaskedIp::V6), json),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn aggregate_stdin(json: bool) -> Result<ExitCode, String> {
+    let mut nets = Vec::new();
+    for (number, line) in io::stdin().lock().lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        let net = parse_network(line).map_err(|e| format!("line {}: {e}", number + 1))?;
+        nets.push(net);
+    }
+    print_list(aggregate(nets).into_iter(), json);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn contains(args: &[String], json: bool) -> Result<ExitCode, String> {
+    let [network, ips @ ..] = args else {
+        return Err(USAGE.into());
+    };
+    if ips.is_empty() {
+        return Err(USAGE.into());
+    }
+    let net = parse_network(network)?;
+    let mut all = true;
+    let mut results = Vec::new();
+    for ip in ips {
+        let addr = ip
+            .parse::<IpAddr>()
+            .map_err(|_| format!("invalid address: {ip:?}"))?;
+        let contained = net.contains(addr);
+        all &= contained;
+        results.push((addr, contained));
+    }
+    if json {
+        // An array in argument order, since the same address may be given more than once.
+        let items: Vec<_> = results
+            .iter()
+            .map(|(ip, c)| format!(r#"{{"ip":{},"contained":{c}}}"#, quote(&ip.to_string())))
+            .collect();
+        println!("[{}]", items.join(","));
+    } else {
+        for (ip, contained) in results {
+            let verb = if contained { "is in" } else { "is not in" };
+            println!("{ip} {verb} {net:#}");
+        }
+    }
+    Ok(if all {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
@@ -0,0 +1,93 @@
+//! Set operations over files of prefixes, built on [`ipstuff::PrefixList`].
+//!
+//! ```text
+//! prefixlist aggregate <file>...          the fewest prefixes covering every file
+//! prefixlist union <file>...              same as aggregate
+//! prefixlist intersect <a> <b>            addresses in both a and b
+//! prefixlist subtract <a> <b>             addresses in a but not b
+//! prefixlist diff <old> <new>             addresses added (+) and removed (-) by new
+//! ```
+//!
+//! Files contain one prefix per line, with `#` comments and blank lines ignored. A file name of
+//! `-` reads standard input. Each output line is annotated with the file and line of every entry
+//! which contributed to it.
+
+use ipstuff::{AnnotatedPrefix, PrefixList, difference, intersection, union};
+
+use std::io::{self, Read};
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+usage: prefixlist aggregate <file>...
+       prefixlist union <file>...
+       prefixlist intersect <a> <b>
+       prefixlist subtract <a> <b>
+       prefixlist diff <old> <new>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(code) => code,
+        Err(message) => {
+            eprintln!("prefixlist: {message}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn read_list(path: &str) -> Result<PrefixList, String> {
+    let text = if path == "-" {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| format!("<stdin>: {e}"))?;
+        text
+    } else {
+        std::fs::read_to_string(path).map_err(|e| format!("{path}: {e}"))?
+    };
+    let name = if path == "-" { "<stdin>" } else { path };
+    PrefixList::parse(name, &text).map_err(|e| e.to_string())
+}
+
+fn print(prefixes: &[AnnotatedPrefix], marker: &str) {
+    for prefix in prefixes {
+        println!("{marker}{prefix}");
+    }
+}
+
+fn run(args: &[String]) -> Result<ExitCode, String> {
+    let Some((command, paths)) = args.split_first() else {
+        println!("{USAGE}");
+        return Ok(ExitCode::SUCCESS);
+    };
+    // Check the command before reading any files, so a bad command line fails without waiting
+    // on standard input.
+    match (command.as_str(), paths.len()) {
+        ("-h" | "--help", _) => {
+            println!("{USAGE}");
+            return Ok(ExitCode::SUCCESS);
+        }
+        ("aggregate" | "union", 1..) | ("intersect" | "subtract" | "diff", 2) => {}
+        _ => return Err(USAGE.into()),
+    }
+    let lists = paths
+        .iter()
+        .map(|path| read_list(path))
+        .collect::<Result<Vec<_>, _>>()?;
+    match (command.as_str(), lists.as_slice()) {
+        ("aggregate" | "union", _) => print(&union(&lists), ""),
+        ("intersect", [a, b]) => print(&intersection(a, b), ""),
+        ("subtract", [a, b]) => print(&difference(a, b), ""),
+        ("diff", [old, new]) => {
+            let added = difference(new, old);
+            let removed = difference(old, new);
+            print(&removed, "- ");
+            print(&added, "+ ");
+            if !added.is_empty() || !removed.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        _ => return Err(USAGE.into()),
+    }
+    Ok(ExitCode::SUCCESS)
+}
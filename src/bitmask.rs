@@ -0,0 +1,343 @@
+use crate::{Ipv4Addr, Ipv4Mask, Ipv6Addr, Ipv6Mask, MaskedIpv4, MaskedIpv6};
+
+use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use core::ops::{BitAnd, Not};
+use core::str::FromStr;
+
+/// A 4-byte IPv4 mask in big-endian byte-order which may have any bit pattern, such as the
+/// non-contiguous masks used by some hardware ACLs. Use [`Ipv4Mask`] for subnet masks.
+#[repr(align(4))]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Ipv4BitMask {
+    mask: [u8; 4],
+}
+/// A 16-byte IPv6 mask in big-endian byte-order which may have any bit pattern. Use [`Ipv6Mask`]
+/// for subnet masks.
+#[repr(align(16))]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Ipv6BitMask {
+    mask: [u8; 16],
+}
+
+/// Error when converting a bit mask which is not contiguous into a subnet mask.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NonContiguousMask;
+
+impl Display for NonContiguousMask {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("mask bits are not contiguous")
+    }
+}
+
+impl Ipv4BitMask {
+    /// Constructs a bit mask from the provided bytes.
+    pub const fn from_bytes(bytes: [u8; 4]) -> Self {
+        Self { mask: bytes }
+    }
+    /// Constructs a bit mask from the provided native-endian u32.
+    pub const fn from_bits(bits: u32) -> Self {
+        Self::from_bytes(bits.to_be_bytes())
+    }
+    /// Returns the mask as an array of bytes.
+    pub const fn octets(self) -> [u8; 4] {
+        self.mask
+    }
+    /// Returns the mask as a native-endian u32.
+    pub const fn to_bits(self) -> u32 {
+        u32::from_be_bytes(self.mask)
+    }
+    /// Returns the number of 1 bits in this mask.
+    pub const fn count_ones(self) -> u8 {
+        self.to_bits().count_ones() as u8
+    }
+    /// Returns true if this mask is a valid subnet mask.
+    pub const fn is_contiguous(self) -> bool {
+        self.to_mask().is_some()
+    }
+    /// Returns this mask as a subnet mask, or None if it is not contiguous.
+    pub const fn to_mask(self) -> Option<Ipv4Mask> {
+        Ipv4Mask::from_u32(self.to_bits())
+    }
+}
+
+impl Ipv6BitMask {
+    /// Constructs a bit mask from the provided bytes.
+    pub const fn from_bytes(bytes: [u8; 16]) -> Self {
+        Self { mask: bytes }
+    }
+    /// Constructs a bit mask from the provided segments.
+    pub const fn from_segments(segments: [u16; 8]) -> Self {
+        Self::from_bytes(Ipv6Addr::from_segments(segments).octets())
+    }
+    /// Constructs a bit mask from the provided native-endian u128.
+    pub const fn from_bits(bits: u128) -> Self {
+        Self::from_bytes(bits.to_be_bytes())
+    }
+    /// Returns the mask as an array of bytes.
+    pub const fn octets(self) -> [u8; 16] {
+        self.mask
+    }
+    /// Returns the mask as an array of segments.
+    pub const fn segments(self) -> [u16; 8] {
+        Ipv6Addr::from_octets(self.mask).segments()
+    }
+    /// Returns the mask as a native-endian u128.
+    pub const fn to_bits(self) -> u128 {
+        u128::from_be_bytes(self.mask)
+    }
+    /// Returns the number of 1 bits in this mask.
+    pub const fn count_ones(self) -> u8 {
+        self.to_bits().count_ones() as u8
+    }
+    /// Returns true if this mask is a valid subnet mask.
+    pub const fn is_contiguous(self) -> bool {
+        self.to_mask().is_some()
+    }
+    /// Returns this mask as a subnet mask, or None if it is not contiguous.
+    pub const fn to_mask(self) -> Option<Ipv6Mask> {
+        Ipv6Mask::from_u128(self.to_bits())
+    }
+}
+
+impl From<Ipv4Mask> for Ipv4BitMask {
+    fn from(mask: Ipv4Mask) -> Self {
+        Self::from_bytes(mask.octets())
+    }
+}
+
+impl From<Ipv6Mask> for Ipv6BitMask {
+    fn from(mask: Ipv6Mask) -> Self {
+        Self::from_bytes(mask.octets())
+    }
+}
+
+impl TryFrom<Ipv4BitMask> for Ipv4Mask {
+    type Error = NonContiguousMask;
+    fn try_from(mask: Ipv4BitMask) -> Result<Self, NonContiguousMask> {
+        mask.to_mask().ok_or(NonContiguousMask)
+    }
+}
+
+impl TryFrom<Ipv6BitMask> for Ipv6Mask {
+    type Error = NonContiguousMask;
+    fn try_from(mask: Ipv6BitMask) -> Result<Self, NonContiguousMask> {
+        mask.to_mask().ok_or(NonContiguousMask)
+    }
+}
+
+impl Not for Ipv4BitMask {
+    type Output = Ipv4BitMask;
+    fn not(self) -> Self::Output {
+        Self::from_bits(!self.to_bits())
+    }
+}
+
+impl Not for Ipv6BitMask {
+    type Output = Ipv6BitMask;
+    fn not(self) -> Self::Output {
+        Self::from_bits(!self.to_bits())
+    }
+}
+
+impl BitAnd<Ipv4BitMask> for Ipv4Addr {
+    type Output = Ipv4Addr;
+    fn bitand(self, rhs: Ipv4BitMask) -> Self::Output {
+        Self::from_bits(self.to_bits() & rhs.to_bits())
+    }
+}
+
+impl BitAnd<Ipv6BitMask> for Ipv6Addr {
+    type Output = Ipv6Addr;
+    fn bitand(self, rhs: Ipv6BitMask) -> Self::Output {
+        Self::from_bits(self.to_bits() & rhs.to_bits())
+    }
+}
+
+impl Display for Ipv4BitMask {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(&Ipv4Addr::from_octets(self.mask), f)
+    }
+}
+
+impl Display for Ipv6BitMask {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(&Ipv6Addr::from_octets(self.mask), f)
+    }
+}
+
+impl Debug for Ipv4BitMask {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+impl Debug for Ipv6BitMask {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+/// Error when failing to parse an Ipv4BitMask.
+#[derive(Debug)]
+pub struct InvalidIpv4BitMask;
+/// Error when failing to parse an Ipv6BitMask.
+#[derive(Debug)]
+pub struct InvalidIpv6BitMask;
+
+impl FromStr for Ipv4BitMask {
+    type Err = InvalidIpv4BitMask;
+    fn from_str(s: &str) -> Result<Self, InvalidIpv4BitMask> {
+        let ip = s.parse::<Ipv4Addr>().map_err(|_| InvalidIpv4BitMask)?;
+        Ok(Self::from_bytes(ip.octets()))
+    }
+}
+
+impl FromStr for Ipv6BitMask {
+    type Err = InvalidIpv6BitMask;
+    fn from_str(s: &str) -> Result<Self, InvalidIpv6BitMask> {
+        let ip = s.parse::<Ipv6Addr>().map_err(|_| InvalidIpv6BitMask)?;
+        Ok(Self::from_bytes(ip.octets()))
+    }
+}
+
+/// An IPv4 value and bit mask pair, which matches any address equal to the value in every bit
+/// set in the mask. Unlike [`MaskedIpv4`], the mask need not be contiguous.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Ipv4Match {
+    /// The value to compare against
+    pub value: Ipv4Addr,
+    /// The bits to compare
+    pub mask: Ipv4BitMask,
+}
+/// An IPv6 value and bit mask pair, which matches any address equal to the value in every bit
+/// set in the mask. Unlike [`MaskedIpv6`], the mask need not be contiguous.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Ipv6Match {
+    /// The value to compare against
+    pub value: Ipv6Addr,
+    /// The bits to compare
+    pub mask: Ipv6BitMask,
+}
+
+impl Ipv4Match {
+    /// Constructs an Ipv4Match from the provided value and mask.
+    pub const fn new(value: Ipv4Addr, mask: Ipv4BitMask) -> Self {
+        Self { value, mask }
+    }
+    /// Returns true if `addr & mask == value & mask`.
+    pub const fn matches(&self, addr: Ipv4Addr) -> bool {
+        (self.value.to_bits() ^ addr.to_bits()) & self.mask.to_bits() == 0
+    }
+    /// Returns the number of addresses which match.
+    pub const fn match_count(&self) -> u64 {
+        1 << (32 - self.mask.count_ones())
+    }
+    /// Returns this match as a network, or None if the mask is not contiguous.
+    pub const fn to_masked(&self) -> Option<MaskedIpv4> {
+        match self.mask.to_mask() {
+            Some(mask) => Some(MaskedIpv4::new(self.value, mask)),
+            None => None,
+        }
+    }
+}
+
+impl Ipv6Match {
+    /// Constructs an Ipv6Match from the provided value and mask.
+    pub const fn new(value: Ipv6Addr, mask: Ipv6BitMask) -> Self {
+        Self { value, mask }
+    }
+    /// Returns true if `addr & mask == value & mask`.
+    pub const fn matches(&self, addr: Ipv6Addr) -> bool {
+        (self.value.to_bits() ^ addr.to_bits()) & self.mask.to_bits() == 0
+    }
+    /// Returns the number of addresses which match, or None if every address matches.
+    pub const fn match_count(&self) -> Option<u128> {
+        1u128.checked_shl(128 - self.mask.count_ones() as u32)
+    }
+    /// Returns this match as a network, or None if the mask is not contiguous.
+    pub const fn to_masked(&self) -> Option<MaskedIpv6> {
+        match self.mask.to_mask() {
+            Some(mask) => Some(MaskedIpv6::new(self.value, mask)),
+            None => None,
+        }
+    }
+}
+
+impl From<MaskedIpv4> for Ipv4Match {
+    fn from(masked: MaskedIpv4) -> Self {
+        Self::new(masked.ip, masked.mask.into())
+    }
+}
+
+impl From<MaskedIpv6> for Ipv6Match {
+    fn from(masked: MaskedIpv6) -> Self {
+        Self::new(masked.ip, masked.mask.into())
+    }
+}
+
+impl TryFrom<Ipv4Match> for MaskedIpv4 {
+    type Error = NonContiguousMask;
+    fn try_from(m: Ipv4Match) -> Result<Self, NonContiguousMask> {
+        m.to_masked().ok_or(NonContiguousMask)
+    }
+}
+
+impl TryFrom<Ipv6Match> for MaskedIpv6 {
+    type Error = NonContiguousMask;
+    fn try_from(m: Ipv6Match) -> Result<Self, NonContiguousMask> {
+        m.to_masked().ok_or(NonContiguousMask)
+    }
+}
+
+impl Display for Ipv4Match {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} {}", self.value, self.mask)
+    }
+}
+
+impl Display for Ipv6Match {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} {}", self.value, self.mask)
+    }
+}
+
+impl Debug for Ipv4Match {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+impl Debug for Ipv6Match {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+/// Error when failing to parse an Ipv4Match.
+#[derive(Debug)]
+pub struct InvalidIpv4Match;
+/// Error when failing to parse an Ipv6Match.
+#[derive(Debug)]
+pub struct InvalidIpv6Match;
+
+impl FromStr for Ipv4Match {
+    type Err = InvalidIpv4Match;
+    /// Parses a value and mask separated by exactly one space, such as `10.0.0.1 255.0.0.255`.
+    fn from_str(s: &str) -> Result<Self, InvalidIpv4Match> {
+        let (value, mask) = s.split_once(' ').ok_or(InvalidIpv4Match)?;
+        let value = value.parse().map_err(|_| InvalidIpv4Match)?;
+        let mask = mask.parse().map_err(|_| InvalidIpv4Match)?;
+        Ok(Self::new(value, mask))
+    }
+}
+
+impl FromStr for Ipv6Match {
+    type Err = InvalidIpv6Match;
+    /// Parses a value and mask separated by exactly one space, such as `2001:db8::1 ffff::ffff`.
+    fn from_str(s: &str) -> Result<Self, InvalidIpv6Match> {
+        let (value, mask) = s.split_once(' ').ok_or(InvalidIpv6Match)?;
+        let value = value.parse().map_err(|_| InvalidIpv6Match)?;
+        let mask = mask.parse().map_err(|_| InvalidIpv6Match)?;
+        Ok(Self::new(value, mask))
+    }
+}
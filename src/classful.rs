@@ -0,0 +1,141 @@
+use crate::parse;
+use crate::{Ipv4Addr, Ipv4Mask, MaskedIpv4};
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The legacy class of an IPv4 address, as determined by its leading bits (RFC 791).
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Ipv4Class {
+    /// `0.0.0.0` - `127.255.255.255`, with a default mask of /8.
+    A,
+    /// `128.0.0.0` - `191.255.255.255`, with a default mask of /16.
+    B,
+    /// `192.0.0.0` - `223.255.255.255`, with a default mask of /24.
+    C,
+    /// `224.0.0.0` - `239.255.255.255`, multicast. There is no default mask.
+    D,
+    /// `240.0.0.0` - `255.255.255.255`, reserved. There is no default mask.
+    E,
+}
+
+impl Ipv4Class {
+    /// Returns the classful default mask of this class, or None for classes D and E.
+    pub const fn default_mask(self) -> Option<Ipv4Mask> {
+        match self {
+            Self::A => Some(Ipv4Mask::new(8)),
+            Self::B => Some(Ipv4Mask::new(16)),
+            Self::C => Some(Ipv4Mask::new(24)),
+            Self::D | Self::E => None,
+        }
+    }
+}
+
+impl Display for Ipv4Class {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::A => "class A",
+            Self::B => "class B",
+            Self::C => "class C",
+            Self::D => "class D",
+            Self::E => "class E",
+        })
+    }
+}
+
+/// A classful routing hazard of a prefix. Returned by [`MaskedIpv4::classful_warning`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ClassfulWarning {
+    /// The mask is longer than the classful default. Classful protocols such as RIPv1 will only
+    /// see the containing classful network.
+    Subnet {
+        /// The classful network which contains the prefix.
+        classful: MaskedIpv4,
+    },
+    /// The mask is shorter than the classful default, so the prefix covers several classful
+    /// networks.
+    Supernet {
+        /// The classful network of the prefix's network address.
+        classful: MaskedIpv4,
+    },
+    /// The prefix contains addresses of more than one class.
+    CrossesClasses {
+        /// The class of the prefix's first address.
+        first: Ipv4Class,
+        /// The class of the prefix's last address.
+        last: Ipv4Class,
+    },
+}
+
+impl Display for ClassfulWarning {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Subnet { classful } => write!(f, "subnet of classful network {classful:#}"),
+            Self::Supernet { classful } => {
+                write!(f, "supernet of classful network {classful:#}")
+            }
+            Self::CrossesClasses { first, last } => {
+                write!(f, "crosses class boundary from {first} to {last}")
+            }
+        }
+    }
+}
+
+impl Ipv4Addr {
+    /// Returns the legacy class of this address.
+    pub const fn class(&self) -> Ipv4Class {
+        match self.octets()[0].leading_ones() {
+            0 => Ipv4Class::A,
+            1 => Ipv4Class::B,
+            2 => Ipv4Class::C,
+            3 => Ipv4Class::D,
+            _ => Ipv4Class::E,
+        }
+    }
+}
+
+impl Ipv4Mask {
+    /// Returns the classful default mask of `addr`, or None if it is class D or E.
+    pub const fn classful_default(addr: Ipv4Addr) -> Option<Self> {
+        addr.class().default_mask()
+    }
+}
+
+impl MaskedIpv4 {
+    /// Constructs a MaskedIpv4 with the classful default mask of `addr`, or None if it is class D or E.
+    pub const fn from_classful(addr: Ipv4Addr) -> Option<Self> {
+        match Ipv4Mask::classful_default(addr) {
+            Some(mask) => Some(Self::new(addr, mask)),
+            None => None,
+        }
+    }
+    /// Parses the same syntax as `FromStr`, but also accepts a bare address, which gets its
+    /// classful default mask. Returns None if the string is invalid, or is a bare class D or E address.
+    pub const fn from_classful_str(s: &str) -> Option<Self> {
+        let b = s.as_bytes();
+        match parse::ipv4(b, 0, b.len()) {
+            Some(addr) => Self::from_classful(addr),
+            None => parse::masked_ipv4(b, 0, b.len()),
+        }
+    }
+    /// Returns a warning if this prefix does not line up with classful boundaries, or None if it
+    /// is exactly a classful network. Prefixes within class D or E never have warnings.
+    pub const fn classful_warning(&self) -> Option<ClassfulWarning> {
+        let first = self.network_address().class();
+        let last = self.broadcast_address().class();
+        if first as u8 != last as u8 {
+            return Some(ClassfulWarning::CrossesClasses { first, last });
+        }
+        let Some(classful) = Self::from_classful(self.network_address()) else {
+            return None;
+        };
+        let classful = classful.network();
+        let (len, classful_len) = (self.network_bits(), classful.network_bits());
+        if len > classful_len {
+            Some(ClassfulWarning::Subnet { classful })
+        } else if len < classful_len {
+            Some(ClassfulWarning::Supernet { classful })
+        } else {
+            None
+        }
+    }
+}
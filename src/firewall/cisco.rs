@@ -0,0 +1,162 @@
+//! Cisco IOS access list entries, such as
+//! `access-list 101 permit tcp 10.0.0.0 0.255.255.255 host 192.0.2.1 eq 22`.
+//!
+//! IPv4 addresses are `any`, `host ADDR`, or an address and a wildcard mask. IPv6 addresses are
+//! `any`, `host ADDR` or a CIDR network. Object groups, `object-group NAME`, are read as named
+//! sets. Standard access lists have only a source address, which may be a bare address. Cisco
+//! has no address negation.
+
+use super::{
+    AddressSpec, EmitError, FirewallError, FirewallErrorKind, RuleAddresses, Ruleset, next_token,
+    parse_network, parse_wildcard, with_lines,
+};
+
+use crate::{IpAddr, Ipv4Addr, MaskedIp};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+
+/// Returns the index of the token after `permit` or `deny`, skipping any `access-list NAME`
+/// prefix, `extended` or `standard` keyword, and sequence number. Returns None for remarks and
+/// lines which are not entries.
+fn entry_start(tokens: &[&str]) -> Option<usize> {
+    let mut i = 0;
+    if tokens.first() == Some(&"access-list") {
+        i += 2;
+        if matches!(tokens.get(i), Some(&("extended" | "standard"))) {
+            i += 1;
+        }
+    }
+    if tokens.get(i)?.bytes().all(|b| b.is_ascii_digit()) {
+        i += 1;
+    }
+    matches!(*tokens.get(i)?, "permit" | "deny").then_some(i + 1)
+}
+
+/// Parses an address, returning it and the number of tokens used. A bare IPv4 address without a
+/// wildcard is only allowed when `bare_host` is true.
+fn parse_spec(
+    tokens: &[&str],
+    after: &str,
+    bare_host: bool,
+) -> Result<(AddressSpec, usize), FirewallError> {
+    let value = next_token(tokens, after)?;
+    Ok(match value {
+        "any" => (AddressSpec::Any, 1),
+        "host" => {
+            let host = next_token(&tokens[1..], value)?;
+            let ip = host
+                .parse::<IpAddr>()
+                .map_err(|_| FirewallError::new(FirewallErrorKind::InvalidAddress, host))?;
+            (AddressSpec::network(ip), 2)
+        }
+        "object-group" => {
+            let name = next_token(&tokens[1..], value)?.to_string();
+            let negated = false;
+            (AddressSpec::Set { negated, name }, 2)
+        }
+        value if value.contains(':') => (
+            AddressSpec::Networks {
+                negated: false,
+                networks: alloc::vec![parse_network(value)?],
+            },
+            1,
+        ),
+        value => match tokens.get(1).filter(|t| t.parse::<Ipv4Addr>().is_ok()) {
+            Some(wildcard) => (AddressSpec::network(parse_wildcard(value, wildcard)?), 2),
+            None if bare_host => (AddressSpec::network(parse_network(value)?), 1),
+            None => return Err(FirewallError::new(FirewallErrorKind::MissingAddress, value)),
+        },
+    })
+}
+
+/// Returns the number of tokens used by a port condition, such as `eq 22` or `range 1 1023`.
+fn port_tokens(tokens: &[&str]) -> usize {
+    match tokens.first() {
+        Some(&("eq" | "neq" | "lt" | "gt")) => 2,
+        Some(&"range") => 3,
+        _ => 0,
+    }
+}
+
+/// Parses the address specifications of one access list entry, with or without an
+/// `access-list NAME` prefix. Returns the default of [`AddressSpec::Any`] for both sides if the
+/// line is a remark or not an entry.
+///
+/// An entry is standard if an address directly follows `permit` or `deny`, in which case only
+/// the source is set. Otherwise a protocol is expected first.
+pub fn parse_rule(line: &str) -> Result<RuleAddresses, FirewallError> {
+    let tokens: alloc::vec::Vec<&str> = line.split_whitespace().collect();
+    let mut rule = RuleAddresses::default();
+    let Some(mut i) = entry_start(&tokens) else {
+        return Ok(rule);
+    };
+    let first = next_token(&tokens[i..], tokens[i - 1])?;
+    let standard = matches!(first, "any" | "host") || first.parse::<IpAddr>().is_ok();
+    if standard {
+        rule.src = parse_spec(&tokens[i..], first, true)?.0;
+        return Ok(rule);
+    }
+    i += 1;
+    let (src, used) = parse_spec(&tokens[i..], first, false)?;
+    i += used;
+    // A truncated port condition leaves the destination missing, rather than out of bounds.
+    i = (i + port_tokens(&tokens[i..])).min(tokens.len());
+    let (dst, _) = parse_spec(&tokens[i..], tokens[i - 1], false)?;
+    rule.src = src;
+    rule.dst = dst;
+    Ok(rule)
+}
+
+/// Parses the address specifications of every `permit` and `deny` entry in a configuration.
+/// Numbered `access-list` lines and entries within named access lists are both read. Remarks,
+/// comments and other configuration are skipped, and no sets are returned.
+pub fn parse_ruleset(text: &str) -> Result<Ruleset, FirewallError> {
+    let mut ruleset = Ruleset::default();
+    with_lines(text, |line| {
+        let tokens: alloc::vec::Vec<&str> = line.split_whitespace().collect();
+        if entry_start(&tokens).is_some() {
+            ruleset.rules.push(parse_rule(line)?);
+        }
+        Ok(())
+    })?;
+    Ok(ruleset)
+}
+
+fn format_spec(spec: &AddressSpec) -> Result<String, EmitError> {
+    let networks = match spec {
+        AddressSpec::Any => return Ok("any".to_string()),
+        AddressSpec::Set {
+            negated: false,
+            name,
+        } => return Ok(format!("object-group {name}")),
+        AddressSpec::Networks {
+            negated: false,
+            networks,
+        } => networks,
+        _ => return Err(EmitError::Negation),
+    };
+    let [network] = networks.as_slice() else {
+        return Err(EmitError::List);
+    };
+    Ok(match network {
+        MaskedIp::V4(n) if n.network_bits() == 32 => format!("host {}", n.ip),
+        MaskedIp::V4(n) => format!("{} {}", n.ip, Ipv4Addr::from_bits(!n.mask.to_bits())),
+        MaskedIp::V6(n) if n.network_bits() == 128 => format!("host {}", n.ip),
+        MaskedIp::V6(n) => format!("{n}"),
+    })
+}
+
+/// Writes the source and destination of an extended entry, such as
+/// `10.0.0.0 0.255.255.255 host 192.0.2.1`.
+///
+/// Negation fails with [`EmitError::Negation`], and more than one network on a side fails with
+/// [`EmitError::List`].
+pub fn format_addresses(rule: &RuleAddresses) -> Result<String, EmitError> {
+    rule.is_ipv6()?;
+    Ok(format!(
+        "{} {}",
+        format_spec(&rule.src)?,
+        format_spec(&rule.dst)?
+    ))
+}
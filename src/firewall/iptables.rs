@@ -0,0 +1,110 @@
+//! `iptables-save` and `ip6tables-save` rules, such as
+//! `-A INPUT -s 10.0.0.0/8 ! -d 192.0.2.1/32 -p tcp -j ACCEPT`.
+//!
+//! Addresses are given by `-s`/`--source` and `-d`/`--destination`, which may be negated with a
+//! `!` before the option, or after it in the older syntax. A value may be a bare address, a CIDR
+//! network, a network with a dotted mask, or a comma separated list of those. ipset matches,
+//! `-m set --match-set NAME src`, are read as named sets.
+
+use super::{
+    AddressSpec, EmitError, FirewallError, RuleAddresses, Ruleset, join, next_token, parse_network,
+    with_lines,
+};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Parses the address specifications of one rule. Options other than addresses are skipped, and
+/// a side without an address is [`AddressSpec::Any`].
+pub fn parse_rule(line: &str) -> Result<RuleAddresses, FirewallError> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let mut rule = RuleAddresses::default();
+    let mut negated = false;
+    let mut i = 0;
+    while i < tokens.len() {
+        let token = tokens[i];
+        i += 1;
+        let (side, spec) = match token {
+            "!" => {
+                negated = true;
+                continue;
+            }
+            "-s" | "--source" | "--src" | "-d" | "--destination" | "--dst" => {
+                if tokens.get(i) == Some(&"!") {
+                    negated = true;
+                    i += 1;
+                }
+                let value = next_token(&tokens[i..], token)?;
+                i += 1;
+                let networks = value
+                    .split(',')
+                    .map(parse_network)
+                    .collect::<Result<_, _>>()?;
+                let side = if token.starts_with("-s") || token.starts_with("--s") {
+                    &mut rule.src
+                } else {
+                    &mut rule.dst
+                };
+                (side, AddressSpec::Networks { negated, networks })
+            }
+            "--match-set" => {
+                let name = next_token(&tokens[i..], token)?;
+                let flags = next_token(&tokens[i + 1..], name)?;
+                i += 2;
+                let side = if flags.starts_with("dst") {
+                    &mut rule.dst
+                } else {
+                    &mut rule.src
+                };
+                let name = name.to_string();
+                (side, AddressSpec::Set { negated, name })
+            }
+            _ => {
+                negated = false;
+                continue;
+            }
+        };
+        *side = spec;
+        negated = false;
+    }
+    Ok(rule)
+}
+
+/// Parses the address specifications of every `-A` and `-I` rule in the output of
+/// `iptables-save` or `ip6tables-save`. Tables, chain policies and comments are skipped, and
+/// no sets are returned.
+pub fn parse_ruleset(text: &str) -> Result<Ruleset, FirewallError> {
+    let mut ruleset = Ruleset::default();
+    with_lines(text, |line| {
+        if matches!(line.split_whitespace().next(), Some("-A" | "-I")) {
+            ruleset.rules.push(parse_rule(line)?);
+        }
+        Ok(())
+    })?;
+    Ok(ruleset)
+}
+
+fn format_spec(out: &mut Vec<String>, spec: &AddressSpec, option: &str, flag: &str) {
+    match spec {
+        AddressSpec::Any => {}
+        AddressSpec::Networks { negated, networks } => {
+            let not = if *negated { "! " } else { "" };
+            out.push(format!("{not}{option} {}", join(networks, ",", false)));
+        }
+        AddressSpec::Set { negated, name } => {
+            let not = if *negated { "! " } else { "" };
+            out.push(format!("-m set {not}--match-set {name} {flag}"));
+        }
+    }
+}
+
+/// Writes the address options of a rule, such as `-s 10.0.0.0/8 ! -d 192.0.2.1/32`. Returns an
+/// empty string if both sides are [`AddressSpec::Any`].
+pub fn format_addresses(rule: &RuleAddresses) -> Result<String, EmitError> {
+    rule.is_ipv6()?;
+    let mut out = Vec::new();
+    format_spec(&mut out, &rule.src, "-s", "src");
+    format_spec(&mut out, &rule.dst, "-d", "dst");
+    Ok(out.join(" "))
+}
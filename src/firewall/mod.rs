@@ -0,0 +1,338 @@
+//! Address specifications of firewall rules in common text formats.
+//!
+//! Each submodule parses one rule syntax into [`RuleAddresses`] and emits them back out in the
+//! same syntax. Only the source and destination addresses of a rule are extracted. Everything
+//! else, such as ports, interfaces and actions, is skipped.
+
+pub mod cisco;
+pub mod iptables;
+pub mod nftables;
+pub mod pf;
+
+use crate::ranges::{CidrBlocks, to_ip};
+use crate::{IpAddr, Ipv4Addr, Ipv4Mask, MaskedIp, MaskedIpv4};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The addresses a rule matches on one side, source or destination.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum AddressSpec {
+    /// Any address. This is also what a rule without an address matches.
+    Any,
+    /// Addresses within any of the networks, or outside all of them when negated.
+    Networks {
+        /// True if the rule matches addresses outside every network.
+        negated: bool,
+        /// The networks, in the order they were written.
+        networks: Vec<MaskedIp>,
+    },
+    /// Addresses within a named set, such as an nftables set, pf table, ipset or Cisco object
+    /// group, or outside it when negated.
+    Set {
+        /// True if the rule matches addresses outside the set.
+        negated: bool,
+        /// The name of the set, without any sigil such as `@` or `<>`.
+        name: String,
+    },
+}
+
+impl AddressSpec {
+    /// Constructs a specification of a single network.
+    pub fn network(network: impl Into<MaskedIp>) -> Self {
+        Self::Networks {
+            negated: false,
+            networks: alloc::vec![network.into()],
+        }
+    }
+    /// Returns true if this specification is negated.
+    pub fn is_negated(&self) -> bool {
+        match self {
+            Self::Any => false,
+            Self::Networks { negated, .. } | Self::Set { negated, .. } => *negated,
+        }
+    }
+    /// Returns true if this is an IPv6 specification, false for IPv4, or None if it has no
+    /// addresses to tell.
+    fn is_ipv6(&self) -> Result<Option<bool>, EmitError> {
+        match self {
+            Self::Networks { networks, .. } => is_ipv6(networks),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Returns true if every network is IPv6, false if every network is IPv4, or None if there are
+/// no networks.
+fn is_ipv6(networks: &[MaskedIp]) -> Result<Option<bool>, EmitError> {
+    let mut family = None;
+    for network in networks {
+        let v6 = matches!(network, MaskedIp::V6(_));
+        if family.is_some_and(|f| f != v6) {
+            return Err(EmitError::MixedFamilies);
+        }
+        family = Some(v6);
+    }
+    Ok(family)
+}
+
+/// The source and destination address specifications of one rule.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RuleAddresses {
+    /// The source addresses
+    pub src: AddressSpec,
+    /// The destination addresses
+    pub dst: AddressSpec,
+}
+
+impl RuleAddresses {
+    /// Returns true if this is an IPv6 rule, false for IPv4, or None if neither side has
+    /// addresses to tell.
+    fn is_ipv6(&self) -> Result<Option<bool>, EmitError> {
+        match (self.src.is_ipv6()?, self.dst.is_ipv6()?) {
+            (Some(a), Some(b)) if a != b => Err(EmitError::MixedFamilies),
+            (a, b) => Ok(a.or(b)),
+        }
+    }
+}
+
+impl Default for RuleAddresses {
+    fn default() -> Self {
+        Self {
+            src: AddressSpec::Any,
+            dst: AddressSpec::Any,
+        }
+    }
+}
+
+/// A named list of networks, such as an nftables set or pf table.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NamedSet {
+    /// The name of the set, without any sigil such as `@` or `<>`.
+    pub name: String,
+    /// The networks, in the order they were written. Address ranges are split into networks.
+    pub networks: Vec<MaskedIp>,
+}
+
+/// The rules and sets of a whole configuration.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct Ruleset {
+    /// The address specifications of each rule, in order.
+    pub rules: Vec<RuleAddresses>,
+    /// The sets defined by the configuration, in order.
+    pub sets: Vec<NamedSet>,
+}
+
+/// The kind of a [`FirewallError`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum FirewallErrorKind {
+    /// The token is not a valid address, network or address range.
+    InvalidAddress,
+    /// The token is a wildcard or subnet mask whose bits are not contiguous.
+    NonContiguousMask,
+    /// The token should have been followed by an address.
+    MissingAddress,
+    /// The token is valid in the format, but cannot be represented, such as `! any`.
+    Unsupported,
+}
+
+impl Display for FirewallErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::InvalidAddress => "invalid address",
+            Self::NonContiguousMask => "non-contiguous mask",
+            Self::MissingAddress => "missing address after",
+            Self::Unsupported => "unsupported syntax",
+        })
+    }
+}
+
+/// Error when failing to parse a firewall rule or configuration.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FirewallError {
+    /// What went wrong
+    pub kind: FirewallErrorKind,
+    /// The line number of the error, starting at 1
+    pub line: usize,
+    /// The token which caused the error
+    pub token: String,
+}
+
+impl FirewallError {
+    fn new(kind: FirewallErrorKind, token: &str) -> Self {
+        Self {
+            kind,
+            line: 1,
+            token: token.to_string(),
+        }
+    }
+}
+
+impl Display for FirewallError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "line {}: {} `{}`", self.line, self.kind, self.token)
+    }
+}
+
+/// Error when an address specification cannot be written in a format.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum EmitError {
+    /// The format cannot negate this specification.
+    Negation,
+    /// The format cannot list several networks in one rule.
+    List,
+    /// The format cannot reference named sets.
+    Set,
+    /// The rule or set has both IPv4 and IPv6 networks.
+    MixedFamilies,
+    /// The format needs an address family, but the rule only references sets or any address.
+    UnknownFamily,
+}
+
+impl Display for EmitError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::Negation => "negated addresses are not supported",
+            Self::List => "lists of networks are not supported",
+            Self::Set => "named sets are not supported",
+            Self::MixedFamilies => "cannot mix IPv4 and IPv6 networks",
+            Self::UnknownFamily => "cannot tell whether the rule is IPv4 or IPv6",
+        })
+    }
+}
+
+/// Parses a bare address as a host network, a CIDR network, or an IPv4 address with a dotted
+/// subnet mask such as `10.0.0.0/255.0.0.0`.
+fn parse_network(token: &str) -> Result<MaskedIp, FirewallError> {
+    if let Ok(ip) = token.parse::<IpAddr>() {
+        return Ok(MaskedIp::from(ip));
+    }
+    if let Some(net) = MaskedIp::from_cidr_str(token) {
+        return Ok(net);
+    }
+    let invalid = || FirewallError::new(FirewallErrorKind::InvalidAddress, token);
+    let (ip, mask) = token.split_once('/').ok_or_else(invalid)?;
+    let ip = ip.parse::<Ipv4Addr>().map_err(|_| invalid())?;
+    let mask = mask.parse::<Ipv4Addr>().map_err(|_| invalid())?;
+    let mask = Ipv4Mask::from_u32(mask.to_bits())
+        .ok_or_else(|| FirewallError::new(FirewallErrorKind::NonContiguousMask, token))?;
+    Ok(MaskedIp::V4(MaskedIpv4::new(ip, mask)))
+}
+
+/// Parses an IPv4 address and a Cisco-style wildcard mask, such as `10.0.0.0 0.255.255.255`.
+fn parse_wildcard(ip: &str, wildcard: &str) -> Result<MaskedIpv4, FirewallError> {
+    let ip = ip
+        .parse::<Ipv4Addr>()
+        .map_err(|_| FirewallError::new(FirewallErrorKind::InvalidAddress, ip))?;
+    let bits = wildcard
+        .parse::<Ipv4Addr>()
+        .map_err(|_| FirewallError::new(FirewallErrorKind::InvalidAddress, wildcard))?;
+    let mask = Ipv4Mask::from_wildcard(bits.octets())
+        .ok_or_else(|| FirewallError::new(FirewallErrorKind::NonContiguousMask, wildcard))?;
+    Ok(MaskedIpv4::new(ip, mask))
+}
+
+/// Parses a network as [`parse_network`], or an inclusive address range such as
+/// `10.0.0.1-10.0.0.9`, which is split into the networks covering it.
+fn parse_element(token: &str, out: &mut Vec<MaskedIp>) -> Result<(), FirewallError> {
+    let Some((first, last)) = token.split_once('-') else {
+        out.push(parse_network(token)?);
+        return Ok(());
+    };
+    let invalid = || FirewallError::new(FirewallErrorKind::InvalidAddress, token);
+    let (start, end, width) = match (first.parse().map_err(|_| invalid())?, last.parse()) {
+        (IpAddr::V4(a), Ok(IpAddr::V4(b))) => (a.to_bits() as u128, b.to_bits() as u128, 32),
+        (IpAddr::V6(a), Ok(IpAddr::V6(b))) => (a.to_bits(), b.to_bits(), 128),
+        _ => return Err(invalid()),
+    };
+    if start > end {
+        return Err(invalid());
+    }
+    out.extend(CidrBlocks::new(start, end, width).map(|block| to_ip(block, width)));
+    Ok(())
+}
+
+/// Splits a line into whitespace separated tokens, where each of `{`, `}`, `,` and `;` is also a
+/// token of its own.
+fn tokenize(line: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    for word in line.split_whitespace() {
+        let mut rest = word;
+        while let Some(i) = rest.find(['{', '}', ',', ';']) {
+            if i > 0 {
+                tokens.push(&rest[..i]);
+            }
+            tokens.push(&rest[i..i + 1]);
+            rest = &rest[i + 1..];
+        }
+        if !rest.is_empty() {
+            tokens.push(rest);
+        }
+    }
+    tokens
+}
+
+/// Parses the elements of a `{ a, b, ... }` list which starts at `tokens[0]`, returning them and
+/// the number of tokens used. Fails with [`FirewallErrorKind::MissingAddress`] naming `after` if
+/// the list does not start with `{`.
+fn parse_braced(tokens: &[&str], after: &str) -> Result<(Vec<MaskedIp>, usize), FirewallError> {
+    if tokens.first() != Some(&"{") {
+        return Err(FirewallError::new(FirewallErrorKind::MissingAddress, after));
+    }
+    let mut networks = Vec::new();
+    for (i, token) in tokens.iter().enumerate().skip(1) {
+        match *token {
+            "}" => return Ok((networks, i + 1)),
+            "," => {}
+            token => parse_element(token, &mut networks)?,
+        }
+    }
+    Err(FirewallError::new(
+        FirewallErrorKind::MissingAddress,
+        tokens[0],
+    ))
+}
+
+/// Returns the first token, or a [`FirewallErrorKind::MissingAddress`] error naming `after`.
+fn next_token<'a>(tokens: &[&'a str], after: &str) -> Result<&'a str, FirewallError> {
+    tokens
+        .first()
+        .copied()
+        .ok_or_else(|| FirewallError::new(FirewallErrorKind::MissingAddress, after))
+}
+
+/// Writes networks separated by `sep`, in CIDR notation. Single addresses are written without a
+/// prefix length if `bare_hosts` is true.
+fn join(networks: &[MaskedIp], sep: &str, bare_hosts: bool) -> String {
+    let mut out = String::new();
+    for (i, network) in networks.iter().enumerate() {
+        if i > 0 {
+            out.push_str(sep);
+        }
+        out.push_str(&format_network(network, bare_hosts));
+    }
+    out
+}
+
+/// Writes a network in CIDR notation, or without a prefix length if it is a single address and
+/// `bare_host` is true.
+fn format_network(network: &MaskedIp, bare_host: bool) -> String {
+    match network {
+        MaskedIp::V4(n) if bare_host && n.network_bits() == 32 => n.ip.to_string(),
+        MaskedIp::V6(n) if bare_host && n.network_bits() == 128 => n.ip.to_string(),
+        network => format!("{network:#}"),
+    }
+}
+
+/// Runs `parse` on every line of `text`, setting the line number of any error.
+fn with_lines(
+    text: &str,
+    mut parse: impl FnMut(&str) -> Result<(), FirewallError>,
+) -> Result<(), FirewallError> {
+    for (i, line) in text.lines().enumerate() {
+        parse(line).map_err(|e| FirewallError { line: i + 1, ..e })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,216 @@
+//! nftables rules and sets, such as `ip saddr != { 10.0.0.0/8, 192.0.2.1 } tcp dport 22 accept`.
+//!
+//! Addresses are given by `ip saddr`, `ip daddr`, `ip6 saddr` and `ip6 daddr`, which may be
+//! negated with `!=`. A value may be an address, a CIDR network, an address range such as
+//! `10.0.0.1-10.0.0.9`, an anonymous set in braces, or a named set such as `@blocked`.
+
+use super::{
+    AddressSpec, EmitError, FirewallError, NamedSet, RuleAddresses, Ruleset, format_network,
+    is_ipv6, join, next_token, parse_braced, parse_element, tokenize,
+};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Parses the address specifications of rule tokens.
+fn parse_tokens(tokens: &[&str]) -> Result<RuleAddresses, FirewallError> {
+    let mut rule = RuleAddresses::default();
+    let mut i = 0;
+    while i + 1 < tokens.len() {
+        let side = match (tokens[i], tokens[i + 1]) {
+            ("ip" | "ip6", "saddr") => &mut rule.src,
+            ("ip" | "ip6", "daddr") => &mut rule.dst,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        let key = tokens[i + 1];
+        i += 2;
+        let mut negated = false;
+        match tokens.get(i) {
+            Some(&"!=") => {
+                negated = true;
+                i += 1;
+            }
+            Some(&"==") => i += 1,
+            _ => {}
+        }
+        let value = next_token(&tokens[i..], key)?;
+        *side = if value == "{" {
+            let (networks, used) = parse_braced(&tokens[i..], key)?;
+            i += used;
+            AddressSpec::Networks { negated, networks }
+        } else if let Some(name) = value.strip_prefix('@') {
+            i += 1;
+            let name = name.to_string();
+            AddressSpec::Set { negated, name }
+        } else {
+            i += 1;
+            let mut networks = Vec::new();
+            parse_element(value, &mut networks)?;
+            AddressSpec::Networks { negated, networks }
+        };
+    }
+    Ok(rule)
+}
+
+/// Parses the address specifications of one rule. Statements other than addresses are skipped,
+/// and a side without an address is [`AddressSpec::Any`].
+pub fn parse_rule(line: &str) -> Result<RuleAddresses, FirewallError> {
+    let line = line.split('#').next().unwrap_or_default();
+    parse_tokens(&tokenize(line))
+}
+
+/// Parses the rules and sets of a ruleset, as written by `nft list ruleset`.
+///
+/// Rules are the statements of `chain` blocks, along with `add rule` and `insert rule` commands.
+/// Sets are `set` blocks and `add element` commands. Maps and other objects are skipped.
+pub fn parse_ruleset(text: &str) -> Result<Ruleset, FirewallError> {
+    let mut ruleset = Ruleset::default();
+    // The keyword of each open block, such as `table` or `chain`.
+    let mut blocks: Vec<&str> = Vec::new();
+    let mut statement: Vec<&str> = Vec::new();
+    let mut statement_line = 0;
+    // Braces opened within the current statement, such as an anonymous set.
+    let mut depth = 0;
+    for (i, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default();
+        // A line break ends a statement like `;` does.
+        for token in tokenize(line).into_iter().chain([";"]) {
+            if statement.is_empty() {
+                statement_line = i + 1;
+            }
+            match token {
+                "{" if depth == 0 && is_block(&statement) => {
+                    if statement[0] == "set" {
+                        let name = statement.get(1).copied().unwrap_or_default().to_string();
+                        let networks = Vec::new();
+                        ruleset.sets.push(NamedSet { name, networks });
+                    }
+                    blocks.push(statement[0]);
+                    statement.clear();
+                }
+                "}" if depth == 0 => {
+                    finish(&mut ruleset, blocks.last(), &statement)
+                        .map_err(|e| FirewallError { line: i + 1, ..e })?;
+                    statement.clear();
+                    blocks.pop();
+                }
+                ";" if depth == 0 => {
+                    finish(&mut ruleset, blocks.last(), &statement).map_err(|e| FirewallError {
+                        line: statement_line,
+                        ..e
+                    })?;
+                    statement.clear();
+                }
+                ";" => {}
+                token => {
+                    match token {
+                        "{" => depth += 1,
+                        "}" => depth -= 1,
+                        _ => {}
+                    }
+                    statement.push(token);
+                }
+            }
+        }
+    }
+    Ok(ruleset)
+}
+
+/// Returns true if the statement is the header of a block, such as `chain input`.
+fn is_block(statement: &[&str]) -> bool {
+    matches!(
+        statement.first(),
+        Some(&("table" | "chain" | "set" | "map" | "flowtable"))
+    )
+}
+
+/// Adds a complete statement to the ruleset, given the keyword of the block it is in.
+fn finish(
+    ruleset: &mut Ruleset,
+    block: Option<&&str>,
+    statement: &[&str],
+) -> Result<(), FirewallError> {
+    match (block, statement) {
+        (_, []) => {}
+        (Some(&"chain"), ["type" | "policy" | "comment" | "devices", ..]) => {}
+        (Some(&"chain"), _) => ruleset.rules.push(parse_tokens(statement)?),
+        (Some(&"set"), ["elements", "=", rest @ ..]) => {
+            let (networks, _) = parse_braced(rest, "=")?;
+            if let Some(set) = ruleset.sets.last_mut() {
+                set.networks.extend(networks);
+            }
+        }
+        (None | Some(&"table"), ["add" | "insert", "rule", ..]) => {
+            ruleset.rules.push(parse_tokens(statement)?);
+        }
+        (None | Some(&"table"), ["add", "element", _family, _table, name, rest @ ..]) => {
+            let (networks, _) = parse_braced(rest, name)?;
+            match ruleset.sets.iter_mut().find(|set| set.name == *name) {
+                Some(set) => set.networks.extend(networks),
+                None => ruleset.sets.push(NamedSet {
+                    name: name.to_string(),
+                    networks,
+                }),
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn format_spec(spec: &AddressSpec, key: &str, ipv6: Option<bool>) -> Result<String, EmitError> {
+    let family = |ipv6: Option<bool>| match ipv6 {
+        Some(true) => Ok("ip6"),
+        Some(false) => Ok("ip"),
+        None => Err(EmitError::UnknownFamily),
+    };
+    let not = |negated: bool| if negated { "!= " } else { "" };
+    Ok(match spec {
+        AddressSpec::Any => String::new(),
+        AddressSpec::Networks { negated, networks } => {
+            let family = family(spec.is_ipv6()?)?;
+            let not = not(*negated);
+            match networks.as_slice() {
+                [network] => format!("{family} {key} {not}{}", format_network(network, true)),
+                networks => format!("{family} {key} {not}{{ {} }}", join(networks, ", ", true)),
+            }
+        }
+        AddressSpec::Set { negated, name } => {
+            format!("{} {key} {}@{name}", family(ipv6)?, not(*negated))
+        }
+    })
+}
+
+/// Writes the address statements of a rule, such as `ip saddr 10.0.0.0/8 ip daddr != @blocked`.
+/// Returns an empty string if both sides are [`AddressSpec::Any`].
+///
+/// A named set is written with the family of the other side, and fails with
+/// [`EmitError::UnknownFamily`] if the other side has no networks.
+pub fn format_addresses(rule: &RuleAddresses) -> Result<String, EmitError> {
+    let ipv6 = rule.is_ipv6()?;
+    let src = format_spec(&rule.src, "saddr", ipv6)?;
+    let dst = format_spec(&rule.dst, "daddr", ipv6)?;
+    Ok(match (src.is_empty(), dst.is_empty()) {
+        (false, false) => format!("{src} {dst}"),
+        _ => src + &dst,
+    })
+}
+
+/// Writes a set block with the `interval` flag, so it may hold networks as well as addresses.
+/// Fails with [`EmitError::UnknownFamily`] if the set is empty.
+pub fn format_set(set: &NamedSet) -> Result<String, EmitError> {
+    let kind = match is_ipv6(&set.networks)? {
+        Some(true) => "ipv6_addr",
+        Some(false) => "ipv4_addr",
+        None => return Err(EmitError::UnknownFamily),
+    };
+    Ok(format!(
+        "set {} {{\n\ttype {kind}\n\tflags interval\n\telements = {{ {} }}\n}}",
+        set.name,
+        join(&set.networks, ", ", true)
+    ))
+}
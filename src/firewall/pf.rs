@@ -0,0 +1,187 @@
+//! pf rules and tables, such as `pass in proto tcp from ! 10.0.0.0/8 to <servers> port 22`.
+//!
+//! Addresses follow `from` and `to`, and may be negated with `!`. An address may be `any`, an
+//! address, a CIDR network, an address range such as `10.0.0.1 - 10.0.0.9`, a list in braces,
+//! or a table such as `<servers>`. `all` is the same as `from any to any`.
+
+use super::{
+    AddressSpec, EmitError, FirewallError, FirewallErrorKind, NamedSet, RuleAddresses, Ruleset,
+    format_network, join, next_token, parse_braced, parse_element, tokenize, with_lines,
+};
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Returns an error if any element of a list is negated, which cannot be represented.
+fn check_list(tokens: &[&str]) -> Result<(), FirewallError> {
+    let end = tokens
+        .iter()
+        .position(|t| *t == "}")
+        .unwrap_or(tokens.len());
+    match tokens[..end].iter().find(|t| t.starts_with('!')) {
+        Some(token) => Err(FirewallError::new(FirewallErrorKind::Unsupported, token)),
+        None => Ok(()),
+    }
+}
+
+/// Parses the address after `from` or `to`, returning it and the number of tokens used.
+fn parse_spec(tokens: &[&str], key: &str) -> Result<(AddressSpec, usize), FirewallError> {
+    let mut value = next_token(tokens, key)?;
+    let mut used = 1;
+    let mut negated = false;
+    if value == "!" {
+        negated = true;
+        value = next_token(&tokens[1..], "!")?;
+        used += 1;
+    } else if let Some(rest) = value.strip_prefix('!') {
+        negated = true;
+        value = rest;
+    }
+    let tokens = &tokens[used - 1..];
+    let spec = match value {
+        "any" if negated => {
+            return Err(FirewallError::new(FirewallErrorKind::Unsupported, "! any"));
+        }
+        "any" => AddressSpec::Any,
+        "{" if negated => return Err(FirewallError::new(FirewallErrorKind::Unsupported, "! {")),
+        "{" => {
+            check_list(tokens)?;
+            let (networks, list_used) = parse_braced(tokens, key)?;
+            used += list_used - 1;
+            AddressSpec::Networks { negated, networks }
+        }
+        value if value.starts_with('<') && value.ends_with('>') => AddressSpec::Set {
+            negated,
+            name: value[1..value.len() - 1].to_string(),
+        },
+        value if value.starts_with('$') || value.starts_with('(') || value == "self" => {
+            return Err(FirewallError::new(FirewallErrorKind::Unsupported, value));
+        }
+        value => {
+            let mut networks = Vec::new();
+            if tokens.get(1) == Some(&"-") {
+                let last = next_token(&tokens[2..], "-")?;
+                parse_element(&format!("{value}-{last}"), &mut networks)?;
+                used += 2;
+            } else {
+                parse_element(value, &mut networks)?;
+            }
+            AddressSpec::Networks { negated, networks }
+        }
+    };
+    Ok((spec, used))
+}
+
+/// Parses the address specifications of one rule. Everything other than `from` and `to` is
+/// skipped, and a side without an address is [`AddressSpec::Any`].
+///
+/// Macros such as `$lan`, interface addresses such as `(em0)` and `self` cannot be resolved, so
+/// they fail with [`FirewallErrorKind::Unsupported`]. So do negated lists and negated elements
+/// within a list, since pf negates each element rather than the list as a whole.
+pub fn parse_rule(line: &str) -> Result<RuleAddresses, FirewallError> {
+    let line = line.split('#').next().unwrap_or_default();
+    let tokens = tokenize(line);
+    let mut rule = RuleAddresses::default();
+    let mut i = 0;
+    while i < tokens.len() {
+        let key = tokens[i];
+        i += 1;
+        let side = match key {
+            "from" => &mut rule.src,
+            "to" => &mut rule.dst,
+            _ => continue,
+        };
+        let (spec, used) = parse_spec(&tokens[i..], key)?;
+        *side = spec;
+        i += used;
+    }
+    Ok(rule)
+}
+
+/// Parses the rules and tables of a `pf.conf` file.
+///
+/// Rules are lines starting with `pass`, `block` or `match`. Tables are `table` lines, where a
+/// table loaded from a file has no networks. Lines ending with `\` continue on the next line.
+pub fn parse_ruleset(text: &str) -> Result<Ruleset, FirewallError> {
+    let mut ruleset = Ruleset::default();
+    let mut pending = String::new();
+    with_lines(text, |line| {
+        if let Some(line) = line.strip_suffix('\\') {
+            pending.push_str(line);
+            pending.push(' ');
+            return Ok(());
+        }
+        pending.push_str(line);
+        let line = core::mem::take(&mut pending);
+        match line.split_whitespace().next() {
+            Some("pass" | "block" | "match") => ruleset.rules.push(parse_rule(&line)?),
+            Some("table") => ruleset.sets.push(parse_table(&line)?),
+            _ => {}
+        }
+        Ok(())
+    })?;
+    Ok(ruleset)
+}
+
+/// Parses a table definition such as `table <servers> persist { 192.0.2.1, 10.0.0.0/8 }`.
+/// Tables may hold both IPv4 and IPv6 networks. Negated entries fail with [`FirewallErrorKind::Unsupported`].
+pub fn parse_table(line: &str) -> Result<NamedSet, FirewallError> {
+    let line = line.split('#').next().unwrap_or_default();
+    let tokens = tokenize(line);
+    let name = next_token(tokens.get(1..).unwrap_or_default(), "table")?;
+    let name = name
+        .strip_prefix('<')
+        .and_then(|name| name.strip_suffix('>'))
+        .ok_or_else(|| FirewallError::new(FirewallErrorKind::Unsupported, name))?;
+    let mut networks = Vec::new();
+    if let Some(start) = tokens.iter().position(|t| *t == "{") {
+        check_list(&tokens[start..])?;
+        networks = parse_braced(&tokens[start..], "table")?.0;
+    }
+    Ok(NamedSet {
+        name: name.to_string(),
+        networks,
+    })
+}
+
+fn format_spec(spec: &AddressSpec) -> Result<String, EmitError> {
+    Ok(match spec {
+        AddressSpec::Any => "any".to_string(),
+        AddressSpec::Networks { negated, networks } => match (negated, networks.as_slice()) {
+            (false, [network]) => format_network(network, true),
+            (true, [network]) => format!("! {}", format_network(network, true)),
+            (false, networks) => format!("{{ {} }}", join(networks, ", ", true)),
+            (true, _) => return Err(EmitError::Negation),
+        },
+        AddressSpec::Set {
+            negated: false,
+            name,
+        } => format!("<{name}>"),
+        AddressSpec::Set {
+            negated: true,
+            name,
+        } => format!("! <{name}>"),
+    })
+}
+
+/// Writes the addresses of a rule, such as `from ! 10.0.0.0/8 to <servers>`.
+///
+/// A negated list fails with [`EmitError::Negation`], since pf negates each element of a list
+/// rather than the list as a whole.
+pub fn format_addresses(rule: &RuleAddresses) -> Result<String, EmitError> {
+    Ok(format!(
+        "from {} to {}",
+        format_spec(&rule.src)?,
+        format_spec(&rule.dst)?
+    ))
+}
+
+/// Writes a table definition such as `table <servers> { 192.0.2.1, 10.0.0.0/8 }`.
+pub fn format_table(set: &NamedSet) -> String {
+    format!(
+        "table <{}> {{ {} }}",
+        set.name,
+        join(&set.networks, ", ", true)
+    )
+}
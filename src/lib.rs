@@ -5,11 +5,106 @@
 #![no_std]
 #![forbid(unsafe_code)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod macros;
+
+#[cfg(feature = "alloc")]
+mod acl;
+#[cfg(feature = "alloc")]
+pub use acl::*;
+
 mod addrs;
 pub use addrs::*;
 
+mod anonymize;
+pub use anonymize::*;
+
+mod bitmask;
+pub use bitmask::*;
+
+mod classful;
+pub use classful::*;
+
 mod masked;
 pub use masked::*;
 
+mod parse;
+
+mod ranges;
+pub use ranges::*;
+
+#[cfg(feature = "alloc")]
+mod aggregate;
+#[cfg(feature = "alloc")]
+pub use aggregate::*;
+
+#[cfg(feature = "alloc")]
+mod allocator;
+#[cfg(feature = "alloc")]
+pub use allocator::*;
+
+#[cfg(feature = "bytemuck")]
+mod prefix_db;
+#[cfg(feature = "bytemuck")]
+pub use prefix_db::*;
+
+#[cfg(feature = "alloc")]
+mod prefix_list;
+#[cfg(feature = "alloc")]
+pub use prefix_list::*;
+
+#[cfg(feature = "alloc")]
+pub mod firewall;
+
+#[cfg(feature = "alloc")]
+mod matcher;
+#[cfg(feature = "alloc")]
+pub use matcher::*;
+
+#[cfg(feature = "mmdb")]
+mod mmdb;
+#[cfg(feature = "mmdb")]
+pub use mmdb::*;
+
+mod network;
+pub use network::*;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+
+#[cfg(feature = "alloc")]
+mod pool;
+#[cfg(feature = "alloc")]
+pub use pool::*;
+
+mod proc_net;
+pub use proc_net::*;
+
+mod random;
+pub use random::*;
+
+mod relation;
+pub use relation::*;
+
+mod rir;
+pub use rir::*;
+
+mod rtnetlink;
+pub use rtnetlink::*;
+
+#[cfg(feature = "serde")]
+mod serde_impls;
+
+#[cfg(any(feature = "arbitrary", feature = "proptest"))]
+mod special;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary_impls;
+
+#[cfg(feature = "proptest")]
+pub mod strategy;
+
 #[cfg(test)]
 mod tests;
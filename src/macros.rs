@@ -0,0 +1,134 @@
+/// Expands a literal to an [`Ipv4Addr`](crate::Ipv4Addr) at compile time. An invalid literal is a
+/// compile error. The result may be used in `const` and `static` items.
+///
+/// ```
+/// use ipstuff::{Ipv4Addr, ipv4};
+/// const GATEWAY: Ipv4Addr = ipv4!("192.168.1.1");
+/// assert_eq!(GATEWAY, Ipv4Addr::new(192, 168, 1, 1));
+/// ```
+///
+/// ```compile_fail
+/// const BAD: ipstuff::Ipv4Addr = ipstuff::ipv4!("192.168.1.256");
+/// ```
+#[macro_export]
+macro_rules! ipv4 {
+    ($s:literal) => {{
+        const PARSED: $crate::Ipv4Addr = match $crate::Ipv4Addr::from_str_const($s) {
+            ::core::option::Option::Some(ip) => ip,
+            ::core::option::Option::None => ::core::panic!(concat!("invalid IPv4 address: ", $s)),
+        };
+        PARSED
+    }};
+}
+
+/// Expands a literal to an [`Ipv6Addr`](crate::Ipv6Addr) at compile time. An invalid literal is a
+/// compile error. The result may be used in `const` and `static` items.
+///
+/// ```
+/// use ipstuff::{Ipv6Addr, ipv6};
+/// const DNS: Ipv6Addr = ipv6!("2001:4860:4860::8888");
+/// assert_eq!(DNS.segments(), [0x2001, 0x4860, 0x4860, 0, 0, 0, 0, 0x8888]);
+/// ```
+///
+/// ```compile_fail
+/// const BAD: ipstuff::Ipv6Addr = ipstuff::ipv6!("2001::db8::1");
+/// ```
+#[macro_export]
+macro_rules! ipv6 {
+    ($s:literal) => {{
+        const PARSED: $crate::Ipv6Addr = match $crate::Ipv6Addr::from_str_const($s) {
+            ::core::option::Option::Some(ip) => ip,
+            ::core::option::Option::None => ::core::panic!(concat!("invalid IPv6 address: ", $s)),
+        };
+        PARSED
+    }};
+}
+
+/// Expands a literal to an [`IpAddr`](crate::IpAddr) at compile time. An invalid literal is a
+/// compile error. The result may be used in `const` and `static` items.
+///
+/// ```
+/// use ipstuff::{IpAddr, ip};
+/// static RESOLVERS: [IpAddr; 2] = [ip!("1.1.1.1"), ip!("2606:4700:4700::1111")];
+/// assert!(matches!(RESOLVERS[1], IpAddr::V6(_)));
+/// ```
+#[macro_export]
+macro_rules! ip {
+    ($s:literal) => {{
+        const PARSED: $crate::IpAddr = match $crate::IpAddr::from_str_const($s) {
+            ::core::option::Option::Some(ip) => ip,
+            ::core::option::Option::None => ::core::panic!(concat!("invalid IP address: ", $s)),
+        };
+        PARSED
+    }};
+}
+
+/// Expands a literal to a [`MaskedIpv4`](crate::MaskedIpv4) at compile time. Both `ip/len` and
+/// `ip mask` are accepted. An invalid literal is a compile error. The result may be used in
+/// `const` and `static` items.
+///
+/// ```
+/// use ipstuff::{MaskedIpv4, cidr4};
+/// static PRIVATE: [MaskedIpv4; 3] = [
+///     cidr4!("10.0.0.0/8"),
+///     cidr4!("172.16.0.0 255.240.0.0"),
+///     cidr4!("192.168.0.0/16"),
+/// ];
+/// assert_eq!(PRIVATE[1].network_bits(), 12);
+/// ```
+///
+/// ```compile_fail
+/// const BAD: ipstuff::MaskedIpv4 = ipstuff::cidr4!("10.0.0.0 255.0.255.0");
+/// ```
+#[macro_export]
+macro_rules! cidr4 {
+    ($s:literal) => {{
+        const PARSED: $crate::MaskedIpv4 = match $crate::MaskedIpv4::from_str_const($s) {
+            ::core::option::Option::Some(m) => m,
+            ::core::option::Option::None => ::core::panic!(concat!("invalid IPv4 prefix: ", $s)),
+        };
+        PARSED
+    }};
+}
+
+/// Expands a literal to a [`MaskedIpv6`](crate::MaskedIpv6) at compile time. An invalid literal
+/// is a compile error. The result may be used in `const` and `static` items.
+///
+/// ```
+/// use ipstuff::{MaskedIpv6, cidr6};
+/// const ULA: MaskedIpv6 = cidr6!("fc00::/7");
+/// assert!(ULA.contains("fd12::1".parse().unwrap()));
+/// ```
+///
+/// ```compile_fail
+/// const BAD: ipstuff::MaskedIpv6 = ipstuff::cidr6!("fc00::/129");
+/// ```
+#[macro_export]
+macro_rules! cidr6 {
+    ($s:literal) => {{
+        const PARSED: $crate::MaskedIpv6 = match $crate::MaskedIpv6::from_str_const($s) {
+            ::core::option::Option::Some(m) => m,
+            ::core::option::Option::None => ::core::panic!(concat!("invalid IPv6 prefix: ", $s)),
+        };
+        PARSED
+    }};
+}
+
+/// Expands a literal to a [`MaskedIp`](crate::MaskedIp) at compile time. An invalid literal is a
+/// compile error. The result may be used in `const` and `static` items.
+///
+/// ```
+/// use ipstuff::{MaskedIp, cidr};
+/// static BOGONS: [MaskedIp; 2] = [cidr!("0.0.0.0/8"), cidr!("::/128")];
+/// assert_eq!(BOGONS[0].network_bits(), 8);
+/// ```
+#[macro_export]
+macro_rules! cidr {
+    ($s:literal) => {{
+        const PARSED: $crate::MaskedIp = match $crate::MaskedIp::from_str_const($s) {
+            ::core::option::Option::Some(m) => m,
+            ::core::option::Option::None => ::core::panic!(concat!("invalid IP prefix: ", $s)),
+        };
+        PARSED
+    }};
+}
@@ -1,5 +1,6 @@
 use crate::{IpAddr, Ipv4Addr, Ipv6Addr};
 
+use core::cmp::Ordering;
 use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
 use core::ops::Not;
 use core::str::FromStr;
@@ -37,6 +38,7 @@ impl Ipv4Mask {
     ///
     /// Will panic if provided length is > 32
     pub const fn new(len: u8) -> Self {
+        assert!(len <= 32, "IPv4 mask length must be <= 32");
         #[rustfmt::skip]
         const MASKS: [[u8; 4]; 33] = [
             // /0
@@ -58,11 +60,22 @@ impl Ipv4Mask {
         Self { mask }
     }
     /// Constructs a subnet mask from the provided bytes, if they represent a valid mask.
-    pub fn from_bytes(bytes: [u8; 4]) -> Option<Self> {
+    pub const fn from_bytes(bytes: [u8; 4]) -> Option<Self> {
         Self::from_u32(u32::from_be_bytes(bytes))
     }
+    /// Constructs a subnet mask from the provided bytes. In a const context, an invalid mask is a compile error.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the bytes do not represent a valid mask
+    pub const fn from_octets(octets: [u8; 4]) -> Self {
+        match Self::from_bytes(octets) {
+            Some(mask) => mask,
+            None => panic!("IPv4 mask bits must be contiguous"),
+        }
+    }
     /// Constructs a subnet mask from the provided u32, if it represents a valid mask.
-    pub fn from_u32(x: u32) -> Option<Self> {
+    pub const fn from_u32(x: u32) -> Option<Self> {
         let ones = if cfg!(target_feature = "popcnt") {
             x.count_ones() as u8 // popcnt;
         } else {
@@ -97,8 +110,16 @@ impl Ipv4Mask {
     pub const fn to_bits(self) -> u32 {
         u32::from_be_bytes(self.mask)
     }
-    pub fn from_bits(bits: u32) -> Option<Self> {
-        Self::from_bytes(bits.to_be_bytes())
+    pub const fn from_bits(bits: u32) -> Option<Self> {
+        Self::from_u32(bits)
+    }
+    /// Constructs a subnet mask from the provided wildcard (inverse) mask, such as `0.0.0.255`, if it represents a valid mask.
+    pub const fn from_wildcard(bytes: [u8; 4]) -> Option<Self> {
+        Self::from_u32(!u32::from_be_bytes(bytes))
+    }
+    /// Returns the wildcard (inverse) mask as an array of bytes, such as `0.0.0.255` for a /24.
+    pub const fn wildcard(self) -> [u8; 4] {
+        (!self.to_bits()).to_be_bytes()
     }
 }
 #[allow(clippy::len_without_is_empty)]
@@ -109,6 +130,7 @@ impl Ipv6Mask {
     ///
     /// Will panic if provided length is > 128
     pub const fn new(len: u8) -> Self {
+        assert!(len <= 128, "IPv6 mask length must be <= 128");
         #[rustfmt::skip]
         const MASKS: [u128; 129] = [
 			0x0,
@@ -126,15 +148,26 @@ impl Ipv6Mask {
         Self { mask }
     }
     /// Constructs a subnet mask from the provided bytes, if they represent a valid mask.
-    pub fn from_bytes(bytes: [u8; 16]) -> Option<Self> {
+    pub const fn from_bytes(bytes: [u8; 16]) -> Option<Self> {
         Self::from_u128(u128::from_be_bytes(bytes))
     }
+    /// Constructs a subnet mask from the provided bytes. In a const context, an invalid mask is a compile error.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the bytes do not represent a valid mask
+    pub const fn from_octets(octets: [u8; 16]) -> Self {
+        match Self::from_bytes(octets) {
+            Some(mask) => mask,
+            None => panic!("IPv6 mask bits must be contiguous"),
+        }
+    }
     /// Constructs a subnet mask from the provided segments, if they represent a valid mask.
-    pub fn from_segments(segments: [u16; 8]) -> Option<Self> {
-        Self::from_bytes(Ipv6Addr::from(segments).octets())
+    pub const fn from_segments(segments: [u16; 8]) -> Option<Self> {
+        Self::from_bytes(Ipv6Addr::from_segments(segments).octets())
     }
     /// Constructs a subnet mask from the provided u128, if it represents a valid mask.
-    pub fn from_u128(x: u128) -> Option<Self> {
+    pub const fn from_u128(x: u128) -> Option<Self> {
         let ones = if cfg!(target_feature = "popcnt") {
             x.count_ones() as u8
         } else {
@@ -153,8 +186,8 @@ impl Ipv6Mask {
         self.mask
     }
     /// Returns the subnet mask as an array of segments.
-    pub fn segments(self) -> [u16; 8] {
-        Ipv6Addr::from(self.mask).segments()
+    pub const fn segments(self) -> [u16; 8] {
+        Ipv6Addr::from_octets(self.mask).segments()
     }
     /// Returns th subnet mask as a native-endian u128.
     pub const fn as_u128(self) -> u128 {
@@ -169,9 +202,12 @@ impl Ipv6Mask {
         let len = (!x).leading_zeros() as u8;
         len
     }
-    pub fn to_bits(self) -> u128 {
+    pub const fn to_bits(self) -> u128 {
         u128::from_be_bytes(self.octets())
     }
+    pub const fn from_bits(bits: u128) -> Option<Self> {
+        Self::from_u128(bits)
+    }
 }
 
 impl Display for Ipv4Mask {
@@ -222,8 +258,8 @@ impl Not for Ipv6Mask {
 impl FromStr for Ipv4Mask {
     type Err = InvalidIpv4Mask;
     fn from_str(s: &str) -> Result<Self, InvalidIpv4Mask> {
-        if s.starts_with('/') {
-            match s[1..].parse::<u8>() {
+        if let Some(len) = s.strip_prefix('/') {
+            match len.parse::<u8>() {
                 Ok(len @ 0..=32) => Ok(Ipv4Mask::new(len)),
                 _ => Err(InvalidIpv4Mask),
             }
@@ -237,8 +273,8 @@ impl FromStr for Ipv4Mask {
 impl FromStr for Ipv6Mask {
     type Err = InvalidIpv6Mask;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.starts_with('/') {
-            match s[1..].parse::<u8>() {
+        if let Some(len) = s.strip_prefix('/') {
+            match len.parse::<u8>() {
                 Ok(len @ 0..=128) => Ok(Ipv6Mask::new(len)),
                 _ => Err(InvalidIpv6Mask),
             }
@@ -322,31 +358,31 @@ impl MaskedIpv4 {
     //     format!("{}", self)
     // }
     /// Returns the network adderss by setting all host bits to 0.
-    pub fn network_address(&self) -> Ipv4Addr {
-        self.ip & self.mask
+    pub const fn network_address(&self) -> Ipv4Addr {
+        Ipv4Addr::from_bits(self.ip.to_bits() & self.mask.to_bits())
     }
     /// Constructs a new MaskedIpv4 using the network address and mask of this MaskedIpv4.
-    pub fn network(&self) -> MaskedIpv4 {
+    pub const fn network(&self) -> MaskedIpv4 {
         Self::new(self.network_address(), self.mask)
     }
     /// Returns true if all host bits in the IP are 0. Always returns false if the mask length is 31 or 32.
-    pub fn is_network_address(&self) -> bool {
-        self.mask.len() <= 30 && self.ip == self.network_address()
+    pub const fn is_network_address(&self) -> bool {
+        self.mask.len() <= 30 && self.ip.to_bits() == self.network_address().to_bits()
     }
     /// Returns the broadcast address by setting all host bits to 1.
-    pub fn broadcast_address(&self) -> Ipv4Addr {
-        self.ip | !self.mask
+    pub const fn broadcast_address(&self) -> Ipv4Addr {
+        Ipv4Addr::from_bits(self.ip.to_bits() | !self.mask.to_bits())
     }
     /// Returns true if all host bits in the IP are 1. Always returns false if the mask length is 31 or 32.
-    pub fn is_broadcast_address(&self) -> bool {
-        self.mask.len() <= 30 && self.ip == self.broadcast_address()
+    pub const fn is_broadcast_address(&self) -> bool {
+        self.mask.len() <= 30 && self.ip.to_bits() == self.broadcast_address().to_bits()
     }
     /// Returns the number of network bits. That is, the length of the mask.
-    pub fn network_bits(&self) -> u8 {
+    pub const fn network_bits(&self) -> u8 {
         self.mask.len()
     }
     /// Returns the number of host bits. That is, the number of 0 bits in the mask.
-    pub fn host_bits(&self) -> u8 {
+    pub const fn host_bits(&self) -> u8 {
         32 - self.network_bits()
     }
     /// Returns the number of host addresses in the network.
@@ -354,21 +390,21 @@ impl MaskedIpv4 {
     /// # Panics
     ///
     /// Will panic if usize is not large enough to hold the host count.
-    pub fn host_count(&self) -> usize {
+    pub const fn host_count(&self) -> usize {
         let host_bits = self.host_bits();
         match host_bits {
             0 => 1,
             1 => 2,
-            _ => 2usize.checked_shl(host_bits as u32).unwrap() - 2,
+            _ => 1usize.checked_shl(host_bits as u32).unwrap() - 2,
         }
     }
     /// Returns the number of host addresses in the network as u64. Unlike host_count, this will never panic.
-    pub fn host_count_u64(&self) -> u64 {
+    pub const fn host_count_u64(&self) -> u64 {
         let host_bits = self.host_bits();
         match host_bits {
             0 => 1,
             1 => 2,
-            _ => (2 << host_bits) - 2,
+            _ => (1 << host_bits) - 2,
         }
     }
     /// Returns the number of networks of the provided mask length will fit in this network.
@@ -376,7 +412,7 @@ impl MaskedIpv4 {
     /// # Panics
     ///
     /// Will panic if the provided length is > 32, or if the number of networks does not fit in usize
-    pub fn network_count(&self, new_len: u8) -> usize {
+    pub const fn network_count(&self, new_len: u8) -> usize {
         let curr_len = self.mask.len();
         if new_len < curr_len {
             0
@@ -384,7 +420,7 @@ impl MaskedIpv4 {
             panic!("Invalid mask length > 32")
         } else {
             let borrowed_bits = new_len - curr_len;
-            2usize.checked_shl(borrowed_bits as u32).unwrap()
+            1usize.checked_shl(borrowed_bits as u32).unwrap()
         }
     }
     /// Returns the number of networks of the provided mask length will fit in this network as u64. Unlike network_count, this will not panic
@@ -393,7 +429,7 @@ impl MaskedIpv4 {
     /// # Panics
     ///
     /// Will panic if the provided length is > 32
-    pub fn network_count_u64(&self, new_len: u8) -> u64 {
+    pub const fn network_count_u64(&self, new_len: u8) -> u64 {
         let curr_len = self.mask.len();
         if new_len < curr_len {
             0
@@ -401,12 +437,19 @@ impl MaskedIpv4 {
             panic!("Invalid mask length > 32")
         } else {
             let borrowed_bits = new_len - curr_len;
-            2 << borrowed_bits
+            1 << borrowed_bits
         }
     }
     /// Returns true if this network contains the provided IP address, even if the provided IP is the network or broadcast address.
-    pub fn contains(&self, ip: Ipv4Addr) -> bool {
-        self.ip & self.mask == ip & self.mask
+    pub const fn contains(&self, ip: Ipv4Addr) -> bool {
+        (self.ip.to_bits() ^ ip.to_bits()) & self.mask.to_bits() == 0
+    }
+    /// Returns the `in-addr.arpa` reverse DNS zone of this network, rounded down to an octet boundary.
+    pub const fn reverse_zone(&self) -> ReverseZone {
+        ReverseZone {
+            ip: IpAddr::V4(self.network_address()),
+            labels: self.network_bits() / 8,
+        }
     }
 }
 
@@ -443,23 +486,23 @@ impl MaskedIpv6 {
     ///
     /// Note: Ipv6 does not technically have a "network" address, however this method can still
     /// be useful to determine the base address of a network.
-    pub fn network_address(&self) -> Ipv6Addr {
-        self.ip & self.mask
+    pub const fn network_address(&self) -> Ipv6Addr {
+        Ipv6Addr::from_bits(self.ip.to_bits() & self.mask.to_bits())
     }
     /// Constructs a new MaskedIpv6 using the network address and mask of this MaskedIpv6.
-    pub fn network(&self) -> MaskedIpv6 {
+    pub const fn network(&self) -> MaskedIpv6 {
         Self::new(self.network_address(), self.mask)
     }
     /// Returns true if all host bits in the IP are 0. Always returns false if the mask len is 127 or 128.
-    pub fn is_network_address(&self) -> bool {
-        self.mask.len() <= 126 && self.ip == self.network_address()
+    pub const fn is_network_address(&self) -> bool {
+        self.mask.len() <= 126 && self.ip.to_bits() == self.network_address().to_bits()
     }
     /// Returns the number of network bits. That is, the length of the mask.
-    pub fn network_bits(&self) -> u8 {
+    pub const fn network_bits(&self) -> u8 {
         self.mask.len()
     }
     /// Returns the number of host bits. That is, the number of 0 bits in the mask.
-    pub fn host_bits(&self) -> u8 {
+    pub const fn host_bits(&self) -> u8 {
         128 - self.network_bits()
     }
     /// Returns the number of hosts in the network.
@@ -467,15 +510,15 @@ impl MaskedIpv6 {
     /// # Panics
     ///
     /// Will panic if u128 is not large enough to hold the host count.
-    pub fn host_count(&self) -> u128 {
-        2u128.checked_shl(self.host_bits() as u32).unwrap()
+    pub const fn host_count(&self) -> u128 {
+        1u128.checked_shl(self.host_bits() as u32).unwrap()
     }
     /// Returns the number of networks of the provided mask length will fit in this network.
     ///
     /// # Panics
     ///
     /// Will panic if the provided length is > 128, or if the number of networks does not fit in usize
-    pub fn network_count(&self, new_len: u8) -> u128 {
+    pub const fn network_count(&self, new_len: u8) -> u128 {
         let curr_len = self.mask.len();
         if new_len < curr_len {
             0
@@ -487,8 +530,15 @@ impl MaskedIpv6 {
         }
     }
     /// Returns true if this network contains the provided IP address.
-    pub fn contains(&self, ip: Ipv6Addr) -> bool {
-        self.ip & self.mask == ip & self.mask
+    pub const fn contains(&self, ip: Ipv6Addr) -> bool {
+        (self.ip.to_bits() ^ ip.to_bits()) & self.mask.to_bits() == 0
+    }
+    /// Returns the `ip6.arpa` reverse DNS zone of this network, rounded down to a nibble boundary.
+    pub const fn reverse_zone(&self) -> ReverseZone {
+        ReverseZone {
+            ip: IpAddr::V6(self.network_address()),
+            labels: self.network_bits() / 4,
+        }
     }
 }
 
@@ -506,61 +556,99 @@ impl MaskedIp {
     // pub fn to_cidr_string(&self) -> String {
     //     format!("{:#}", self)
     // }
-    pub fn network_address(&self) -> IpAddr {
+    pub const fn network_address(&self) -> IpAddr {
         match self {
             Self::V4(m) => IpAddr::V4(m.network_address()),
             Self::V6(m) => IpAddr::V6(m.network_address()),
         }
     }
-    pub fn network(&self) -> Self {
+    pub const fn network(&self) -> Self {
         match self {
             Self::V4(m) => Self::V4(m.network()),
             Self::V6(m) => Self::V6(m.network()),
         }
     }
-    pub fn is_network_address(&self) -> bool {
+    pub const fn is_network_address(&self) -> bool {
         match self {
             Self::V4(m) => m.is_network_address(),
             Self::V6(m) => m.is_network_address(),
         }
     }
-    pub fn is_broadcast_address(&self) -> bool {
+    pub const fn is_broadcast_address(&self) -> bool {
         match self {
             Self::V4(m) => m.is_broadcast_address(),
             Self::V6(_) => false,
         }
     }
-    pub fn network_bits(&self) -> u8 {
+    pub const fn network_bits(&self) -> u8 {
         match self {
             Self::V4(m) => m.network_bits(),
             Self::V6(m) => m.network_bits(),
         }
     }
-    pub fn host_bits(&self) -> u8 {
+    pub const fn host_bits(&self) -> u8 {
         match self {
             Self::V4(m) => m.host_bits(),
             Self::V6(m) => m.host_bits(),
         }
     }
-    pub fn host_count(&self) -> u128 {
+    pub const fn host_count(&self) -> u128 {
         match self {
             Self::V4(m) => m.host_count_u64() as u128,
             Self::V6(m) => m.host_count(),
         }
     }
-    pub fn network_count(&self, len: u8) -> u128 {
+    pub const fn network_count(&self, len: u8) -> u128 {
         match self {
             Self::V4(m) => m.network_count_u64(len) as u128,
             Self::V6(m) => m.network_count(len),
         }
     }
-    pub fn contains(&self, ip: IpAddr) -> bool {
+    pub const fn contains(&self, ip: IpAddr) -> bool {
         match (self, ip) {
             (Self::V4(m), IpAddr::V4(ip)) => m.contains(ip),
             (Self::V6(m), IpAddr::V6(ip)) => m.contains(ip),
             _ => false,
         }
     }
+    pub const fn reverse_zone(&self) -> ReverseZone {
+        match self {
+            Self::V4(m) => m.reverse_zone(),
+            Self::V6(m) => m.reverse_zone(),
+        }
+    }
+}
+
+/// The reverse DNS zone of a network, such as `2.0.192.in-addr.arpa`. The zone covers the network
+/// rounded down to an octet boundary for IPv4, or a nibble boundary for IPv6.
+///
+/// Created by [`MaskedIpv4::reverse_zone`], [`MaskedIpv6::reverse_zone`] and [`MaskedIp::reverse_zone`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ReverseZone {
+    ip: IpAddr,
+    labels: u8,
+}
+
+impl Display for ReverseZone {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self.ip {
+            IpAddr::V4(ip) => {
+                let octets = ip.octets();
+                for i in (0..self.labels as usize).rev() {
+                    write!(f, "{}.", octets[i])?;
+                }
+                f.write_str("in-addr.arpa")
+            }
+            IpAddr::V6(ip) => {
+                let octets = ip.octets();
+                for i in (0..self.labels as usize).rev() {
+                    let shift = if i % 2 == 0 { 4 } else { 0 };
+                    write!(f, "{:x}.", (octets[i / 2] >> shift) & 0xF)?;
+                }
+                f.write_str("ip6.arpa")
+            }
+        }
+    }
 }
 
 impl Display for MaskedIpv4 {
@@ -606,6 +694,91 @@ impl Debug for MaskedIp {
     }
 }
 
+/// Networks are ordered by network address, then by mask length, so a network sorts
+/// immediately before its subnets. Networks which differ only in host bits are ordered by IP.
+impl Ord for MaskedIpv4 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |m: &Self| (m.network_address(), m.network_bits(), m.ip);
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for MaskedIpv4 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Networks are ordered by network address, then by mask length, so a network sorts
+/// immediately before its subnets. Networks which differ only in host bits are ordered by IP.
+impl Ord for MaskedIpv6 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let key = |m: &Self| (m.network_address(), m.network_bits(), m.ip);
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for MaskedIpv6 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// All IPv4 networks are ordered before all IPv6 networks. Networks of the same family are
+/// ordered as [`MaskedIpv4`] and [`MaskedIpv6`] are.
+impl Ord for MaskedIp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::V4(a), Self::V4(b)) => a.cmp(b),
+            (Self::V6(a), Self::V6(b)) => a.cmp(b),
+            (Self::V4(_), Self::V6(_)) => Ordering::Less,
+            (Self::V6(_), Self::V4(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl PartialOrd for MaskedIp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<MaskedIpv4> for MaskedIp {
+    fn from(value: MaskedIpv4) -> Self {
+        Self::V4(value)
+    }
+}
+
+impl From<MaskedIpv6> for MaskedIp {
+    fn from(value: MaskedIpv6) -> Self {
+        Self::V6(value)
+    }
+}
+
+impl From<Ipv4Addr> for MaskedIpv4 {
+    /// Converts an address into a /32 network.
+    fn from(ip: Ipv4Addr) -> Self {
+        Self::cidr(ip, 32)
+    }
+}
+
+impl From<Ipv6Addr> for MaskedIpv6 {
+    /// Converts an address into a /128 network.
+    fn from(ip: Ipv6Addr) -> Self {
+        Self::cidr(ip, 128)
+    }
+}
+
+impl From<IpAddr> for MaskedIp {
+    /// Converts an address into a /32 or /128 network.
+    fn from(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => Self::V4(ip.into()),
+            IpAddr::V6(ip) => Self::V6(ip.into()),
+        }
+    }
+}
+
 impl FromStr for MaskedIpv4 {
     type Err = InvalidMaskedIpv4;
     fn from_str(s: &str) -> Result<Self, InvalidMaskedIpv4> {
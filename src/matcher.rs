@@ -0,0 +1,220 @@
+use crate::{Ipv4Addr, Ipv6Addr, MaskedIpv4, MaskedIpv6};
+
+use alloc::vec::Vec;
+
+/// Bits indexed by the root table.
+const ROOT_STRIDE: u32 = 16;
+/// Bits indexed by every table below the root.
+const STRIDE: u32 = 8;
+/// Set on entries which point to a child table. Other entries hold a prefix index plus one, or 0
+/// for no match.
+const CHILD: u32 = 1 << 31;
+
+/// A multibit trie with a 16-bit root table followed by 8-bit tables, stored in one flat array.
+///
+/// Prefixes are inserted shortest first, so inserting a prefix never has to look beneath an
+/// existing child table: any child in its range was created by a longer prefix, which would
+/// have been inserted later.
+#[derive(Clone)]
+struct Trie {
+    table: Vec<u32>,
+}
+
+impl Trie {
+    /// Builds a trie over `width`-bit keys from `(key, len)` prefixes, where each prefix's value
+    /// is its position.
+    fn build(mut prefixes: Vec<(u128, u8, usize)>, width: u32) -> Self {
+        assert!(
+            prefixes.len() < CHILD as usize,
+            "too many prefixes for a matcher"
+        );
+        // Shortest first. Among equal lengths, the lowest index is inserted last so it wins.
+        prefixes.sort_unstable_by(|a, b| a.1.cmp(&b.1).then(b.2.cmp(&a.2)));
+        let mut trie = Self {
+            table: alloc::vec![0; 1 << ROOT_STRIDE],
+        };
+        for (key, len, index) in prefixes {
+            trie.insert(key, len as u32, width, index as u32 + 1);
+        }
+        trie
+    }
+    fn insert(&mut self, key: u128, len: u32, width: u32, value: u32) {
+        let (mut base, mut end, mut stride) = (0, ROOT_STRIDE, ROOT_STRIDE);
+        loop {
+            let slot = ((key >> (width - end)) as usize) & ((1 << stride) - 1);
+            if len <= end {
+                let span = 1 << (end - len);
+                let first = base + (slot & !(span - 1));
+                self.table[first..first + span].fill(value);
+                return;
+            }
+            let entry = self.table[base + slot];
+            base = if entry & CHILD != 0 {
+                (entry & !CHILD) as usize
+            } else {
+                let child = self.table.len();
+                assert!(child < CHILD as usize, "matcher table is too large");
+                // The child inherits the shorter match which covered this slot.
+                self.table.resize(child + (1 << STRIDE), entry);
+                self.table[base + slot] = child as u32 | CHILD;
+                child
+            };
+            end += STRIDE;
+            stride = STRIDE;
+        }
+    }
+    #[inline]
+    fn resolve(&self, mut entry: u32, key: u128, width: u32) -> Option<usize> {
+        let mut end = ROOT_STRIDE;
+        while entry & CHILD != 0 {
+            end += STRIDE;
+            let slot = ((key >> (width - end)) as usize) & ((1 << STRIDE) - 1);
+            entry = self.table[(entry & !CHILD) as usize + slot];
+        }
+        (entry as usize).checked_sub(1)
+    }
+}
+
+/// An immutable set of IPv4 networks compiled for fast longest-prefix lookups of addresses.
+///
+/// Lookups take at most 3 table reads. The root table always uses 256 KiB, and every prefix
+/// longer than /16 adds at most 2 KiB.
+#[derive(Clone)]
+pub struct Ipv4Matcher {
+    trie: Trie,
+    len: usize,
+}
+/// An immutable set of IPv6 networks compiled for fast longest-prefix lookups of addresses.
+///
+/// Lookups take at most 15 table reads. The root table always uses 256 KiB, and every prefix
+/// longer than /16 adds at most 1 KiB per 8 bits of length beyond /16.
+#[derive(Clone)]
+pub struct Ipv6Matcher {
+    trie: Trie,
+    len: usize,
+}
+
+impl Ipv4Matcher {
+    /// Compiles a matcher from a list of networks. Host bits are ignored.
+    ///
+    /// # Panics
+    /// Panics if there are 2^31 or more networks.
+    pub fn new<T: Into<MaskedIpv4>>(networks: impl IntoIterator<Item = T>) -> Self {
+        let prefixes: Vec<_> = networks
+            .into_iter()
+            .enumerate()
+            .map(|(i, net)| {
+                let net = net.into();
+                (net.ip.to_bits() as u128, net.network_bits(), i)
+            })
+            .collect();
+        let len = prefixes.len();
+        Self {
+            trie: Trie::build(prefixes, 32),
+            len,
+        }
+    }
+    /// Returns the number of networks this matcher was built from.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns true if this matcher was built from no networks.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns the index of the longest network containing `addr`, or None if no network does.
+    /// If the same network appears more than once, the lowest index is returned.
+    #[inline]
+    pub fn matches(&self, addr: Ipv4Addr) -> Option<usize> {
+        let bits = addr.to_bits();
+        let entry = self.trie.table[(bits >> (32 - ROOT_STRIDE)) as usize];
+        self.trie.resolve(entry, bits as u128, 32)
+    }
+    /// Returns true if any network contains `addr`.
+    #[inline]
+    pub fn contains(&self, addr: Ipv4Addr) -> bool {
+        self.matches(addr).is_some()
+    }
+    /// Looks up every address of `addrs`, writing each result to the same position of `out`.
+    ///
+    /// Addresses are handled in groups, reading all of a group's root entries before following
+    /// any child tables, so the memory accesses of a group overlap. `Ipv4Addr` has the same
+    /// layout as `[u8; 4]`, so loading each key is a single 4-byte read.
+    ///
+    /// # Panics
+    /// Panics if `addrs` and `out` have different lengths.
+    pub fn matches_batch(&self, addrs: &[Ipv4Addr], out: &mut [Option<usize>]) {
+        const GROUP: usize = 8;
+        assert_eq!(addrs.len(), out.len(), "output length must match input");
+        let mut addr_groups = addrs.chunks_exact(GROUP);
+        let mut out_groups = out.chunks_exact_mut(GROUP);
+        for (addrs, out) in (&mut addr_groups).zip(&mut out_groups) {
+            let mut bits = [0u32; GROUP];
+            let mut entries = [0u32; GROUP];
+            for i in 0..GROUP {
+                bits[i] = addrs[i].to_bits();
+                entries[i] = self.trie.table[(bits[i] >> (32 - ROOT_STRIDE)) as usize];
+            }
+            for i in 0..GROUP {
+                out[i] = self.trie.resolve(entries[i], bits[i] as u128, 32);
+            }
+        }
+        let addrs = addr_groups.remainder();
+        for (addr, out) in addrs.iter().zip(out_groups.into_remainder()) {
+            *out = self.matches(*addr);
+        }
+    }
+}
+
+impl Ipv6Matcher {
+    /// Compiles a matcher from a list of networks. Host bits are ignored.
+    ///
+    /// # Panics
+    /// Panics if there are 2^31 or more networks, or the tables would exceed 2^31 entries.
+    pub fn new<T: Into<MaskedIpv6>>(networks: impl IntoIterator<Item = T>) -> Self {
+        let prefixes: Vec<_> = networks
+            .into_iter()
+            .enumerate()
+            .map(|(i, net)| {
+                let net = net.into();
+                (net.ip.to_bits(), net.network_bits(), i)
+            })
+            .collect();
+        let len = prefixes.len();
+        Self {
+            trie: Trie::build(prefixes, 128),
+            len,
+        }
+    }
+    /// Returns the number of networks this matcher was built from.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns true if this matcher was built from no networks.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns the index of the longest network containing `addr`, or None if no network does.
+    /// If the same network appears more than once, the lowest index is returned.
+    #[inline]
+    pub fn matches(&self, addr: Ipv6Addr) -> Option<usize> {
+        let bits = addr.to_bits();
+        let entry = self.trie.table[(bits >> (128 - ROOT_STRIDE)) as usize];
+        self.trie.resolve(entry, bits, 128)
+    }
+    /// Returns true if any network contains `addr`.
+    #[inline]
+    pub fn contains(&self, addr: Ipv6Addr) -> bool {
+        self.matches(addr).is_some()
+    }
+    /// Looks up every address of `addrs`, writing each result to the same position of `out`.
+    ///
+    /// # Panics
+    /// Panics if `addrs` and `out` have different lengths.
+    pub fn matches_batch(&self, addrs: &[Ipv6Addr], out: &mut [Option<usize>]) {
+        assert_eq!(addrs.len(), out.len(), "output length must match input");
+        for (addr, out) in addrs.iter().zip(out) {
+            *out = self.matches(*addr);
+        }
+    }
+}
@@ -0,0 +1,714 @@
+//! Reading and writing MaxMind DB files, the format used by GeoIP2 and many other IP metadata
+//! databases.
+//!
+//! A file is a binary search tree over address bits, followed by a data section holding the
+//! values the tree points to, followed by metadata describing the tree. The specification is at
+//! <https://maxmind.github.io/MaxMind-DB/>.
+
+use crate::{IpAddr, MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Separates the data section from the metadata.
+const METADATA_MARKER: &[u8] = b"\xab\xcd\xefMaxMind.com";
+/// The metadata is within this many bytes of the end of the file.
+const METADATA_MAX_LEN: usize = 128 * 1024;
+/// Nested maps and arrays deeper than this are rejected, so corrupt data cannot overflow the
+/// stack.
+const MAX_DEPTH: usize = 512;
+/// At most this many values are decoded for one record. Pointers may share a target, so a small
+/// corrupt file could otherwise expand into exponentially many values.
+const MAX_VALUES: usize = 1 << 16;
+
+const POINTER: u8 = 1;
+const STRING: u8 = 2;
+const DOUBLE: u8 = 3;
+const BYTES: u8 = 4;
+const U16: u8 = 5;
+const U32: u8 = 6;
+const MAP: u8 = 7;
+const I32: u8 = 8;
+const U64: u8 = 9;
+const U128: u8 = 10;
+const ARRAY: u8 = 11;
+const BOOL: u8 = 14;
+const FLOAT: u8 = 15;
+
+/// Error when reading or writing a MaxMind DB file.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum MmdbError {
+    /// The metadata marker was not found near the end of the file.
+    MissingMetadata,
+    /// The metadata is missing a required field, or a field has the wrong type.
+    InvalidMetadata,
+    /// The major version of the binary format is not 2.
+    UnsupportedVersion(u16),
+    /// The search tree is truncated or does not lead to a value.
+    InvalidTree,
+    /// A value in the data section could not be decoded.
+    InvalidData,
+    /// An IPv6 address or network was used with an IPv4 database.
+    Ipv6InIpv4Database,
+}
+
+impl Display for MmdbError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::MissingMetadata => f.write_str("MaxMind DB metadata not found"),
+            Self::InvalidMetadata => f.write_str("invalid MaxMind DB metadata"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported MaxMind DB format version {v}"),
+            Self::InvalidTree => f.write_str("invalid MaxMind DB search tree"),
+            Self::InvalidData => f.write_str("invalid MaxMind DB data"),
+            Self::Ipv6InIpv4Database => f.write_str("IPv6 address used with an IPv4 database"),
+        }
+    }
+}
+
+/// A decoded value from the data section of a MaxMind DB file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MmdbValue {
+    /// A UTF-8 string
+    String(String),
+    /// A 64-bit float
+    Double(f64),
+    /// A 32-bit float
+    Float(f32),
+    /// Raw bytes
+    Bytes(Vec<u8>),
+    /// An unsigned 16-bit integer
+    U16(u16),
+    /// An unsigned 32-bit integer
+    U32(u32),
+    /// An unsigned 64-bit integer
+    U64(u64),
+    /// An unsigned 128-bit integer
+    U128(u128),
+    /// A signed 32-bit integer
+    I32(i32),
+    /// A boolean
+    Bool(bool),
+    /// A list of values
+    Array(Vec<MmdbValue>),
+    /// A map from string keys to values
+    Map(BTreeMap<String, MmdbValue>),
+}
+
+impl MmdbValue {
+    /// Returns the value of `key` if this is a map containing it.
+    pub fn get(&self, key: &str) -> Option<&MmdbValue> {
+        match self {
+            Self::Map(map) => map.get(key),
+            _ => None,
+        }
+    }
+    /// Returns the string if this is a string.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::String(s) => Some(s),
+            _ => None,
+        }
+    }
+    /// Returns the integer if this is an unsigned integer which fits in a u64.
+    pub fn as_u64(&self) -> Option<u64> {
+        match *self {
+            Self::U16(n) => Some(n.into()),
+            Self::U32(n) => Some(n.into()),
+            Self::U64(n) => Some(n),
+            Self::U128(n) => n.try_into().ok(),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for MmdbValue {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_string())
+    }
+}
+
+impl From<String> for MmdbValue {
+    fn from(s: String) -> Self {
+        Self::String(s)
+    }
+}
+
+impl From<u32> for MmdbValue {
+    fn from(n: u32) -> Self {
+        Self::U32(n)
+    }
+}
+
+impl From<bool> for MmdbValue {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<f64> for MmdbValue {
+    fn from(n: f64) -> Self {
+        Self::Double(n)
+    }
+}
+
+/// The metadata of a MaxMind DB file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MmdbMetadata {
+    /// The major version of the binary format, always 2
+    pub binary_format_major_version: u16,
+    /// The minor version of the binary format
+    pub binary_format_minor_version: u16,
+    /// When the database was built, in seconds since the Unix epoch
+    pub build_epoch: u64,
+    /// The kind of database, such as `GeoIP2-City`
+    pub database_type: String,
+    /// Descriptions of the database, keyed by language code
+    pub description: BTreeMap<String, String>,
+    /// 4 for a database of IPv4 networks only, or 6 for a database which may hold both
+    pub ip_version: u16,
+    /// The languages which values may have names in
+    pub languages: Vec<String>,
+    /// The number of nodes in the search tree
+    pub node_count: u32,
+    /// The size of each tree record in bits, 24, 28 or 32
+    pub record_size: u16,
+}
+
+/// Decodes values from a data section, where pointers are offsets from its start.
+#[derive(Copy, Clone)]
+struct Decoder<'a> {
+    data: &'a [u8],
+}
+
+impl core::fmt::Debug for Decoder<'_> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "Decoder({} bytes)", self.data.len())
+    }
+}
+
+impl<'a> Decoder<'a> {
+    fn bytes(&self, offset: usize, len: usize) -> Result<&'a [u8], MmdbError> {
+        offset
+            .checked_add(len)
+            .and_then(|end| self.data.get(offset..end))
+            .ok_or(MmdbError::InvalidData)
+    }
+    /// Reads a big-endian unsigned integer of at most `max` bytes.
+    fn uint(&self, offset: usize, len: usize, max: usize) -> Result<u128, MmdbError> {
+        if len > max {
+            return Err(MmdbError::InvalidData);
+        }
+        let bytes = self.bytes(offset, len)?;
+        Ok(bytes.iter().fold(0, |n, &b| (n << 8) | b as u128))
+    }
+    /// Decodes the complete value at `offset`.
+    fn value(&self, offset: usize) -> Result<MmdbValue, MmdbError> {
+        let mut budget = MAX_VALUES;
+        Ok(self.decode(offset, 0, &mut budget)?.0)
+    }
+    /// Decodes the value at `offset`, returning it and the offset just after it. Each value
+    /// decoded, including through a pointer, uses one of `budget`.
+    fn decode(
+        &self,
+        offset: usize,
+        depth: usize,
+        budget: &mut usize,
+    ) -> Result<(MmdbValue, usize), MmdbError> {
+        if depth > MAX_DEPTH || *budget == 0 {
+            return Err(MmdbError::InvalidData);
+        }
+        *budget -= 1;
+        let control = *self.data.get(offset).ok_or(MmdbError::InvalidData)?;
+        let mut offset = offset + 1;
+        let mut kind = control >> 5;
+        if kind == POINTER {
+            let size = (control >> 3) & 3;
+            let high = (control & 7) as usize;
+            let low = self.uint(offset, size as usize + 1, 4)? as usize;
+            let target = match size {
+                0 => (high << 8) | low,
+                1 => ((high << 16) | low) + 2048,
+                2 => ((high << 24) | low) + 526336,
+                _ => low,
+            };
+            // A pointer may not point to another pointer.
+            if self.data.get(target).is_none_or(|c| c >> 5 == POINTER) {
+                return Err(MmdbError::InvalidData);
+            }
+            let (value, _) = self.decode(target, depth + 1, budget)?;
+            return Ok((value, offset + size as usize + 1));
+        }
+        if kind == 0 {
+            // Extended types start at 8, so an extended byte of 0 is not a map.
+            let extended = *self.data.get(offset).ok_or(MmdbError::InvalidData)?;
+            kind = match extended.checked_add(7) {
+                Some(kind) if extended != 0 && kind <= FLOAT => kind,
+                _ => return Err(MmdbError::InvalidData),
+            };
+            offset += 1;
+        }
+        let mut len = (control & 0x1f) as usize;
+        if len >= 29 {
+            let extra = len - 28;
+            let n = self.uint(offset, extra, 3)? as usize;
+            len = [29, 285, 65821][extra - 1] + n;
+            offset += extra;
+        }
+        let value = match kind {
+            STRING => {
+                let s = core::str::from_utf8(self.bytes(offset, len)?);
+                MmdbValue::String(s.map_err(|_| MmdbError::InvalidData)?.to_string())
+            }
+            DOUBLE if len == 8 => {
+                MmdbValue::Double(f64::from_bits(self.uint(offset, 8, 8)? as u64))
+            }
+            FLOAT if len == 4 => MmdbValue::Float(f32::from_bits(self.uint(offset, 4, 4)? as u32)),
+            BYTES => MmdbValue::Bytes(self.bytes(offset, len)?.to_vec()),
+            U16 => MmdbValue::U16(self.uint(offset, len, 2)? as u16),
+            U32 => MmdbValue::U32(self.uint(offset, len, 4)? as u32),
+            U64 => MmdbValue::U64(self.uint(offset, len, 8)? as u64),
+            U128 => MmdbValue::U128(self.uint(offset, len, 16)?),
+            I32 => MmdbValue::I32(self.uint(offset, len, 4)? as u32 as i32),
+            BOOL if len <= 1 => return Ok((MmdbValue::Bool(len == 1), offset)),
+            MAP => {
+                let mut map = BTreeMap::new();
+                for _ in 0..len {
+                    let (key, next) = self.decode(offset, depth + 1, budget)?;
+                    let MmdbValue::String(key) = key else {
+                        return Err(MmdbError::InvalidData);
+                    };
+                    let (value, next) = self.decode(next, depth + 1, budget)?;
+                    map.insert(key, value);
+                    offset = next;
+                }
+                return Ok((MmdbValue::Map(map), offset));
+            }
+            ARRAY => {
+                let mut array = Vec::new();
+                for _ in 0..len {
+                    let (value, next) = self.decode(offset, depth + 1, budget)?;
+                    array.push(value);
+                    offset = next;
+                }
+                return Ok((MmdbValue::Array(array), offset));
+            }
+            _ => return Err(MmdbError::InvalidData),
+        };
+        Ok((value, offset + len))
+    }
+}
+
+impl MmdbMetadata {
+    fn from_value(value: &MmdbValue) -> Result<Self, MmdbError> {
+        let uint = |key| value.get(key).and_then(MmdbValue::as_u64);
+        let small = |key| uint(key).and_then(|n| u16::try_from(n).ok());
+        let languages = match value.get("languages") {
+            Some(MmdbValue::Array(values)) => values
+                .iter()
+                .map(|v| Some(v.as_str()?.to_string()))
+                .collect::<Option<_>>(),
+            None => Some(Vec::new()),
+            Some(_) => None,
+        };
+        let description = match value.get("description") {
+            Some(MmdbValue::Map(map)) => map
+                .iter()
+                .map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                .collect::<Option<_>>(),
+            None => Some(BTreeMap::new()),
+            Some(_) => None,
+        };
+        let metadata = || -> Option<Self> {
+            Some(Self {
+                binary_format_major_version: small("binary_format_major_version")?,
+                binary_format_minor_version: small("binary_format_minor_version")?,
+                build_epoch: uint("build_epoch")?,
+                database_type: value.get("database_type")?.as_str()?.to_string(),
+                description: description?,
+                ip_version: small("ip_version").filter(|v| *v == 4 || *v == 6)?,
+                languages: languages?,
+                node_count: uint("node_count").and_then(|n| n.try_into().ok())?,
+                record_size: small("record_size").filter(|s| matches!(s, 24 | 28 | 32))?,
+            })
+        };
+        let metadata = metadata().ok_or(MmdbError::InvalidMetadata)?;
+        if metadata.binary_format_major_version != 2 {
+            return Err(MmdbError::UnsupportedVersion(
+                metadata.binary_format_major_version,
+            ));
+        }
+        Ok(metadata)
+    }
+}
+
+/// A MaxMind DB file borrowed from its bytes, such as a memory-mapped file.
+#[derive(Clone, Debug)]
+pub struct MmdbReader<'a> {
+    tree: &'a [u8],
+    data: Decoder<'a>,
+    metadata: MmdbMetadata,
+    /// The node reached after the 96 zero bits which precede IPv4 addresses, and its depth.
+    ipv4_start: (u32, u8),
+}
+
+impl<'a> MmdbReader<'a> {
+    /// Opens a database from its bytes, reading its metadata.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, MmdbError> {
+        let search_from = bytes.len().saturating_sub(METADATA_MAX_LEN);
+        let marker = bytes[search_from..]
+            .windows(METADATA_MARKER.len())
+            .rposition(|w| w == METADATA_MARKER)
+            .ok_or(MmdbError::MissingMetadata)?
+            + search_from;
+        let metadata = Decoder {
+            data: &bytes[marker + METADATA_MARKER.len()..],
+        };
+        let metadata = metadata.value(0).map_err(|_| MmdbError::InvalidMetadata)?;
+        let metadata = MmdbMetadata::from_value(&metadata)?;
+        let tree_len = metadata.node_count as usize * metadata.record_size as usize / 4;
+        // The tree is followed by 16 zero bytes, then the data section.
+        if tree_len + 16 > marker {
+            return Err(MmdbError::InvalidTree);
+        }
+        let mut reader = Self {
+            tree: &bytes[..tree_len],
+            data: Decoder {
+                data: &bytes[tree_len + 16..marker],
+            },
+            metadata,
+            ipv4_start: (0, 0),
+        };
+        if reader.metadata.ip_version == 6 {
+            let mut node = 0;
+            let mut depth = 0;
+            while depth < 96 && node < reader.metadata.node_count {
+                node = reader.record(node, false);
+                depth += 1;
+            }
+            reader.ipv4_start = (node, depth);
+        }
+        Ok(reader)
+    }
+    /// Returns the metadata of the database.
+    pub fn metadata(&self) -> &MmdbMetadata {
+        &self.metadata
+    }
+    /// Reads the left or right record of a node, which must be less than the node count.
+    fn record(&self, node: u32, right: bool) -> u32 {
+        let size = self.metadata.record_size as usize;
+        let at = node as usize * size / 4;
+        let b = |i: usize| self.tree[at + i] as u32;
+        let three = |i: usize| (b(i) << 16) | (b(i + 1) << 8) | b(i + 2);
+        match (size, right) {
+            (24, false) => three(0),
+            (24, true) => three(3),
+            (28, false) => ((b(3) & 0xf0) << 20) | three(0),
+            (28, true) => ((b(3) & 0x0f) << 24) | three(4),
+            (_, false) => (b(0) << 24) | three(1),
+            (_, true) => (b(4) << 24) | three(5),
+        }
+    }
+    /// Looks up an address, returning the network of the matching record and its value, or
+    /// None if the address is not in the database.
+    ///
+    /// The network is the one covered by the record in the search tree. Where a longer network
+    /// was nested inside a shorter one when the database was built, the record may cover only
+    /// part of the shorter network.
+    ///
+    /// The network has the same family as `addr`. In an IPv6 database, IPv4 addresses are
+    /// looked up as `::a.b.c.d`, and a record covering more than all of IPv4 is returned as
+    /// `0.0.0.0/0`.
+    pub fn lookup(&self, addr: IpAddr) -> Result<Option<(MaskedIp, MmdbValue)>, MmdbError> {
+        let node_count = self.metadata.node_count;
+        // The key bits are read from the most significant bit, up to `end` bits.
+        let (key, mut node, mut depth, end) = match (addr, self.metadata.ip_version) {
+            (IpAddr::V4(ip), 4) => ((ip.to_bits() as u128) << 96, 0, 0, 32),
+            (IpAddr::V4(ip), _) => (
+                ip.to_bits() as u128,
+                self.ipv4_start.0,
+                self.ipv4_start.1,
+                128,
+            ),
+            (IpAddr::V6(_), 4) => return Err(MmdbError::Ipv6InIpv4Database),
+            (IpAddr::V6(ip), _) => (ip.to_bits(), 0, 0, 128),
+        };
+        while depth < end && node < node_count {
+            node = self.record(node, (key >> (127 - depth)) & 1 == 1);
+            depth += 1;
+        }
+        if node == node_count {
+            return Ok(None);
+        }
+        let offset = (node as usize)
+            .checked_sub(node_count as usize + 16)
+            .ok_or(MmdbError::InvalidTree)?;
+        let value = self.data.value(offset)?;
+        let network = match addr {
+            IpAddr::V4(ip) => {
+                let len = if end == 32 {
+                    depth
+                } else {
+                    depth.saturating_sub(96)
+                };
+                MaskedIp::V4(MaskedIpv4::cidr(ip, len).network())
+            }
+            IpAddr::V6(ip) => MaskedIp::V6(MaskedIpv6::cidr(ip, depth).network()),
+        };
+        Ok(Some((network, value)))
+    }
+}
+
+/// The longest length a control byte can encode.
+const MAX_LEN: usize = 65821 + (1 << 24) - 1;
+
+/// Appends a control byte for a value of type `kind` and length `len`.
+fn encode_control(out: &mut Vec<u8>, kind: u8, len: usize) {
+    assert!(len <= MAX_LEN, "value is too long");
+    let (first, extra): (usize, &[u8]) = match len {
+        0..29 => (len, &[]),
+        29..285 => (29, &[(len - 29) as u8]),
+        285..65821 => (30, &(len - 285).to_be_bytes()[size_of::<usize>() - 2..]),
+        _ => (31, &(len - 65821).to_be_bytes()[size_of::<usize>() - 3..]),
+    };
+    if kind <= MAP {
+        out.push((kind << 5) | first as u8);
+    } else {
+        out.push(first as u8);
+        out.push(kind - 7);
+    }
+    out.extend_from_slice(extra);
+}
+
+/// Appends an unsigned integer without leading zero bytes.
+fn encode_uint(out: &mut Vec<u8>, kind: u8, n: u128) {
+    let bytes = n.to_be_bytes();
+    let len = 16 - n.leading_zeros() as usize / 8;
+    encode_control(out, kind, len);
+    out.extend_from_slice(&bytes[16 - len..]);
+}
+
+fn encode(out: &mut Vec<u8>, value: &MmdbValue) {
+    match value {
+        MmdbValue::String(s) => {
+            encode_control(out, STRING, s.len());
+            out.extend_from_slice(s.as_bytes());
+        }
+        MmdbValue::Double(n) => {
+            encode_control(out, DOUBLE, 8);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+        MmdbValue::Float(n) => {
+            encode_control(out, FLOAT, 4);
+            out.extend_from_slice(&n.to_be_bytes());
+        }
+        MmdbValue::Bytes(b) => {
+            encode_control(out, BYTES, b.len());
+            out.extend_from_slice(b);
+        }
+        MmdbValue::U16(n) => encode_uint(out, U16, (*n).into()),
+        MmdbValue::U32(n) => encode_uint(out, U32, (*n).into()),
+        MmdbValue::U64(n) => encode_uint(out, U64, (*n).into()),
+        MmdbValue::U128(n) => encode_uint(out, U128, *n),
+        MmdbValue::I32(n) => encode_uint(out, I32, (*n as u32).into()),
+        MmdbValue::Bool(b) => encode_control(out, BOOL, *b as usize),
+        MmdbValue::Array(values) => {
+            encode_control(out, ARRAY, values.len());
+            for value in values {
+                encode(out, value);
+            }
+        }
+        MmdbValue::Map(map) => {
+            encode_control(out, MAP, map.len());
+            for (key, value) in map {
+                encode_control(out, STRING, key.len());
+                out.extend_from_slice(key.as_bytes());
+                encode(out, value);
+            }
+        }
+    }
+}
+
+/// A link from a node of the search tree being built.
+#[derive(Copy, Clone, PartialEq)]
+enum Link {
+    Empty,
+    Node(usize),
+    Value(usize),
+}
+
+/// Builds a MaxMind DB file from a map of networks to values.
+///
+/// Networks may be nested, in which case the longest prefix containing an address determines
+/// its value. IPv4 networks in an IPv6 database are stored under `::/96`; the IPv4-mapped and
+/// 6to4 ranges are not aliased to them.
+#[derive(Clone, Debug)]
+pub struct MmdbWriter {
+    database_type: String,
+    ip_version: u16,
+    languages: Vec<String>,
+    description: BTreeMap<String, String>,
+    build_epoch: u64,
+    networks: Vec<(MaskedIp, MmdbValue)>,
+}
+
+impl MmdbWriter {
+    /// Constructs a writer for an IPv6 database, which may hold both IPv4 and IPv6 networks.
+    pub fn ipv6(database_type: &str) -> Self {
+        Self {
+            database_type: database_type.to_string(),
+            ip_version: 6,
+            languages: Vec::new(),
+            description: BTreeMap::new(),
+            build_epoch: 0,
+            networks: Vec::new(),
+        }
+    }
+    /// Constructs a writer for an IPv4 database, which may only hold IPv4 networks.
+    pub fn ipv4(database_type: &str) -> Self {
+        Self {
+            ip_version: 4,
+            ..Self::ipv6(database_type)
+        }
+    }
+    /// Adds a description of the database in the given language.
+    pub fn with_description(mut self, language: &str, description: &str) -> Self {
+        self.description
+            .insert(language.to_string(), description.to_string());
+        self
+    }
+    /// Adds a language which values may have names in.
+    pub fn with_language(mut self, language: &str) -> Self {
+        self.languages.push(language.to_string());
+        self
+    }
+    /// Sets when the database was built, in seconds since the Unix epoch. Defaults to 0.
+    pub fn with_build_epoch(mut self, build_epoch: u64) -> Self {
+        self.build_epoch = build_epoch;
+        self
+    }
+    /// Adds a network with its value. Host bits are ignored. If the same network is inserted
+    /// more than once, the last value is kept.
+    pub fn insert(&mut self, network: impl Into<MaskedIp>, value: MmdbValue) {
+        self.networks.push((network.into(), value));
+    }
+    /// Serializes the database. The record size is the smallest which fits.
+    ///
+    /// # Errors
+    /// Fails with [`MmdbError::Ipv6InIpv4Database`] if an IPv6 network was inserted into an
+    /// IPv4 database.
+    ///
+    /// # Panics
+    /// Panics if the database needs more than 32 bits per record, or if a string, byte array,
+    /// map or array has more than 16843036 bytes or entries.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, MmdbError> {
+        let ipv4 = self.ip_version == 4;
+        // Each network as (key, length), with keys read from the most significant bit.
+        let mut keys = Vec::with_capacity(self.networks.len());
+        for (i, (network, _)) in self.networks.iter().enumerate() {
+            let key = match network {
+                MaskedIp::V4(n) if ipv4 => ((n.ip.to_bits() as u128) << 96, n.network_bits()),
+                MaskedIp::V4(n) => (n.ip.to_bits() as u128, n.network_bits() + 96),
+                MaskedIp::V6(_) if ipv4 => return Err(MmdbError::Ipv6InIpv4Database),
+                MaskedIp::V6(n) => (n.ip.to_bits(), n.network_bits()),
+            };
+            keys.push((key, i));
+        }
+        // Shorter networks are inserted first so longer ones split them. The sort is stable, so
+        // the last duplicate is inserted last.
+        keys.sort_by_key(|&((_, len), _)| len);
+
+        let mut data = Vec::new();
+        let mut offsets: BTreeMap<Vec<u8>, usize> = BTreeMap::new();
+        let mut nodes = alloc::vec![[Link::Empty; 2]];
+        for ((key, len), i) in keys {
+            let mut encoded = Vec::new();
+            encode(&mut encoded, &self.networks[i].1);
+            let offset = *offsets.entry(encoded).or_insert_with_key(|encoded| {
+                data.extend_from_slice(encoded);
+                data.len() - encoded.len()
+            });
+            let value = Link::Value(offset);
+            if len == 0 {
+                nodes[0] = [value; 2];
+                continue;
+            }
+            let mut node = 0;
+            for depth in 0..len as usize - 1 {
+                let bit = ((key >> (127 - depth)) & 1) as usize;
+                node = match nodes[node][bit] {
+                    Link::Node(next) => next,
+                    link => {
+                        // Split an empty or shorter network into a node with both halves.
+                        nodes.push([link; 2]);
+                        nodes[node][bit] = Link::Node(nodes.len() - 1);
+                        nodes.len() - 1
+                    }
+                };
+            }
+            let bit = ((key >> (128 - len as usize)) & 1) as usize;
+            nodes[node][bit] = value;
+        }
+
+        let node_count = nodes.len();
+        let max_record = node_count + 16 + data.len();
+        let record_size: usize = match max_record {
+            0..0x100_0000 => 24,
+            0x100_0000..0x1000_0000 => 28,
+            _ if u32::try_from(max_record).is_ok() => 32,
+            _ => panic!("database is too large"),
+        };
+        let record = |link: Link| match link {
+            Link::Empty => node_count as u32,
+            Link::Node(n) => n as u32,
+            Link::Value(offset) => (node_count + 16 + offset) as u32,
+        };
+        let mut out = Vec::with_capacity(node_count * record_size / 4 + 16 + data.len());
+        for [left, right] in nodes {
+            let (left, right) = (record(left).to_be_bytes(), record(right).to_be_bytes());
+            match record_size {
+                24 => {
+                    out.extend_from_slice(&left[1..]);
+                    out.extend_from_slice(&right[1..]);
+                }
+                28 => {
+                    out.extend_from_slice(&left[1..]);
+                    out.push((left[0] << 4) | right[0]);
+                    out.extend_from_slice(&right[1..]);
+                }
+                _ => {
+                    out.extend_from_slice(&left);
+                    out.extend_from_slice(&right);
+                }
+            }
+        }
+        out.extend_from_slice(&[0; 16]);
+        out.extend_from_slice(&data);
+        out.extend_from_slice(METADATA_MARKER);
+
+        let strings = |s: &[String]| s.iter().map(|s| s.as_str().into()).collect();
+        let description = self.description.iter();
+        let metadata = [
+            ("binary_format_major_version", MmdbValue::U16(2)),
+            ("binary_format_minor_version", MmdbValue::U16(0)),
+            ("build_epoch", MmdbValue::U64(self.build_epoch)),
+            ("database_type", self.database_type.as_str().into()),
+            (
+                "description",
+                MmdbValue::Map(
+                    description
+                        .map(|(k, v)| (k.clone(), v.as_str().into()))
+                        .collect(),
+                ),
+            ),
+            ("ip_version", MmdbValue::U16(self.ip_version)),
+            ("languages", MmdbValue::Array(strings(&self.languages))),
+            ("node_count", MmdbValue::U32(node_count as u32)),
+            ("record_size", MmdbValue::U16(record_size as u16)),
+        ];
+        let metadata = metadata.into_iter().map(|(k, v)| (k.to_string(), v));
+        encode(&mut out, &MmdbValue::Map(metadata.collect()));
+        Ok(out)
+    }
+}
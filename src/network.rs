@@ -0,0 +1,348 @@
+use crate::{IpAddr, Ipv4Addr, Ipv4Mask, Ipv6Addr, Ipv6Mask, MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use core::str::FromStr;
+
+/// An IPv4 network, such as a route. Unlike [`MaskedIpv4`], which may hold any address within its
+/// network, such as an interface address, the host bits of an `Ipv4Network` are always zero.
+///
+/// Converting from a [`MaskedIpv4`] with `TryFrom` or parsing fails if host bits are set, so a
+/// typo such as `10.0.0.5/8` is caught. Use [`Ipv4Network::truncate`] or
+/// [`Ipv4Network::parse_truncate`] to clear the host bits instead.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Ipv4Network(MaskedIpv4);
+
+/// An IPv6 network, such as a route. Unlike [`MaskedIpv6`], the host bits are always zero.
+///
+/// See [`Ipv4Network`] for the conversions.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Ipv6Network(MaskedIpv6);
+
+/// An enum which may represent either a V4 or V6 network.
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub enum IpNetwork {
+    /// An [`Ipv4Network`]
+    V4(Ipv4Network),
+    /// An [`Ipv6Network`]
+    V6(Ipv6Network),
+}
+
+/// Error when converting a masked IP with host bits set into a network.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct HostBitsSet;
+
+/// Error when failing to parse a network.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum InvalidNetwork {
+    /// The text is not an address and prefix length or mask.
+    Syntax,
+    /// The text is a valid masked IP, but has host bits set.
+    HostBitsSet,
+}
+
+impl Display for HostBitsSet {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str("host bits are set")
+    }
+}
+
+impl Display for InvalidNetwork {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Syntax => f.write_str("invalid network syntax"),
+            Self::HostBitsSet => f.write_str("host bits are set"),
+        }
+    }
+}
+
+impl From<HostBitsSet> for InvalidNetwork {
+    fn from(_: HostBitsSet) -> Self {
+        Self::HostBitsSet
+    }
+}
+
+impl Ipv4Network {
+    /// Constructs a network from its address and mask, failing if host bits are set.
+    pub const fn new(ip: Ipv4Addr, mask: Ipv4Mask) -> Result<Self, HostBitsSet> {
+        let masked = MaskedIpv4::new(ip, mask);
+        if masked.ip.to_bits() == masked.network_address().to_bits() {
+            Ok(Self(masked))
+        } else {
+            Err(HostBitsSet)
+        }
+    }
+    /// Constructs a network from its address and prefix length, failing if host bits are set.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if provided length > 32
+    pub const fn cidr(ip: Ipv4Addr, mask_len: u8) -> Result<Self, HostBitsSet> {
+        Self::new(ip, Ipv4Mask::new(mask_len))
+    }
+    /// Constructs a network from any address within it, clearing the host bits.
+    pub const fn truncate(masked: MaskedIpv4) -> Self {
+        Self(masked.network())
+    }
+    /// Parses a network like [`FromStr`], but clears host bits instead of failing.
+    pub fn parse_truncate(s: &str) -> Result<Self, InvalidNetwork> {
+        let masked = s.parse().map_err(|_| InvalidNetwork::Syntax)?;
+        Ok(Self::truncate(masked))
+    }
+    /// Returns the network address.
+    pub const fn address(&self) -> Ipv4Addr {
+        self.0.ip
+    }
+    /// Returns the subnet mask.
+    pub const fn mask(&self) -> Ipv4Mask {
+        self.0.mask
+    }
+    /// Returns the prefix length.
+    pub const fn network_bits(&self) -> u8 {
+        self.0.network_bits()
+    }
+    /// Returns the network as a [`MaskedIpv4`] with the network address.
+    pub const fn to_masked(&self) -> MaskedIpv4 {
+        self.0
+    }
+    /// Returns true if the address is within this network.
+    pub const fn contains(&self, ip: Ipv4Addr) -> bool {
+        self.0.contains(ip)
+    }
+}
+
+impl Ipv6Network {
+    /// Constructs a network from its address and mask, failing if host bits are set.
+    pub const fn new(ip: Ipv6Addr, mask: Ipv6Mask) -> Result<Self, HostBitsSet> {
+        let masked = MaskedIpv6::new(ip, mask);
+        if masked.ip.to_bits() == masked.network_address().to_bits() {
+            Ok(Self(masked))
+        } else {
+            Err(HostBitsSet)
+        }
+    }
+    /// Constructs a network from its address and prefix length, failing if host bits are set.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if provided length > 128
+    pub const fn cidr(ip: Ipv6Addr, mask_len: u8) -> Result<Self, HostBitsSet> {
+        Self::new(ip, Ipv6Mask::new(mask_len))
+    }
+    /// Constructs a network from any address within it, clearing the host bits.
+    pub const fn truncate(masked: MaskedIpv6) -> Self {
+        Self(masked.network())
+    }
+    /// Parses a network like [`FromStr`], but clears host bits instead of failing.
+    pub fn parse_truncate(s: &str) -> Result<Self, InvalidNetwork> {
+        let masked = s.parse().map_err(|_| InvalidNetwork::Syntax)?;
+        Ok(Self::truncate(masked))
+    }
+    /// Returns the network address.
+    pub const fn address(&self) -> Ipv6Addr {
+        self.0.ip
+    }
+    /// Returns the subnet mask.
+    pub const fn mask(&self) -> Ipv6Mask {
+        self.0.mask
+    }
+    /// Returns the prefix length.
+    pub const fn network_bits(&self) -> u8 {
+        self.0.network_bits()
+    }
+    /// Returns the network as a [`MaskedIpv6`] with the network address.
+    pub const fn to_masked(&self) -> MaskedIpv6 {
+        self.0
+    }
+    /// Returns true if the address is within this network.
+    pub const fn contains(&self, ip: Ipv6Addr) -> bool {
+        self.0.contains(ip)
+    }
+}
+
+impl IpNetwork {
+    /// Constructs a network from any address within it, clearing the host bits.
+    pub const fn truncate(masked: MaskedIp) -> Self {
+        match masked {
+            MaskedIp::V4(m) => Self::V4(Ipv4Network::truncate(m)),
+            MaskedIp::V6(m) => Self::V6(Ipv6Network::truncate(m)),
+        }
+    }
+    /// Parses a network like [`FromStr`], but clears host bits instead of failing.
+    pub fn parse_truncate(s: &str) -> Result<Self, InvalidNetwork> {
+        let masked = s.parse().map_err(|_| InvalidNetwork::Syntax)?;
+        Ok(Self::truncate(masked))
+    }
+    /// Returns the network address.
+    pub const fn address(&self) -> IpAddr {
+        match self {
+            Self::V4(n) => IpAddr::V4(n.address()),
+            Self::V6(n) => IpAddr::V6(n.address()),
+        }
+    }
+    /// Returns the prefix length.
+    pub const fn network_bits(&self) -> u8 {
+        match self {
+            Self::V4(n) => n.network_bits(),
+            Self::V6(n) => n.network_bits(),
+        }
+    }
+    /// Returns the network as a [`MaskedIp`] with the network address.
+    pub const fn to_masked(&self) -> MaskedIp {
+        match self {
+            Self::V4(n) => MaskedIp::V4(n.to_masked()),
+            Self::V6(n) => MaskedIp::V6(n.to_masked()),
+        }
+    }
+    /// Returns true if the address is within this network.
+    pub const fn contains(&self, ip: IpAddr) -> bool {
+        self.to_masked().contains(ip)
+    }
+}
+
+impl TryFrom<MaskedIpv4> for Ipv4Network {
+    type Error = HostBitsSet;
+    /// Fails if host bits are set. Use [`Ipv4Network::truncate`] to clear them instead.
+    fn try_from(masked: MaskedIpv4) -> Result<Self, HostBitsSet> {
+        Self::new(masked.ip, masked.mask)
+    }
+}
+
+impl TryFrom<MaskedIpv6> for Ipv6Network {
+    type Error = HostBitsSet;
+    /// Fails if host bits are set. Use [`Ipv6Network::truncate`] to clear them instead.
+    fn try_from(masked: MaskedIpv6) -> Result<Self, HostBitsSet> {
+        Self::new(masked.ip, masked.mask)
+    }
+}
+
+impl TryFrom<MaskedIp> for IpNetwork {
+    type Error = HostBitsSet;
+    /// Fails if host bits are set. Use [`IpNetwork::truncate`] to clear them instead.
+    fn try_from(masked: MaskedIp) -> Result<Self, HostBitsSet> {
+        match masked {
+            MaskedIp::V4(m) => m.try_into().map(Self::V4),
+            MaskedIp::V6(m) => m.try_into().map(Self::V6),
+        }
+    }
+}
+
+impl From<Ipv4Network> for MaskedIpv4 {
+    fn from(network: Ipv4Network) -> Self {
+        network.to_masked()
+    }
+}
+
+impl From<Ipv6Network> for MaskedIpv6 {
+    fn from(network: Ipv6Network) -> Self {
+        network.to_masked()
+    }
+}
+
+impl From<IpNetwork> for MaskedIp {
+    fn from(network: IpNetwork) -> Self {
+        network.to_masked()
+    }
+}
+
+impl From<Ipv4Network> for IpNetwork {
+    fn from(network: Ipv4Network) -> Self {
+        Self::V4(network)
+    }
+}
+
+impl From<Ipv6Network> for IpNetwork {
+    fn from(network: Ipv6Network) -> Self {
+        Self::V6(network)
+    }
+}
+
+impl From<Ipv4Addr> for Ipv4Network {
+    /// Converts an address into a /32 network.
+    fn from(ip: Ipv4Addr) -> Self {
+        Self(ip.into())
+    }
+}
+
+impl From<Ipv6Addr> for Ipv6Network {
+    /// Converts an address into a /128 network.
+    fn from(ip: Ipv6Addr) -> Self {
+        Self(ip.into())
+    }
+}
+
+impl From<IpAddr> for IpNetwork {
+    /// Converts an address into a /32 or /128 network.
+    fn from(ip: IpAddr) -> Self {
+        match ip {
+            IpAddr::V4(ip) => Self::V4(ip.into()),
+            IpAddr::V6(ip) => Self::V6(ip.into()),
+        }
+    }
+}
+
+impl FromStr for Ipv4Network {
+    type Err = InvalidNetwork;
+    /// Parses a network in the formats accepted by [`MaskedIpv4`], failing if host bits are set.
+    fn from_str(s: &str) -> Result<Self, InvalidNetwork> {
+        let masked: MaskedIpv4 = s.parse().map_err(|_| InvalidNetwork::Syntax)?;
+        Ok(masked.try_into()?)
+    }
+}
+
+impl FromStr for Ipv6Network {
+    type Err = InvalidNetwork;
+    /// Parses a network in the formats accepted by [`MaskedIpv6`], failing if host bits are set.
+    fn from_str(s: &str) -> Result<Self, InvalidNetwork> {
+        let masked: MaskedIpv6 = s.parse().map_err(|_| InvalidNetwork::Syntax)?;
+        Ok(masked.try_into()?)
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = InvalidNetwork;
+    /// Parses a network in the formats accepted by [`MaskedIp`], failing if host bits are set.
+    fn from_str(s: &str) -> Result<Self, InvalidNetwork> {
+        let masked: MaskedIp = s.parse().map_err(|_| InvalidNetwork::Syntax)?;
+        Ok(masked.try_into()?)
+    }
+}
+
+/// Networks are always written in CIDR format, such as `10.0.0.0/8`.
+impl Display for Ipv4Network {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}/{}", self.0.ip, self.0.network_bits())
+    }
+}
+
+impl Display for Ipv6Network {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl Display for IpNetwork {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::V4(n) => Display::fmt(n, f),
+            Self::V6(n) => Display::fmt(n, f),
+        }
+    }
+}
+
+impl Debug for Ipv4Network {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+impl Debug for Ipv6Network {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+impl Debug for IpNetwork {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
@@ -0,0 +1,563 @@
+//! Const parsers. These accept the same syntax as the `FromStr` implementations, but can be
+//! evaluated at compile time, and back the `parse_ascii` and `scan_ascii` methods for parsing
+//! byte slices without UTF-8 validation. Each parser works on `b[start..end]` of a byte slice,
+//! since slicing is not yet available in const fns.
+
+use crate::{
+    InvalidIpv4Mask, InvalidIpv6Mask, InvalidMaskedIp, InvalidMaskedIpv4, InvalidMaskedIpv6,
+    IpAddr, Ipv4Addr, Ipv4Mask, Ipv6Addr, Ipv6Mask, MaskedIp, MaskedIpv4, MaskedIpv6,
+};
+
+use core::net::{
+    AddrParseError, IpAddr as StdIpAddr, Ipv4Addr as StdIpv4Addr, Ipv6Addr as StdIpv6Addr,
+};
+use core::str::FromStr;
+
+/// Parses a decimal number of at most `max_digits` digits starting at `i`, returning the value
+/// and the index after the last digit. Stops early at the first non-digit.
+const fn decimal(b: &[u8], mut i: usize, end: usize, max_digits: usize) -> Option<(u32, usize)> {
+    let start = i;
+    let mut value = 0u32;
+    while i < end && i - start < max_digits && b[i].is_ascii_digit() {
+        value = value * 10 + (b[i] - b'0') as u32;
+        i += 1;
+    }
+    if i == start { None } else { Some((value, i)) }
+}
+
+/// Parses up to 4 hex digits starting at `i`.
+const fn hex_group(b: &[u8], mut i: usize, end: usize) -> Option<(u16, usize)> {
+    let start = i;
+    let mut value = 0u16;
+    while i < end && i - start < 4 {
+        let digit = match b[i] {
+            c @ b'0'..=b'9' => c - b'0',
+            c @ b'a'..=b'f' => c - b'a' + 10,
+            c @ b'A'..=b'F' => c - b'A' + 10,
+            _ => break,
+        };
+        value = value << 4 | digit as u16;
+        i += 1;
+    }
+    if i == start { None } else { Some((value, i)) }
+}
+
+/// Returns the index of the first occurrence of `c` in `b[start..end]`, or `end`.
+const fn find(b: &[u8], c: u8, start: usize, end: usize) -> usize {
+    let mut i = start;
+    while i < end && b[i] != c {
+        i += 1;
+    }
+    i
+}
+
+/// Parses a prefix length of at most `max` starting at `i`, returning it and the index after the
+/// last digit. Like `u8::from_str`, a leading `+` and any number of leading zeros are accepted.
+const fn prefix_digits(b: &[u8], mut i: usize, end: usize, max: u8) -> Option<(u8, usize)> {
+    if i < end && b[i] == b'+' {
+        i += 1;
+    }
+    let start = i;
+    let mut len = 0u32;
+    while i < end && b[i].is_ascii_digit() {
+        len = len * 10 + (b[i] - b'0') as u32;
+        if len > max as u32 {
+            return None;
+        }
+        i += 1;
+    }
+    if i == start {
+        None
+    } else {
+        Some((len as u8, i))
+    }
+}
+
+/// Parses a prefix length of at most `max`.
+pub(crate) const fn prefix_len(b: &[u8], start: usize, end: usize, max: u8) -> Option<u8> {
+    match prefix_digits(b, start, end, max) {
+        Some((len, i)) if i == end => Some(len),
+        _ => None,
+    }
+}
+
+/// Parses a dotted-decimal IPv4 address. Like `core::net`, octets with leading zeros are rejected.
+pub(crate) const fn ipv4(b: &[u8], start: usize, end: usize) -> Option<Ipv4Addr> {
+    let mut octets = [0u8; 4];
+    let mut i = start;
+    let mut n = 0;
+    while n < 4 {
+        if n > 0 {
+            if i >= end || b[i] != b'.' {
+                return None;
+            }
+            i += 1;
+        }
+        let Some((octet, next)) = decimal(b, i, end, 3) else {
+            return None;
+        };
+        if octet > 255 || (next - i > 1 && b[i] == b'0') {
+            return None;
+        }
+        octets[n] = octet as u8;
+        i = next;
+        n += 1;
+    }
+    if i == end {
+        Some(Ipv4Addr::from_octets(octets))
+    } else {
+        None
+    }
+}
+
+/// Parses an IPv6 address, including `::` compression and a trailing embedded IPv4 address.
+pub(crate) const fn ipv6(b: &[u8], start: usize, end: usize) -> Option<Ipv6Addr> {
+    // Groups before the `::` go in head, and groups after it go in tail.
+    let mut head = [0u16; 8];
+    let mut tail = [0u16; 8];
+    let (mut head_len, mut tail_len) = (0, 0);
+    let mut compressed = false;
+    let mut i = start;
+    if end - start >= 2 && b[i] == b':' && b[i + 1] == b':' {
+        compressed = true;
+        i += 2;
+    }
+    while i < end {
+        let count = head_len + tail_len;
+        let group_end = find(b, b':', i, end);
+        if find(b, b'.', i, group_end) < group_end {
+            // An embedded IPv4 address must be the last thing in the address.
+            if group_end != end || count + 2 > if compressed { 7 } else { 8 } {
+                return None;
+            }
+            let Some(v4) = ipv4(b, i, end) else {
+                return None;
+            };
+            let octets = v4.octets();
+            let groups = [
+                (octets[0] as u16) << 8 | octets[1] as u16,
+                (octets[2] as u16) << 8 | octets[3] as u16,
+            ];
+            let mut k = 0;
+            while k < 2 {
+                if compressed {
+                    tail[tail_len] = groups[k];
+                    tail_len += 1;
+                } else {
+                    head[head_len] = groups[k];
+                    head_len += 1;
+                }
+                k += 1;
+            }
+            break;
+        }
+        if count == 8 {
+            return None;
+        }
+        let Some((group, next)) = hex_group(b, i, end) else {
+            return None;
+        };
+        if next != group_end {
+            return None;
+        }
+        if compressed {
+            tail[tail_len] = group;
+            tail_len += 1;
+        } else {
+            head[head_len] = group;
+            head_len += 1;
+        }
+        i = next;
+        if i == end {
+            break;
+        }
+        // Skip the separator. A second colon starts the compressed run.
+        i += 1;
+        if i < end && b[i] == b':' {
+            if compressed {
+                return None;
+            }
+            compressed = true;
+            i += 1;
+        } else if i == end {
+            return None;
+        }
+    }
+    let count = head_len + tail_len;
+    if (compressed && count > 7) || (!compressed && count != 8) {
+        return None;
+    }
+    let mut segments = head;
+    let mut k = 0;
+    while k < tail_len {
+        segments[8 - tail_len + k] = tail[k];
+        k += 1;
+    }
+    Some(Ipv6Addr::from_segments(segments))
+}
+
+/// Parses an IPv4 mask as `/len` or in dotted-decimal.
+pub(crate) const fn ipv4_mask(b: &[u8], start: usize, end: usize) -> Option<Ipv4Mask> {
+    if start < end && b[start] == b'/' {
+        match prefix_len(b, start + 1, end, 32) {
+            Some(len) => Some(Ipv4Mask::new(len)),
+            None => None,
+        }
+    } else {
+        match ipv4(b, start, end) {
+            Some(ip) => Ipv4Mask::from_bits(ip.to_bits()),
+            None => None,
+        }
+    }
+}
+
+/// Parses an IPv6 mask as `/len` or as an address.
+pub(crate) const fn ipv6_mask(b: &[u8], start: usize, end: usize) -> Option<Ipv6Mask> {
+    if start < end && b[start] == b'/' {
+        match prefix_len(b, start + 1, end, 128) {
+            Some(len) => Some(Ipv6Mask::new(len)),
+            None => None,
+        }
+    } else {
+        match ipv6(b, start, end) {
+            Some(ip) => Ipv6Mask::from_bits(ip.to_bits()),
+            None => None,
+        }
+    }
+}
+
+/// Parses a MaskedIpv4 as `ip/len` or `ip mask`, separated by exactly one space.
+pub(crate) const fn masked_ipv4(b: &[u8], start: usize, end: usize) -> Option<MaskedIpv4> {
+    let mut split = start;
+    while split < end && b[split] != b'/' && b[split] != b' ' {
+        split += 1;
+    }
+    if split == end {
+        return None;
+    }
+    let Some(ip) = ipv4(b, start, split) else {
+        return None;
+    };
+    let mask = if b[split] == b'/' {
+        match prefix_len(b, split + 1, end, 32) {
+            Some(len) => Ipv4Mask::new(len),
+            None => return None,
+        }
+    } else {
+        match ipv4(b, split + 1, end) {
+            Some(mask) => match Ipv4Mask::from_bits(mask.to_bits()) {
+                Some(mask) => mask,
+                None => return None,
+            },
+            None => return None,
+        }
+    };
+    Some(MaskedIpv4::new(ip, mask))
+}
+
+/// Parses a MaskedIpv6 as `ip/len`.
+pub(crate) const fn masked_ipv6(b: &[u8], start: usize, end: usize) -> Option<MaskedIpv6> {
+    let split = find(b, b'/', start, end);
+    if split == end {
+        return None;
+    }
+    match (ipv6(b, start, split), prefix_len(b, split + 1, end, 128)) {
+        (Some(ip), Some(len)) => Some(MaskedIpv6::cidr(ip, len)),
+        _ => None,
+    }
+}
+
+/// Returns true if the address in `b[start..end]` looks like IPv6. That is, it has a colon
+/// before any dot, slash or space.
+const fn looks_like_v6(b: &[u8], start: usize, end: usize) -> bool {
+    let mut i = start;
+    while i < end {
+        match b[i] {
+            b':' => return true,
+            b'.' | b'/' | b' ' => return false,
+            _ => i += 1,
+        }
+    }
+    false
+}
+
+/// Returns the error `T`'s `FromStr` gives for invalid syntax. `AddrParseError` can only be
+/// created by the standard library, so `parse_ascii` borrows one from parsing an empty string.
+fn addr_parse_error<T: FromStr<Err = AddrParseError>>() -> AddrParseError {
+    match "".parse::<T>() {
+        Err(e) => e,
+        Ok(_) => unreachable!(),
+    }
+}
+
+/// The longest IPv4 and IPv6 addresses, such as `255.255.255.255` and
+/// `ffff:ffff:ffff:ffff:ffff:ffff:255.255.255.255`.
+const MAX_IPV4_LEN: usize = 15;
+const MAX_IPV6_LEN: usize = 45;
+
+/// Returns the length of the run of bytes at the start of `b` which may be part of an address,
+/// up to `max`.
+const fn address_run(b: &[u8], v6: bool, max: usize) -> usize {
+    let mut i = 0;
+    while i < b.len() && i < max {
+        match b[i] {
+            b'0'..=b'9' | b'.' => {}
+            b'a'..=b'f' | b'A'..=b'F' | b':' if v6 => {}
+            _ => break,
+        }
+        i += 1;
+    }
+    i
+}
+
+/// Returns true if `end` is the end of `b`, or is not followed by a letter or digit.
+const fn at_boundary(b: &[u8], end: usize) -> bool {
+    end == b.len() || !b[end].is_ascii_alphanumeric()
+}
+
+/// Parses the longest IPv4 address at the start of `b`, returning it and its length.
+const fn scan_ipv4(b: &[u8]) -> Option<(Ipv4Addr, usize)> {
+    let mut end = address_run(b, false, MAX_IPV4_LEN);
+    while end > 0 {
+        if at_boundary(b, end)
+            && let Some(ip) = ipv4(b, 0, end)
+        {
+            return Some((ip, end));
+        }
+        end -= 1;
+    }
+    None
+}
+
+/// Parses the longest IPv6 address at the start of `b`, returning it and its length.
+const fn scan_ipv6(b: &[u8]) -> Option<(Ipv6Addr, usize)> {
+    let mut end = address_run(b, true, MAX_IPV6_LEN);
+    while end > 0 {
+        if at_boundary(b, end)
+            && let Some(ip) = ipv6(b, 0, end)
+        {
+            return Some((ip, end));
+        }
+        end -= 1;
+    }
+    None
+}
+
+/// Parses a `/len` prefix length of at most `max` starting at `i`, returning it and the index
+/// after it.
+const fn scan_prefix_len(b: &[u8], i: usize, max: u8) -> Option<(u8, usize)> {
+    if i >= b.len() || b[i] != b'/' {
+        return None;
+    }
+    match prefix_digits(b, i + 1, b.len(), max) {
+        Some((len, end)) if at_boundary(b, end) => Some((len, end)),
+        _ => None,
+    }
+}
+
+impl Ipv4Addr {
+    /// Parses an address in dotted-decimal notation, returning None if it is invalid. Unlike
+    /// `FromStr`, this can be evaluated at compile time. See also [`ipv4!`](crate::ipv4!).
+    pub const fn from_str_const(s: &str) -> Option<Self> {
+        ipv4(s.as_bytes(), 0, s.len())
+    }
+}
+
+impl Ipv6Addr {
+    /// Parses an address, returning None if it is invalid. Unlike `FromStr`, this can be evaluated
+    /// at compile time. See also [`ipv6!`](crate::ipv6!).
+    pub const fn from_str_const(s: &str) -> Option<Self> {
+        ipv6(s.as_bytes(), 0, s.len())
+    }
+}
+
+impl IpAddr {
+    /// Parses an IPv4 or IPv6 address, returning None if it is invalid. Unlike `FromStr`, this can
+    /// be evaluated at compile time. See also [`ip!`](crate::ip!).
+    pub const fn from_str_const(s: &str) -> Option<Self> {
+        let b = s.as_bytes();
+        if looks_like_v6(b, 0, b.len()) {
+            match ipv6(b, 0, b.len()) {
+                Some(ip) => Some(Self::V6(ip)),
+                None => None,
+            }
+        } else {
+            match ipv4(b, 0, b.len()) {
+                Some(ip) => Some(Self::V4(ip)),
+                None => None,
+            }
+        }
+    }
+}
+
+impl Ipv4Mask {
+    /// Parses a mask as `/len` or in dotted-decimal, returning None if it is invalid. Unlike
+    /// `FromStr`, this can be evaluated at compile time.
+    pub const fn from_str_const(s: &str) -> Option<Self> {
+        ipv4_mask(s.as_bytes(), 0, s.len())
+    }
+}
+
+impl Ipv6Mask {
+    /// Parses a mask as `/len` or as an address, returning None if it is invalid. Unlike
+    /// `FromStr`, this can be evaluated at compile time.
+    pub const fn from_str_const(s: &str) -> Option<Self> {
+        ipv6_mask(s.as_bytes(), 0, s.len())
+    }
+}
+
+impl MaskedIpv4 {
+    /// Parses `ip/len` or `ip mask`, returning None if it is invalid. Unlike `FromStr`, this can be
+    /// evaluated at compile time. See also [`cidr4!`](crate::cidr4!).
+    pub const fn from_str_const(s: &str) -> Option<Self> {
+        masked_ipv4(s.as_bytes(), 0, s.len())
+    }
+}
+
+impl MaskedIpv6 {
+    /// Parses `ip/len`, returning None if it is invalid. Unlike `FromStr`, this can be evaluated at
+    /// compile time. See also [`cidr6!`](crate::cidr6!).
+    pub const fn from_str_const(s: &str) -> Option<Self> {
+        masked_ipv6(s.as_bytes(), 0, s.len())
+    }
+}
+
+impl MaskedIp {
+    /// Parses a [`MaskedIpv4`] or [`MaskedIpv6`], returning None if it is invalid. Unlike `FromStr`,
+    /// this can be evaluated at compile time. See also [`cidr!`](crate::cidr!).
+    pub const fn from_str_const(s: &str) -> Option<Self> {
+        let b = s.as_bytes();
+        if looks_like_v6(b, 0, b.len()) {
+            match masked_ipv6(b, 0, b.len()) {
+                Some(m) => Some(Self::V6(m)),
+                None => None,
+            }
+        } else {
+            match masked_ipv4(b, 0, b.len()) {
+                Some(m) => Some(Self::V4(m)),
+                None => None,
+            }
+        }
+    }
+}
+
+impl Ipv4Addr {
+    /// Parses an address in dotted-decimal notation from ASCII bytes, without UTF-8 validation.
+    pub fn parse_ascii(b: &[u8]) -> Result<Self, AddrParseError> {
+        ipv4(b, 0, b.len()).ok_or_else(addr_parse_error::<StdIpv4Addr>)
+    }
+    /// Parses the address at the start of `b`, returning it and the number of bytes consumed. The
+    /// longest address is taken, and it must not be followed by a letter or digit, so
+    /// `192.0.2.1:80` gives `192.0.2.1` but `192.0.2.123` is never cut short.
+    pub const fn scan_ascii(b: &[u8]) -> Option<(Self, usize)> {
+        scan_ipv4(b)
+    }
+}
+
+impl Ipv6Addr {
+    /// Parses an address from ASCII bytes, without UTF-8 validation.
+    pub fn parse_ascii(b: &[u8]) -> Result<Self, AddrParseError> {
+        ipv6(b, 0, b.len()).ok_or_else(addr_parse_error::<StdIpv6Addr>)
+    }
+    /// Parses the address at the start of `b`, returning it and the number of bytes consumed. See
+    /// [`Ipv4Addr::scan_ascii`].
+    pub const fn scan_ascii(b: &[u8]) -> Option<(Self, usize)> {
+        scan_ipv6(b)
+    }
+}
+
+impl IpAddr {
+    /// Parses an IPv4 or IPv6 address from ASCII bytes, without UTF-8 validation.
+    pub fn parse_ascii(b: &[u8]) -> Result<Self, AddrParseError> {
+        if looks_like_v6(b, 0, b.len()) {
+            ipv6(b, 0, b.len()).map(Self::V6)
+        } else {
+            ipv4(b, 0, b.len()).map(Self::V4)
+        }
+        .ok_or_else(addr_parse_error::<StdIpAddr>)
+    }
+    /// Parses the IPv4 or IPv6 address at the start of `b`, returning it and the number of bytes
+    /// consumed. See [`Ipv4Addr::scan_ascii`].
+    pub const fn scan_ascii(b: &[u8]) -> Option<(Self, usize)> {
+        match (scan_ipv4(b), scan_ipv6(b)) {
+            (Some((v4, n4)), Some((_, n6))) if n4 >= n6 => Some((Self::V4(v4), n4)),
+            (_, Some((v6, n6))) => Some((Self::V6(v6), n6)),
+            (Some((v4, n4)), None) => Some((Self::V4(v4), n4)),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Ipv4Mask {
+    /// Parses a mask as `/len` or in dotted-decimal from ASCII bytes, without UTF-8 validation.
+    pub fn parse_ascii(b: &[u8]) -> Result<Self, InvalidIpv4Mask> {
+        ipv4_mask(b, 0, b.len()).ok_or(InvalidIpv4Mask)
+    }
+}
+
+impl Ipv6Mask {
+    /// Parses a mask as `/len` or as an address from ASCII bytes, without UTF-8 validation.
+    pub fn parse_ascii(b: &[u8]) -> Result<Self, InvalidIpv6Mask> {
+        ipv6_mask(b, 0, b.len()).ok_or(InvalidIpv6Mask)
+    }
+}
+
+impl MaskedIpv4 {
+    /// Parses `ip/len` or `ip mask` from ASCII bytes, without UTF-8 validation.
+    pub fn parse_ascii(b: &[u8]) -> Result<Self, InvalidMaskedIpv4> {
+        masked_ipv4(b, 0, b.len()).ok_or(InvalidMaskedIpv4)
+    }
+    /// Parses `ip/len` at the start of `b`, returning it and the number of bytes consumed. The
+    /// `ip mask` form is not scanned, since a space usually ends the address in a log line. See
+    /// [`Ipv4Addr::scan_ascii`].
+    pub const fn scan_ascii(b: &[u8]) -> Option<(Self, usize)> {
+        let Some((ip, i)) = scan_ipv4(b) else {
+            return None;
+        };
+        match scan_prefix_len(b, i, 32) {
+            Some((len, end)) => Some((Self::cidr(ip, len), end)),
+            None => None,
+        }
+    }
+}
+
+impl MaskedIpv6 {
+    /// Parses `ip/len` from ASCII bytes, without UTF-8 validation.
+    pub fn parse_ascii(b: &[u8]) -> Result<Self, InvalidMaskedIpv6> {
+        masked_ipv6(b, 0, b.len()).ok_or(InvalidMaskedIpv6)
+    }
+    /// Parses `ip/len` at the start of `b`, returning it and the number of bytes consumed. See
+    /// [`Ipv4Addr::scan_ascii`].
+    pub const fn scan_ascii(b: &[u8]) -> Option<(Self, usize)> {
+        let Some((ip, i)) = scan_ipv6(b) else {
+            return None;
+        };
+        match scan_prefix_len(b, i, 128) {
+            Some((len, end)) => Some((Self::cidr(ip, len), end)),
+            None => None,
+        }
+    }
+}
+
+impl MaskedIp {
+    /// Parses a [`MaskedIpv4`] or [`MaskedIpv6`] from ASCII bytes, without UTF-8 validation.
+    pub fn parse_ascii(b: &[u8]) -> Result<Self, InvalidMaskedIp> {
+        if looks_like_v6(b, 0, b.len()) {
+            masked_ipv6(b, 0, b.len()).map(Self::V6)
+        } else {
+            masked_ipv4(b, 0, b.len()).map(Self::V4)
+        }
+        .ok_or(InvalidMaskedIp)
+    }
+    /// Parses `ip/len` for either family at the start of `b`, returning it and the number of bytes
+    /// consumed. See [`Ipv4Addr::scan_ascii`].
+    pub const fn scan_ascii(b: &[u8]) -> Option<(Self, usize)> {
+        match MaskedIpv4::scan_ascii(b) {
+            Some((m, n)) => Some((Self::V4(m), n)),
+            None => match MaskedIpv6::scan_ascii(b) {
+                Some((m, n)) => Some((Self::V6(m), n)),
+                None => None,
+            },
+        }
+    }
+}
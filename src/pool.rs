@@ -0,0 +1,291 @@
+use crate::{IpAddr, Ipv4Addr, Ipv6Addr, MaskedIp};
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The largest number of host bits an [`AddressPool`] will track. This bounds the pool's bitmaps to 2 MiB each.
+pub const MAX_POOL_HOST_BITS: u8 = 24;
+
+/// Error returned by [`AddressPool`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PoolError {
+    /// The network has more than [`MAX_POOL_HOST_BITS`] host bits.
+    TooLarge,
+    /// The address is not inside the pool's network, or is of the wrong family.
+    OutOfPool,
+    /// The start of an excluded range is after its end.
+    InvalidRange,
+    /// The address is the network address, broadcast address, gateway, or in an excluded range.
+    Reserved,
+    /// The address is already leased.
+    InUse,
+    /// The address being released is not leased.
+    NotAllocated,
+    /// A deserialized pool does not have the same number of addresses as its network, or leases reserved addresses.
+    InvalidState,
+}
+
+impl Display for PoolError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::TooLarge => "network is too large for an address pool",
+            Self::OutOfPool => "address is outside of the pool",
+            Self::InvalidRange => "range start is after range end",
+            Self::Reserved => "address is reserved",
+            Self::InUse => "address is already allocated",
+            Self::NotAllocated => "address is not allocated",
+            Self::InvalidState => "address pool state is inconsistent",
+        })
+    }
+}
+
+/// A pool of individual host addresses within a network, such as the lease pool of a DHCP server.
+///
+/// The network address, broadcast address, gateway and any excluded ranges are never handed out.
+/// For IPv4 networks with a mask length of 31 or 32, and IPv6 networks with a mask length of 127
+/// or 128, every address is usable, matching [`MaskedIpv4::is_network_address`](crate::MaskedIpv4::is_network_address).
+/// For other IPv6 networks, the subnet-router anycast (network) address is reserved.
+///
+/// Leases are tracked with one bit per address, so the network may have at most
+/// [`MAX_POOL_HOST_BITS`] host bits.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "PoolState", try_from = "PoolState")
+)]
+pub struct AddressPool {
+    network: MaskedIp,
+    gateway: Option<IpAddr>,
+    excluded: Vec<(IpAddr, IpAddr)>,
+    reserved: Vec<u64>,
+    leases: Vec<u64>,
+}
+
+/// The serialized form of an [`AddressPool`]. The reserved bitmap is rebuilt on deserialization.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PoolState {
+    network: MaskedIp,
+    gateway: Option<IpAddr>,
+    excluded: Vec<(IpAddr, IpAddr)>,
+    leases: Vec<u64>,
+}
+
+#[cfg(feature = "serde")]
+impl From<AddressPool> for PoolState {
+    fn from(pool: AddressPool) -> Self {
+        Self {
+            network: pool.network,
+            gateway: pool.gateway,
+            excluded: pool.excluded,
+            leases: pool.leases,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PoolState> for AddressPool {
+    type Error = PoolError;
+    fn try_from(state: PoolState) -> Result<Self, PoolError> {
+        let mut pool = Self::new(state.network)?;
+        if let Some(gateway) = state.gateway {
+            pool.set_gateway(gateway)?;
+        }
+        for (start, end) in state.excluded {
+            pool.exclude(start, end)?;
+        }
+        let overlaps = pool
+            .reserved
+            .iter()
+            .zip(&state.leases)
+            .any(|(r, l)| r & l != 0);
+        if state.leases.len() != pool.leases.len() || overlaps {
+            return Err(PoolError::InvalidState);
+        }
+        pool.leases = state.leases;
+        Ok(pool)
+    }
+}
+
+fn ip_bits(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(ip) => ip.to_bits() as u128,
+        IpAddr::V6(ip) => ip.to_bits(),
+    }
+}
+
+impl AddressPool {
+    /// Constructs an empty pool over the network of `network`.
+    pub fn new(network: impl Into<MaskedIp>) -> Result<Self, PoolError> {
+        let network = network.into().network();
+        if network.host_bits() > MAX_POOL_HOST_BITS {
+            return Err(PoolError::TooLarge);
+        }
+        let words = words(network.host_bits());
+        let mut pool = Self {
+            network,
+            gateway: None,
+            excluded: Vec::new(),
+            reserved: vec![0; words],
+            leases: vec![0; words],
+        };
+        pool.reserved = pool.build_reserved();
+        Ok(pool)
+    }
+    /// Returns the network this pool hands out addresses from.
+    pub fn network(&self) -> MaskedIp {
+        self.network
+    }
+    /// Returns the gateway address, if one has been set.
+    pub fn gateway(&self) -> Option<IpAddr> {
+        self.gateway
+    }
+    /// Returns the excluded ranges, as inclusive (start, end) pairs.
+    pub fn excluded(&self) -> &[(IpAddr, IpAddr)] {
+        &self.excluded
+    }
+    /// Sets the gateway address, which will never be handed out. Replaces any previous gateway.
+    ///
+    /// Fails if the gateway is outside of the pool, or is currently leased.
+    pub fn set_gateway(&mut self, gateway: IpAddr) -> Result<(), PoolError> {
+        self.offset(gateway)?;
+        let previous = self.gateway.replace(gateway);
+        self.update_reserved()
+            .inspect_err(|_| self.gateway = previous)
+    }
+    /// Excludes the inclusive range of addresses from `start` to `end` from being handed out.
+    ///
+    /// Fails if the range is not inside the pool, or contains a leased address.
+    pub fn exclude(&mut self, start: IpAddr, end: IpAddr) -> Result<(), PoolError> {
+        if self.offset(start)? > self.offset(end)? {
+            return Err(PoolError::InvalidRange);
+        }
+        self.excluded.push((start, end));
+        self.update_reserved().inspect_err(|_| {
+            self.excluded.pop();
+        })
+    }
+    /// Leases the lowest available address, or returns None if the pool is exhausted.
+    pub fn allocate(&mut self) -> Option<IpAddr> {
+        let (index, word) = self
+            .leases
+            .iter()
+            .zip(&self.reserved)
+            .map(|(l, r)| !(l | r))
+            .enumerate()
+            .find(|&(_, free)| free != 0)?;
+        let offset = index * 64 + word.trailing_zeros() as usize;
+        self.leases[index] |= 1 << (offset % 64);
+        Some(self.address(offset))
+    }
+    /// Leases the provided address.
+    pub fn allocate_specific(&mut self, ip: IpAddr) -> Result<(), PoolError> {
+        let offset = self.offset(ip)?;
+        let (index, bit) = (offset / 64, 1 << (offset % 64));
+        if self.reserved[index] & bit != 0 {
+            Err(PoolError::Reserved)
+        } else if self.leases[index] & bit != 0 {
+            Err(PoolError::InUse)
+        } else {
+            self.leases[index] |= bit;
+            Ok(())
+        }
+    }
+    /// Returns a leased address to the pool.
+    pub fn release(&mut self, ip: IpAddr) -> Result<(), PoolError> {
+        let offset = self.offset(ip)?;
+        let (index, bit) = (offset / 64, 1 << (offset % 64));
+        if self.leases[index] & bit == 0 {
+            return Err(PoolError::NotAllocated);
+        }
+        self.leases[index] &= !bit;
+        Ok(())
+    }
+    /// Returns true if the provided address is currently leased.
+    pub fn is_allocated(&self, ip: IpAddr) -> bool {
+        match self.offset(ip) {
+            Ok(offset) => self.leases[offset / 64] & (1 << (offset % 64)) != 0,
+            Err(_) => false,
+        }
+    }
+    /// Returns true if the provided address can never be leased.
+    pub fn is_reserved(&self, ip: IpAddr) -> bool {
+        match self.offset(ip) {
+            Ok(offset) => self.reserved[offset / 64] & (1 << (offset % 64)) != 0,
+            Err(_) => false,
+        }
+    }
+    /// Returns the number of addresses which are neither leased nor reserved.
+    pub fn available(&self) -> usize {
+        let free = self.leases.iter().zip(&self.reserved);
+        free.map(|(l, r)| (!(l | r)).count_ones() as usize).sum()
+    }
+    /// Returns an iterator over all leased addresses, in ascending order.
+    pub fn allocated(&self) -> impl Iterator<Item = IpAddr> + '_ {
+        self.leases
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, &word)| {
+                (0..64usize)
+                    .filter(move |bit| word & (1 << bit) != 0)
+                    .map(move |bit| self.address(index * 64 + bit))
+            })
+    }
+    fn offset(&self, ip: IpAddr) -> Result<usize, PoolError> {
+        if !self.network.contains(ip) {
+            return Err(PoolError::OutOfPool);
+        }
+        Ok((ip_bits(ip) - ip_bits(self.network.network_address())) as usize)
+    }
+    fn address(&self, offset: usize) -> IpAddr {
+        let bits = ip_bits(self.network.network_address()) + offset as u128;
+        match self.network {
+            MaskedIp::V4(_) => IpAddr::V4(Ipv4Addr::from_bits(bits as u32)),
+            MaskedIp::V6(_) => IpAddr::V6(Ipv6Addr::from_bits(bits)),
+        }
+    }
+    fn update_reserved(&mut self) -> Result<(), PoolError> {
+        let reserved = self.build_reserved();
+        if reserved.iter().zip(&self.leases).any(|(r, l)| r & l != 0) {
+            return Err(PoolError::InUse);
+        }
+        self.reserved = reserved;
+        Ok(())
+    }
+    fn build_reserved(&self) -> Vec<u64> {
+        let size = 1usize << self.network.host_bits();
+        let mut reserved = vec![0u64; words(self.network.host_bits())];
+        let mut set = |offset: usize| reserved[offset / 64] |= 1 << (offset % 64);
+        // Bits past the end of a small network are never usable.
+        (size..bitmap_len(size)).for_each(&mut set);
+        let network_reserved = match self.network {
+            MaskedIp::V4(m) => m.network_bits() <= 30,
+            MaskedIp::V6(m) => m.network_bits() <= 126,
+        };
+        if network_reserved {
+            set(0);
+        }
+        if matches!(self.network, MaskedIp::V4(m) if m.network_bits() <= 30) {
+            set(size - 1);
+        }
+        let offset = |ip| (ip_bits(ip) - ip_bits(self.network.network_address())) as usize;
+        if let Some(gateway) = self.gateway {
+            set(offset(gateway));
+        }
+        for &(start, end) in &self.excluded {
+            (offset(start)..=offset(end)).for_each(&mut set);
+        }
+        reserved
+    }
+}
+
+fn words(host_bits: u8) -> usize {
+    bitmap_len(1 << host_bits) / 64
+}
+
+/// Rounds the number of addresses up to a whole number of bitmap words.
+fn bitmap_len(size: usize) -> usize {
+    size.div_ceil(64) * 64
+}
@@ -0,0 +1,303 @@
+//! The PostgreSQL `inet` and `cidr` wire formats, and `postgres-types` support.
+//!
+//! An `inet` is an address and the prefix length of its network, so it maps onto a [`MaskedIp`]
+//! with host bits allowed. A `cidr` is a network, and may not have host bits set. Both
+//! [`ToSql`] and [`FromSql`] are implemented for [`MaskedIpv4`], [`MaskedIpv6`], [`MaskedIp`]
+//! and [`IpAddr`], accepting either column type.
+//!
+//! The binary format is the family (2 for IPv4, 3 for IPv6), the prefix length, 1 for a `cidr`
+//! or 0 for an `inet`, the length of the address, and the address.
+
+use crate::{IpAddr, Ipv4Addr, Ipv6Addr, MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use bytes::BytesMut;
+use core::error::Error;
+use core::fmt::{Display, Formatter, Result as FmtResult, Write};
+use postgres_types::{FromSql, IsNull, ToSql, Type, WrongType, accepts};
+
+const PGSQL_AF_INET: u8 = 2;
+const PGSQL_AF_INET6: u8 = 3;
+
+/// The PostgreSQL type of a network value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PgNetType {
+    /// `inet`, an address with the prefix length of its network
+    Inet,
+    /// `cidr`, a network without host bits
+    Cidr,
+}
+
+/// Error when encoding or decoding an `inet` or `cidr` value.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PgNetError {
+    /// The binary value is too short, or its address length does not match its family.
+    InvalidLength,
+    /// The binary family is not IPv4 or IPv6.
+    InvalidFamily(u8),
+    /// The prefix length is too long for the family.
+    InvalidPrefixLength(u8),
+    /// A `cidr` has bits set to the right of its mask.
+    HostBits,
+    /// The text is not a valid address with an optional prefix length.
+    InvalidText,
+    /// The value has a different family from the Rust type.
+    WrongFamily,
+    /// An [`IpAddr`] was read from a value with a prefix shorter than a single host.
+    NotHost,
+}
+
+impl Display for PgNetError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::InvalidLength => f.write_str("invalid inet length"),
+            Self::InvalidFamily(family) => write!(f, "invalid inet family {family}"),
+            Self::InvalidPrefixLength(len) => write!(f, "invalid inet prefix length {len}"),
+            Self::HostBits => f.write_str("cidr has bits set to the right of the mask"),
+            Self::InvalidText => f.write_str("invalid inet text"),
+            Self::WrongFamily => f.write_str("inet has the wrong address family"),
+            Self::NotHost => f.write_str("inet is a network, not a single address"),
+        }
+    }
+}
+
+fn check_cidr(net: &MaskedIp, ty: PgNetType) -> Result<(), PgNetError> {
+    match ty {
+        PgNetType::Cidr if *net != net.network() => Err(PgNetError::HostBits),
+        _ => Ok(()),
+    }
+}
+
+/// Encodes a value in the binary format.
+///
+/// # Errors
+/// Fails with [`PgNetError::HostBits`] for a `cidr` with host bits set.
+pub fn to_binary(net: MaskedIp, ty: PgNetType) -> Result<Vec<u8>, PgNetError> {
+    check_cidr(&net, ty)?;
+    let is_cidr = (ty == PgNetType::Cidr) as u8;
+    let mut out = Vec::with_capacity(20);
+    match net {
+        MaskedIp::V4(net) => {
+            out.extend_from_slice(&[PGSQL_AF_INET, net.network_bits(), is_cidr, 4]);
+            out.extend_from_slice(&net.ip.octets());
+        }
+        MaskedIp::V6(net) => {
+            out.extend_from_slice(&[PGSQL_AF_INET6, net.network_bits(), is_cidr, 16]);
+            out.extend_from_slice(&net.ip.octets());
+        }
+    }
+    Ok(out)
+}
+
+/// Decodes a value in the binary format, returning it and whether it is an `inet` or a `cidr`.
+pub fn from_binary(raw: &[u8]) -> Result<(MaskedIp, PgNetType), PgNetError> {
+    let [family, len, is_cidr, addr_len, addr @ ..] = raw else {
+        return Err(PgNetError::InvalidLength);
+    };
+    if *addr_len as usize != addr.len() {
+        return Err(PgNetError::InvalidLength);
+    }
+    let net = match (*family, addr.len()) {
+        (PGSQL_AF_INET, 4) if *len > 32 => return Err(PgNetError::InvalidPrefixLength(*len)),
+        (PGSQL_AF_INET6, 16) if *len > 128 => return Err(PgNetError::InvalidPrefixLength(*len)),
+        (PGSQL_AF_INET, 4) => {
+            let ip = Ipv4Addr::from_octets(addr.try_into().unwrap());
+            MaskedIp::V4(MaskedIpv4::cidr(ip, *len))
+        }
+        (PGSQL_AF_INET6, 16) => {
+            let ip = Ipv6Addr::from_octets(addr.try_into().unwrap());
+            MaskedIp::V6(MaskedIpv6::cidr(ip, *len))
+        }
+        (PGSQL_AF_INET | PGSQL_AF_INET6, _) => return Err(PgNetError::InvalidLength),
+        (family, _) => return Err(PgNetError::InvalidFamily(family)),
+    };
+    let ty = match is_cidr {
+        0 => PgNetType::Inet,
+        _ => PgNetType::Cidr,
+    };
+    check_cidr(&net, ty)?;
+    Ok((net, ty))
+}
+
+/// Writes an IPv6 address as PostgreSQL does, which differs from the standard format by writing
+/// IPv4-compatible addresses such as `::1.2.3.4` in dotted form.
+fn write_ipv6(out: &mut String, ip: Ipv6Addr) {
+    let segments = ip.segments();
+    if segments[..6] == [0; 6] && segments[6] != 0 {
+        let [.., a, b, c, d] = ip.octets();
+        let _ = write!(out, "::{}", Ipv4Addr::from_octets([a, b, c, d]));
+    } else {
+        let _ = write!(out, "{ip}");
+    }
+}
+
+/// Encodes a value in the text format, such as `192.0.2.1/24`. An `inet` of a single host is
+/// written without a prefix length, as PostgreSQL does.
+///
+/// # Errors
+/// Fails with [`PgNetError::HostBits`] for a `cidr` with host bits set.
+pub fn to_text(net: MaskedIp, ty: PgNetType) -> Result<String, PgNetError> {
+    check_cidr(&net, ty)?;
+    let mut out = String::new();
+    let host = match net {
+        MaskedIp::V4(net) => {
+            let _ = write!(out, "{}", net.ip);
+            net.network_bits() == 32
+        }
+        MaskedIp::V6(net) => {
+            write_ipv6(&mut out, net.ip);
+            net.network_bits() == 128
+        }
+    };
+    if !host || ty == PgNetType::Cidr {
+        let _ = write!(out, "/{}", net.network_bits());
+    }
+    Ok(out)
+}
+
+/// Decodes a value in the text format, such as `192.0.2.1/24` or `2001:db8::1`. Without a
+/// prefix length the value is a single host.
+///
+/// Only full addresses are accepted, as PostgreSQL writes them. The abbreviated IPv4 forms which
+/// PostgreSQL accepts as `cidr` input, such as `10/8`, are not.
+pub fn from_text(s: &str, ty: PgNetType) -> Result<MaskedIp, PgNetError> {
+    let (addr, len) = match s.split_once('/') {
+        Some((addr, len)) => {
+            let valid =
+                !len.is_empty() && len.len() <= 3 && len.bytes().all(|b| b.is_ascii_digit());
+            let len: u8 = len
+                .parse()
+                .ok()
+                .filter(|_| valid)
+                .ok_or(PgNetError::InvalidText)?;
+            (addr, Some(len))
+        }
+        None => (s, None),
+    };
+    let net = match addr
+        .parse::<IpAddr>()
+        .map_err(|_| PgNetError::InvalidText)?
+    {
+        IpAddr::V4(ip) => match len.unwrap_or(32) {
+            len @ 0..=32 => MaskedIp::V4(MaskedIpv4::cidr(ip, len)),
+            len => return Err(PgNetError::InvalidPrefixLength(len)),
+        },
+        IpAddr::V6(ip) => match len.unwrap_or(128) {
+            len @ 0..=128 => MaskedIp::V6(MaskedIpv6::cidr(ip, len)),
+            len => return Err(PgNetError::InvalidPrefixLength(len)),
+        },
+    };
+    check_cidr(&net, ty)?;
+    Ok(net)
+}
+
+type BoxError = Box<dyn Error + Sync + Send>;
+
+fn boxed(e: PgNetError) -> BoxError {
+    e.to_string().into()
+}
+
+/// The body of [`ToSql::to_sql_checked`]. `postgres_types::to_sql_checked!` names `::std`
+/// paths, which this crate cannot use without giving up `no_std`.
+fn to_sql_checked<T: ToSql>(v: &T, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+    if !T::accepts(ty) {
+        return Err(Box::new(WrongType::new::<T>(ty.clone())));
+    }
+    v.to_sql(ty, out)
+}
+
+macro_rules! to_sql_checked {
+    () => {
+        fn to_sql_checked(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+            to_sql_checked(self, ty, out)
+        }
+    };
+}
+
+fn net_type(ty: &Type) -> PgNetType {
+    match *ty {
+        Type::CIDR => PgNetType::Cidr,
+        _ => PgNetType::Inet,
+    }
+}
+
+impl ToSql for MaskedIp {
+    /// Writes an `inet`, or a `cidr`, which fails if host bits are set.
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        out.extend_from_slice(&to_binary(*self, net_type(ty)).map_err(boxed)?);
+        Ok(IsNull::No)
+    }
+    accepts!(INET, CIDR);
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for MaskedIp {
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        Ok(from_binary(raw).map_err(boxed)?.0)
+    }
+    accepts!(INET, CIDR);
+}
+
+impl ToSql for MaskedIpv4 {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        MaskedIp::V4(*self).to_sql(ty, out)
+    }
+    accepts!(INET, CIDR);
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for MaskedIpv4 {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        match MaskedIp::from_sql(ty, raw)? {
+            MaskedIp::V4(net) => Ok(net),
+            MaskedIp::V6(_) => Err(boxed(PgNetError::WrongFamily)),
+        }
+    }
+    accepts!(INET, CIDR);
+}
+
+impl ToSql for MaskedIpv6 {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        MaskedIp::V6(*self).to_sql(ty, out)
+    }
+    accepts!(INET, CIDR);
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for MaskedIpv6 {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        match MaskedIp::from_sql(ty, raw)? {
+            MaskedIp::V6(net) => Ok(net),
+            MaskedIp::V4(_) => Err(boxed(PgNetError::WrongFamily)),
+        }
+    }
+    accepts!(INET, CIDR);
+}
+
+impl ToSql for IpAddr {
+    /// Writes the address as a single host, such as `192.0.2.1/32`.
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        let net = match *self {
+            IpAddr::V4(ip) => MaskedIp::V4(MaskedIpv4::cidr(ip, 32)),
+            IpAddr::V6(ip) => MaskedIp::V6(MaskedIpv6::cidr(ip, 128)),
+        };
+        net.to_sql(ty, out)
+    }
+    accepts!(INET, CIDR);
+    to_sql_checked!();
+}
+
+impl<'a> FromSql<'a> for IpAddr {
+    /// Reads a single host. A value with a shorter prefix fails, rather than losing the prefix
+    /// length; read it as a [`MaskedIp`] instead.
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        match MaskedIp::from_sql(ty, raw)? {
+            MaskedIp::V4(net) if net.network_bits() == 32 => Ok(IpAddr::V4(net.ip)),
+            MaskedIp::V6(net) if net.network_bits() == 128 => Ok(IpAddr::V6(net.ip)),
+            _ => Err(boxed(PgNetError::NotHost)),
+        }
+    }
+    accepts!(INET, CIDR);
+}
@@ -0,0 +1,398 @@
+//! A compact, versioned binary format for prefix-to-value tables, read in place without parsing.
+//!
+//! The prefix tree is stored flattened. When the file is written, nested prefixes are resolved
+//! into a sorted list of disjoint address ranges, each referring to the value of its longest
+//! matching prefix. A lookup is then a binary search over the range starts, which are stored as
+//! `Ipv4Addr` and `Ipv6Addr` arrays and borrowed directly from the file bytes.
+//!
+//! # Layout
+//!
+//! All integers are little-endian. Addresses are in network byte order, so they sort by their
+//! bytes. There is no padding, so the file may be read at any alignment.
+//!
+//! | Offset | Size | Field |
+//! |--------|------|-------|
+//! | 0 | 4 | Magic, `IPDB` |
+//! | 4 | 2 | Version, currently 1 |
+//! | 6 | 2 | Size of each value in bytes |
+//! | 8 | 4 | Number of IPv4 ranges |
+//! | 12 | 4 | Number of IPv6 ranges |
+//! | 16 | 4 | Number of values |
+//! | 20 | 4 | Reserved, 0 |
+//! | 24 | 4 × IPv4 ranges | Start address of each IPv4 range, ascending |
+//! | | 4 × IPv4 ranges | Value index of each IPv4 range, or `u32::MAX` for none |
+//! | | 16 × IPv6 ranges | Start address of each IPv6 range, ascending |
+//! | | 4 × IPv6 ranges | Value index of each IPv6 range, or `u32::MAX` for none |
+//! | | value size × values | Values |
+//!
+//! Each range ends just before the next one starts, and the last range ends at the last
+//! address of its family. The first range, if any, starts at the first address. Identical values
+//! are stored once.
+
+use crate::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use bytemuck::Pod;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::marker::PhantomData;
+use core::mem::size_of;
+
+#[cfg(feature = "alloc")]
+use crate::{MaskedIp, MaskedIpv4, MaskedIpv6};
+#[cfg(feature = "alloc")]
+use alloc::{collections::BTreeMap, vec::Vec};
+
+const MAGIC: [u8; 4] = *b"IPDB";
+const VERSION: u16 = 1;
+const HEADER_LEN: usize = 24;
+/// The value index of a range which no prefix covers.
+const NO_VALUE: u32 = u32::MAX;
+
+/// Error when opening or validating a prefix database.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PrefixDbError {
+    /// The data does not start with the `IPDB` magic.
+    BadMagic,
+    /// The format version is not supported by this reader.
+    UnsupportedVersion(u16),
+    /// The size of values in the file does not match the value type.
+    ValueSize {
+        /// The size of the value type
+        expected: usize,
+        /// The size of values in the file
+        found: usize,
+    },
+    /// The data is shorter or longer than its header describes.
+    Length,
+    /// The range starts are not ascending, or do not begin at the first address.
+    Unsorted,
+    /// A range refers to a value which does not exist.
+    ValueIndex,
+}
+
+impl Display for PrefixDbError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::BadMagic => f.write_str("not a prefix database"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported prefix database version {v}"),
+            Self::ValueSize { expected, found } => {
+                write!(
+                    f,
+                    "expected {expected} byte values, found {found} byte values"
+                )
+            }
+            Self::Length => f.write_str("prefix database length does not match its header"),
+            Self::Unsorted => f.write_str("prefix database ranges are not sorted"),
+            Self::ValueIndex => f.write_str("prefix database range refers to a missing value"),
+        }
+    }
+}
+
+/// A prefix database borrowed from its serialized bytes, such as a memory-mapped file.
+///
+/// Opening only checks the header, so it takes the same time for any size of file. Lookups
+/// never panic on corrupt data, but may return wrong results; use [`validate`](Self::validate)
+/// to check the whole file once.
+pub struct PrefixDb<'a, V> {
+    v4_starts: &'a [Ipv4Addr],
+    v4_values: &'a [[u8; 4]],
+    v6_starts: &'a [Ipv6Addr],
+    v6_values: &'a [[u8; 4]],
+    values: &'a [u8],
+    value_count: usize,
+    _value: PhantomData<fn() -> V>,
+}
+
+impl<V> Clone for PrefixDb<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for PrefixDb<'_, V> {}
+
+/// Splits `count` items of `size` bytes off the front of `bytes`. A total which overflows `usize`
+/// can never fit, so it is a length error too.
+fn take<'a>(bytes: &mut &'a [u8], count: usize, size: usize) -> Result<&'a [u8], PrefixDbError> {
+    let len = match count.checked_mul(size) {
+        Some(len) if len <= bytes.len() => len,
+        _ => return Err(PrefixDbError::Length),
+    };
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> u16 {
+    u16::from_le_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> usize {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]) as usize
+}
+
+/// Returns the value index of the range containing `addr`.
+fn find<A: Ord>(starts: &[A], values: &[[u8; 4]], addr: A) -> Option<usize> {
+    let i = starts
+        .partition_point(|start| *start <= addr)
+        .checked_sub(1)?;
+    let index = u32::from_le_bytes(*values.get(i)?);
+    (index != NO_VALUE).then_some(index as usize)
+}
+
+/// Checks that `starts` begin at `first` and are strictly ascending, and that every value
+/// index is in bounds.
+fn validate_ranges<A: Ord>(
+    starts: &[A],
+    values: &[[u8; 4]],
+    first: A,
+    value_count: usize,
+) -> Result<(), PrefixDbError> {
+    if starts.first().is_some_and(|start| *start != first) || !starts.is_sorted_by(|a, b| a < b) {
+        return Err(PrefixDbError::Unsorted);
+    }
+    for value in values {
+        let index = u32::from_le_bytes(*value);
+        if index != NO_VALUE && index as usize >= value_count {
+            return Err(PrefixDbError::ValueIndex);
+        }
+    }
+    Ok(())
+}
+
+impl<'a, V: Pod> PrefixDb<'a, V> {
+    /// Opens a database from its serialized bytes, checking the header and length.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, PrefixDbError> {
+        let mut rest = bytes;
+        let header = take(&mut rest, HEADER_LEN, 1).map_err(|_| PrefixDbError::BadMagic)?;
+        if header[..4] != MAGIC {
+            return Err(PrefixDbError::BadMagic);
+        }
+        let version = read_u16(header, 4);
+        if version != VERSION {
+            return Err(PrefixDbError::UnsupportedVersion(version));
+        }
+        let value_size = read_u16(header, 6) as usize;
+        if value_size != size_of::<V>() {
+            return Err(PrefixDbError::ValueSize {
+                expected: size_of::<V>(),
+                found: value_size,
+            });
+        }
+        let (v4_count, v6_count) = (read_u32(header, 8), read_u32(header, 12));
+        let value_count = read_u32(header, 16);
+        // The slices have an alignment of 1, so these casts only fail on length, which `take`
+        // already guarantees.
+        let v4_starts = bytemuck::cast_slice(take(&mut rest, v4_count, 4)?);
+        let v4_values = bytemuck::cast_slice(take(&mut rest, v4_count, 4)?);
+        let v6_starts = bytemuck::cast_slice(take(&mut rest, v6_count, 16)?);
+        let v6_values = bytemuck::cast_slice(take(&mut rest, v6_count, 4)?);
+        let values = take(&mut rest, value_count, value_size)?;
+        if !rest.is_empty() {
+            return Err(PrefixDbError::Length);
+        }
+        Ok(Self {
+            v4_starts,
+            v4_values,
+            v6_starts,
+            v6_values,
+            values,
+            value_count,
+            _value: PhantomData,
+        })
+    }
+    /// Checks every range and value index. This takes time proportional to the size of the
+    /// database, so it is separate from [`new`](Self::new).
+    pub fn validate(&self) -> Result<(), PrefixDbError> {
+        let (v4, v6) = (Ipv4Addr::from_bits(0), Ipv6Addr::from_bits(0));
+        validate_ranges(self.v4_starts, self.v4_values, v4, self.value_count)?;
+        validate_ranges(self.v6_starts, self.v6_values, v6, self.value_count)
+    }
+    fn value(&self, index: usize) -> Option<V> {
+        let size = size_of::<V>();
+        // A corrupt index can overflow on 32-bit targets, and is then out of range like any other.
+        let start = index.checked_mul(size)?;
+        let bytes = self.values.get(start..start.checked_add(size)?)?;
+        Some(bytemuck::pod_read_unaligned(bytes))
+    }
+    /// Returns the value of the longest prefix containing `addr`, or None if no prefix does.
+    pub fn lookup_v4(&self, addr: Ipv4Addr) -> Option<V> {
+        self.value(find(self.v4_starts, self.v4_values, addr)?)
+    }
+    /// Returns the value of the longest prefix containing `addr`, or None if no prefix does.
+    pub fn lookup_v6(&self, addr: Ipv6Addr) -> Option<V> {
+        self.value(find(self.v6_starts, self.v6_values, addr)?)
+    }
+    /// Returns the value of the longest prefix containing `addr`, or None if no prefix does.
+    pub fn lookup(&self, addr: IpAddr) -> Option<V> {
+        match addr {
+            IpAddr::V4(addr) => self.lookup_v4(addr),
+            IpAddr::V6(addr) => self.lookup_v6(addr),
+        }
+    }
+    /// Returns the number of distinct values stored.
+    pub fn value_count(&self) -> usize {
+        self.value_count
+    }
+}
+
+/// Builds the serialized form of a [`PrefixDb`].
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+pub struct PrefixDbWriter<V> {
+    v4: Vec<(MaskedIpv4, V)>,
+    v6: Vec<(MaskedIpv6, V)>,
+}
+
+#[cfg(feature = "alloc")]
+impl<V: Pod> Default for PrefixDbWriter<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sets the value from `start` onward, merging with the previous range where possible.
+#[cfg(feature = "alloc")]
+fn set_from(out: &mut Vec<(u128, u32)>, start: u128, value: u32) {
+    if let Some(last) = out.last_mut().filter(|last| last.0 == start) {
+        last.1 = value;
+        if out.len() >= 2 && out[out.len() - 2].1 == value {
+            out.pop();
+        }
+    } else if out.last().is_none_or(|last| last.1 != value) {
+        out.push((start, value));
+    }
+}
+
+/// The distinct values of a database, in the order they are stored.
+#[cfg(feature = "alloc")]
+#[derive(Default)]
+struct ValueTable<'v> {
+    values: Vec<&'v [u8]>,
+    indexes: BTreeMap<&'v [u8], u32>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'v> ValueTable<'v> {
+    fn index_of<V: Pod>(&mut self, value: &'v V) -> u32 {
+        let bytes = bytemuck::bytes_of(value);
+        *self.indexes.entry(bytes).or_insert_with(|| {
+            self.values.push(bytes);
+            u32::try_from(self.values.len() - 1)
+                .ok()
+                .filter(|&i| i != NO_VALUE)
+                .expect("too many values")
+        })
+    }
+}
+
+/// Flattens nested prefixes, given as (start, length, end, value), into the start of each
+/// disjoint range and its value index. `last` is the last address of the family. Later
+/// duplicates of a prefix replace earlier ones.
+#[cfg(feature = "alloc")]
+fn flatten<'v, V: Pod>(
+    mut prefixes: Vec<(u128, u8, u128, &'v V)>,
+    last: u128,
+    table: &mut ValueTable<'v>,
+) -> Vec<(u128, u32)> {
+    // The sort is stable, so the last of each duplicate is the last inserted.
+    prefixes.sort_by_key(|&(start, len, ..)| (start, len));
+    prefixes.dedup_by(|later, earlier| {
+        let same = (later.0, later.1) == (earlier.0, earlier.1);
+        if same {
+            earlier.3 = later.3;
+        }
+        same
+    });
+    let mut out = alloc::vec![(0, NO_VALUE)];
+    // The (end, value) of each prefix containing the current position, from shortest to longest.
+    let mut stack: Vec<(u128, u32)> = Vec::new();
+    let close = |out: &mut Vec<(u128, u32)>, stack: &mut Vec<(u128, u32)>| {
+        if let Some((end, _)) = stack.pop()
+            && end < last
+        {
+            set_from(out, end + 1, stack.last().map_or(NO_VALUE, |top| top.1));
+        }
+    };
+    for (start, _, end, value) in prefixes {
+        while stack.last().is_some_and(|top| top.0 < start) {
+            close(&mut out, &mut stack);
+        }
+        let index = table.index_of(value);
+        set_from(&mut out, start, index);
+        stack.push((end, index));
+    }
+    while !stack.is_empty() {
+        close(&mut out, &mut stack);
+    }
+    if out == [(0, NO_VALUE)] {
+        out.clear();
+    }
+    out
+}
+
+#[cfg(feature = "alloc")]
+impl<V: Pod> PrefixDbWriter<V> {
+    /// Constructs an empty writer.
+    pub fn new() -> Self {
+        Self {
+            v4: Vec::new(),
+            v6: Vec::new(),
+        }
+    }
+    /// Adds a prefix with its value. Host bits are ignored. If the same prefix is inserted more
+    /// than once, the last value is kept.
+    pub fn insert(&mut self, prefix: impl Into<MaskedIp>, value: V) {
+        match prefix.into() {
+            MaskedIp::V4(net) => self.v4.push((net.network(), value)),
+            MaskedIp::V6(net) => self.v6.push((net.network(), value)),
+        }
+    }
+    /// Serializes the database.
+    ///
+    /// # Panics
+    /// Panics if the value type is larger than 65535 bytes, or there are 2^32 - 1 or more ranges
+    /// or distinct values.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let value_size = u16::try_from(size_of::<V>()).expect("value type is too large");
+        let mut table = ValueTable::default();
+        let v4 = self.v4.iter().map(|(net, value)| {
+            let (start, end) = crate::aggregate::v4_range(net);
+            (start, net.network_bits(), end, value)
+        });
+        let v4 = flatten(v4.collect(), u32::MAX.into(), &mut table);
+        let v6 = self.v6.iter().map(|(net, value)| {
+            let (start, end) = crate::aggregate::v6_range(net);
+            (start, net.network_bits(), end, value)
+        });
+        let v6 = flatten(v6.collect(), u128::MAX, &mut table);
+        let values = table.values;
+
+        let count = |len: usize| u32::try_from(len).expect("too many ranges").to_le_bytes();
+        let mut out = Vec::with_capacity(
+            HEADER_LEN + v4.len() * 8 + v6.len() * 20 + values.len() * value_size as usize,
+        );
+        out.extend_from_slice(&MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.extend_from_slice(&value_size.to_le_bytes());
+        out.extend_from_slice(&count(v4.len()));
+        out.extend_from_slice(&count(v6.len()));
+        out.extend_from_slice(&count(values.len()));
+        out.extend_from_slice(&[0; 4]);
+        for (start, _) in &v4 {
+            out.extend_from_slice(&Ipv4Addr::from_bits(*start as u32).octets());
+        }
+        for (_, value) in &v4 {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        for (start, _) in &v6 {
+            out.extend_from_slice(&Ipv6Addr::from_bits(*start).octets());
+        }
+        for (_, value) in &v6 {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in values {
+            out.extend_from_slice(value);
+        }
+        out
+    }
+}
@@ -0,0 +1,291 @@
+use crate::aggregate::{merge_ranges, v4_range, v6_range};
+use crate::ranges::CidrBlocks;
+use crate::{IpAddr, Ipv4Addr, Ipv6Addr, MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// Error returned when a line of a [`PrefixList`] cannot be parsed.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PrefixListError {
+    /// The name of the list being parsed.
+    pub source: String,
+    /// The 1-based line number of the invalid entry.
+    pub line: usize,
+}
+
+impl Display for PrefixListError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}:{}: invalid prefix", self.source, self.line)
+    }
+}
+
+/// A single entry of a [`PrefixList`], with the line it was read from.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct PrefixEntry {
+    /// The prefix as written. Host bits are not cleared.
+    pub prefix: MaskedIp,
+    /// The 1-based line number of the entry.
+    pub line: usize,
+}
+
+/// A named list of prefixes, such as an allowlist or blocklist read from a file.
+///
+/// Lists are parsed one entry per line. Anything after a `#` is a comment, and blank lines are
+/// ignored. IPv4 and IPv6 entries may be mixed. Entries may be in any form accepted by
+/// [`MaskedIp`]'s `FromStr`, or a bare address, which is treated as a /32 or /128.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PrefixList {
+    name: String,
+    entries: Vec<PrefixEntry>,
+}
+
+/// Where a prefix came from: the name of a [`PrefixList`] and a line within it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Origin<'a> {
+    /// The name of the list.
+    pub source: &'a str,
+    /// The 1-based line number of the entry.
+    pub line: usize,
+}
+
+impl Display for Origin<'_> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}:{}", self.source, self.line)
+    }
+}
+
+/// A prefix produced by a set operation, annotated with every input entry which overlaps it.
+///
+/// Displays as the prefix in CIDR notation, followed by its origins as a comment, for example
+/// `10.0.0.0/23 # allow.txt:3, allow.txt:4`.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AnnotatedPrefix<'a> {
+    /// The resulting prefix. Host bits are always 0.
+    pub prefix: MaskedIp,
+    /// The input entries which overlap this prefix, in the order the lists were given.
+    pub origins: Vec<Origin<'a>>,
+}
+
+impl Display for AnnotatedPrefix<'_> {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:#}", self.prefix)?;
+        for (i, origin) in self.origins.iter().enumerate() {
+            let sep = if i == 0 { " # " } else { ", " };
+            write!(f, "{sep}{origin}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a single entry, accepting bare addresses as host prefixes.
+fn parse_entry(s: &str) -> Option<MaskedIp> {
+    if let Ok(masked) = s.parse() {
+        return Some(masked);
+    }
+    s.parse::<IpAddr>().ok().map(MaskedIp::from)
+}
+
+impl PrefixList {
+    /// Constructs an empty list with the given name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entries: Vec::new(),
+        }
+    }
+    /// Parses a list from text. The name is used to annotate results and errors, and is
+    /// typically a file name.
+    pub fn parse(name: impl Into<String>, text: &str) -> Result<Self, PrefixListError> {
+        let mut list = Self::new(name);
+        for (index, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let Some(prefix) = parse_entry(line) else {
+                return Err(PrefixListError {
+                    source: list.name,
+                    line: index + 1,
+                });
+            };
+            list.push(prefix, index + 1);
+        }
+        Ok(list)
+    }
+    /// Appends an entry.
+    pub fn push(&mut self, prefix: impl Into<MaskedIp>, line: usize) {
+        self.entries.push(PrefixEntry {
+            prefix: prefix.into(),
+            line,
+        });
+    }
+    /// Returns the name of this list.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the entries of this list, in the order they were added.
+    pub fn entries(&self) -> &[PrefixEntry] {
+        &self.entries
+    }
+    /// Returns the number of entries in this list.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Returns true if this list has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// Returns the fewest prefixes which cover exactly the addresses in this list.
+    pub fn aggregate(&self) -> Vec<AnnotatedPrefix<'_>> {
+        union([self])
+    }
+}
+
+/// An entry of one family as an inclusive range, with the index of its list and its origin.
+type Span<'a> = (u128, u128, usize, Origin<'a>);
+
+/// Collects the entries of one address family from `lists` as inclusive ranges.
+fn spans<'a>(lists: &[&'a PrefixList], v6: bool) -> Vec<Span<'a>> {
+    let mut spans = Vec::new();
+    for (index, list) in lists.iter().enumerate() {
+        for entry in &list.entries {
+            let (start, end) = match entry.prefix {
+                MaskedIp::V4(m) if !v6 => v4_range(&m),
+                MaskedIp::V6(m) if v6 => v6_range(&m),
+                _ => continue,
+            };
+            let origin = Origin {
+                source: &list.name,
+                line: entry.line,
+            };
+            spans.push((start, end, index, origin));
+        }
+    }
+    spans
+}
+
+/// Returns the sorted, merged ranges covered by `spans`.
+fn merged(spans: &[Span]) -> Vec<(u128, u128)> {
+    let mut ranges: Vec<_> = spans
+        .iter()
+        .map(|&(start, end, _, _)| (start, end))
+        .collect();
+    merge_ranges(&mut ranges);
+    ranges
+}
+
+/// Returns the parts of `a` which are not in `b`. Both must be sorted and merged.
+fn subtract(a: &[(u128, u128)], b: &[(u128, u128)]) -> Vec<(u128, u128)> {
+    let mut out = Vec::new();
+    let mut b = b.iter().peekable();
+    for &(mut start, end) in a {
+        // Skip ranges of b which end before this range begins.
+        while b.next_if(|&&(_, b_end)| b_end < start).is_some() {}
+        let mut remaining = true;
+        while let Some(&&(b_start, b_end)) = b.peek() {
+            if b_start > end {
+                break;
+            }
+            if b_start > start {
+                out.push((start, b_start - 1));
+            }
+            if b_end >= end {
+                remaining = false;
+                break;
+            }
+            start = b_end + 1;
+            b.next();
+        }
+        if remaining {
+            out.push((start, end));
+        }
+    }
+    out
+}
+
+/// Returns the parts of `a` which are also in `b`. Both must be sorted and merged.
+fn intersect(a: &[(u128, u128)], b: &[(u128, u128)]) -> Vec<(u128, u128)> {
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < a.len() && j < b.len() {
+        let start = a[i].0.max(b[j].0);
+        let end = a[i].1.min(b[j].1);
+        if start <= end {
+            out.push((start, end));
+        }
+        if a[i].1 < b[j].1 { i += 1 } else { j += 1 }
+    }
+    out
+}
+
+/// Splits `ranges` into prefixes and annotates each with the `spans` which overlap it.
+fn annotate<'a>(
+    ranges: Vec<(u128, u128)>,
+    mut spans: Vec<Span<'a>>,
+    v6: bool,
+    out: &mut Vec<AnnotatedPrefix<'a>>,
+) {
+    // Sweep through the spans in order of their start, keeping those which may still overlap.
+    spans.sort_by_key(|&(start, ..)| start);
+    let mut pending = spans.into_iter().peekable();
+    let mut active: Vec<Span> = Vec::new();
+    let width = if v6 { 128 } else { 32 };
+    for (addr, len) in ranges
+        .into_iter()
+        .flat_map(|(start, end)| CidrBlocks::new(start, end, width))
+    {
+        let last = addr
+            | u128::MAX
+                .checked_shr(128 - (width - len) as u32)
+                .unwrap_or(0);
+        while let Some(span) = pending.next_if(|&(start, ..)| start <= last) {
+            active.push(span);
+        }
+        active.retain(|&(_, end, ..)| end >= addr);
+        let mut origins: Vec<_> = active.iter().map(|&(_, _, i, o)| (i, o.line, o)).collect();
+        origins.sort_unstable_by_key(|&(i, line, _)| (i, line));
+        let origins = origins.into_iter().map(|(_, _, origin)| origin).collect();
+        let prefix = if v6 {
+            MaskedIp::V6(MaskedIpv6::cidr(Ipv6Addr::from_bits(addr), len))
+        } else {
+            MaskedIp::V4(MaskedIpv4::cidr(Ipv4Addr::from_bits(addr as u32), len))
+        };
+        out.push(AnnotatedPrefix { prefix, origins });
+    }
+}
+
+/// Applies `op` to the merged ranges of each family and annotates the result with `annotate_from`.
+fn apply<'a>(
+    left: &[&'a PrefixList],
+    right: &[&'a PrefixList],
+    annotate_from: &[&'a PrefixList],
+    op: impl Fn(&[(u128, u128)], &[(u128, u128)]) -> Vec<(u128, u128)>,
+) -> Vec<AnnotatedPrefix<'a>> {
+    let mut out = Vec::new();
+    for v6 in [false, true] {
+        let ranges = op(&merged(&spans(left, v6)), &merged(&spans(right, v6)));
+        annotate(ranges, spans(annotate_from, v6), v6, &mut out);
+    }
+    out
+}
+
+/// Returns the fewest prefixes which cover every address in any of `lists`. Each result is
+/// annotated with every entry which overlaps it. IPv4 prefixes are listed before IPv6 prefixes.
+pub fn union<'a>(lists: impl IntoIterator<Item = &'a PrefixList>) -> Vec<AnnotatedPrefix<'a>> {
+    let lists: Vec<_> = lists.into_iter().collect();
+    apply(&lists, &[], &lists, |a, _| a.to_vec())
+}
+
+/// Returns the fewest prefixes which cover every address in `a` which is not in `b`. Each result
+/// is annotated with the entries of `a` which overlap it.
+pub fn difference<'a>(a: &'a PrefixList, b: &'a PrefixList) -> Vec<AnnotatedPrefix<'a>> {
+    apply(&[a], &[b], &[a], subtract)
+}
+
+/// Returns the fewest prefixes which cover every address in both `a` and `b`. Each result is
+/// annotated with the entries of both lists which overlap it.
+pub fn intersection<'a>(a: &'a PrefixList, b: &'a PrefixList) -> Vec<AnnotatedPrefix<'a>> {
+    apply(&[a], &[b], &[a, b], intersect)
+}
@@ -0,0 +1,576 @@
+//! Parsers for the Linux routing and address tables in `/proc/net`.
+//!
+//! IPv4 addresses in `/proc/net/route` are printed as the hex of a network-order address loaded
+//! as a host-order integer, so they are read in the byte order of the target. Files captured on
+//! a machine of the other byte order will be read with reversed addresses.
+
+use crate::{Ipv4Addr, Ipv4Mask, Ipv6Addr, MaskedIpv4, MaskedIpv6};
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::iter::Enumerate;
+use core::str::{Lines, SplitWhitespace};
+
+/// The flags of a route, as in `linux/route.h` and `linux/ipv6_route.h`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RouteFlags(pub u32);
+
+impl RouteFlags {
+    /// The route is usable.
+    pub const UP: Self = Self(0x1);
+    /// The destination is reached through a gateway.
+    pub const GATEWAY: Self = Self(0x2);
+    /// The route is to a single host.
+    pub const HOST: Self = Self(0x4);
+    /// The route was created by a redirect.
+    pub const DYNAMIC: Self = Self(0x10);
+    /// The route was modified by a redirect.
+    pub const MODIFIED: Self = Self(0x20);
+    /// The route rejects packets.
+    pub const REJECT: Self = Self(0x200);
+    /// An IPv6 default route learned from a router advertisement.
+    pub const DEFAULT: Self = Self(0x1_0000);
+    /// An IPv6 route created by address autoconfiguration.
+    pub const ADDRCONF: Self = Self(0x4_0000);
+    /// An IPv6 route to an on-link prefix.
+    pub const PREFIX_RT: Self = Self(0x8_0000);
+    /// An IPv6 route which expires.
+    pub const EXPIRES: Self = Self(0x40_0000);
+    /// A cached IPv6 route.
+    pub const CACHE: Self = Self(0x100_0000);
+
+    /// Returns true if every flag of `other` is set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// The scope of a route, as in `linux/rtnetlink.h`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RouteScope(pub u8);
+
+impl RouteScope {
+    /// Reachable anywhere
+    pub const UNIVERSE: Self = Self(0);
+    /// Reachable within the site
+    pub const SITE: Self = Self(200);
+    /// Reachable on the attached link
+    pub const LINK: Self = Self(253);
+    /// Local to this host
+    pub const HOST: Self = Self(254);
+    /// No destination
+    pub const NOWHERE: Self = Self(255);
+}
+
+impl Display for RouteScope {
+    /// Writes the scope name as `ip route` does for known scopes, otherwise the number.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            Self::UNIVERSE => f.write_str("universe"),
+            Self::SITE => f.write_str("site"),
+            Self::LINK => f.write_str("link"),
+            Self::HOST => f.write_str("host"),
+            Self::NOWHERE => f.write_str("nowhere"),
+            Self(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+/// The type of a route, as in `linux/rtnetlink.h`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RouteType(pub u8);
+
+impl RouteType {
+    /// Unknown type
+    pub const UNSPEC: Self = Self(0);
+    /// A gateway or direct route
+    pub const UNICAST: Self = Self(1);
+    /// An address of this host
+    pub const LOCAL: Self = Self(2);
+    /// A broadcast address, sent as broadcast
+    pub const BROADCAST: Self = Self(3);
+    /// A local broadcast address, sent as unicast
+    pub const ANYCAST: Self = Self(4);
+    /// A multicast route
+    pub const MULTICAST: Self = Self(5);
+    /// Packets are silently dropped
+    pub const BLACKHOLE: Self = Self(6);
+    /// The destination is unreachable
+    pub const UNREACHABLE: Self = Self(7);
+    /// The destination is administratively prohibited
+    pub const PROHIBIT: Self = Self(8);
+    /// Lookup continues in another table
+    pub const THROW: Self = Self(9);
+    /// A network address translation rule
+    pub const NAT: Self = Self(10);
+    /// An external resolver
+    pub const XRESOLVE: Self = Self(11);
+
+    const NAMES: [&str; 12] = [
+        "UNSPEC",
+        "UNICAST",
+        "LOCAL",
+        "BROADCAST",
+        "ANYCAST",
+        "MULTICAST",
+        "BLACKHOLE",
+        "UNREACHABLE",
+        "PROHIBIT",
+        "THROW",
+        "NAT",
+        "XRESOLVE",
+    ];
+}
+
+impl Display for RouteType {
+    /// Writes the type name as `/proc/net/fib_trie` does, such as `UNICAST` or `type 12`.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match Self::NAMES.get(self.0 as usize) {
+            Some(name) => f.write_str(name),
+            None => write!(f, "type {}", self.0),
+        }
+    }
+}
+
+/// The scope of an IPv6 address in `/proc/net/if_inet6`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ipv6AddrScope(pub u8);
+
+impl Ipv6AddrScope {
+    /// A global address
+    pub const GLOBAL: Self = Self(0x00);
+    /// The loopback address
+    pub const LOOPBACK: Self = Self(0x10);
+    /// A link-local address
+    pub const LINK: Self = Self(0x20);
+    /// A site-local address
+    pub const SITE: Self = Self(0x40);
+    /// An IPv4-compatible address
+    pub const COMPAT_V4: Self = Self(0x80);
+}
+
+/// The flags of an IPv6 address in `/proc/net/if_inet6`, as in `linux/if_addr.h`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ipv6AddrFlags(pub u8);
+
+impl Ipv6AddrFlags {
+    /// A temporary privacy address
+    pub const TEMPORARY: Self = Self(0x01);
+    /// Duplicate address detection is disabled.
+    pub const NODAD: Self = Self(0x02);
+    /// The address is used before duplicate address detection completes.
+    pub const OPTIMISTIC: Self = Self(0x04);
+    /// Duplicate address detection failed.
+    pub const DADFAILED: Self = Self(0x08);
+    /// A mobile IPv6 home address
+    pub const HOMEADDRESS: Self = Self(0x10);
+    /// The address should not be used for new connections.
+    pub const DEPRECATED: Self = Self(0x20);
+    /// Duplicate address detection has not completed.
+    pub const TENTATIVE: Self = Self(0x40);
+    /// The address was configured statically.
+    pub const PERMANENT: Self = Self(0x80);
+
+    /// Returns true if every flag of `other` is set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+/// The kind of a [`ProcNetError`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum ProcNetErrorKind {
+    /// The line has too few fields.
+    MissingField,
+    /// An address is not valid hex or dotted decimal.
+    InvalidAddress,
+    /// A mask is not contiguous, or a prefix length is too long.
+    InvalidMask,
+    /// A number is not valid.
+    InvalidNumber,
+    /// A `/proc/net/fib_trie` line is not where it is expected.
+    UnexpectedLine,
+}
+
+impl Display for ProcNetErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::MissingField => "missing field",
+            Self::InvalidAddress => "invalid address",
+            Self::InvalidMask => "invalid mask",
+            Self::InvalidNumber => "invalid number",
+            Self::UnexpectedLine => "unexpected line",
+        })
+    }
+}
+
+/// Error when failing to parse a line of a `/proc/net` file.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct ProcNetError {
+    /// What went wrong
+    pub kind: ProcNetErrorKind,
+    /// The line number, starting at 1
+    pub line: usize,
+}
+
+impl Display for ProcNetError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+/// Parses a hex number of exactly `len` digits.
+fn hex(s: &str, len: usize, kind: ProcNetErrorKind) -> Result<u128, ProcNetErrorKind> {
+    if s.len() != len || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(kind);
+    }
+    u128::from_str_radix(s, 16).map_err(|_| kind)
+}
+
+/// Parses an IPv4 address printed as a host-order hex integer.
+fn host_hex_v4(s: &str) -> Result<Ipv4Addr, ProcNetErrorKind> {
+    let n = hex(s, 8, ProcNetErrorKind::InvalidAddress)? as u32;
+    Ok(Ipv4Addr::from_octets(n.to_ne_bytes()))
+}
+
+/// Parses an IPv6 address printed as 32 hex digits.
+fn packed_hex_v6(s: &str) -> Result<Ipv6Addr, ProcNetErrorKind> {
+    Ok(Ipv6Addr::from_bits(hex(
+        s,
+        32,
+        ProcNetErrorKind::InvalidAddress,
+    )?))
+}
+
+/// Parses an IPv6 prefix length printed as 2 hex digits.
+fn hex_len(s: &str) -> Result<u8, ProcNetErrorKind> {
+    let len = hex(s, 2, ProcNetErrorKind::InvalidMask)? as u8;
+    if len > 128 {
+        return Err(ProcNetErrorKind::InvalidMask);
+    }
+    Ok(len)
+}
+
+fn hex_u32(s: &str) -> Result<u32, ProcNetErrorKind> {
+    hex(s, 8, ProcNetErrorKind::InvalidNumber).map(|n| n as u32)
+}
+
+fn decimal<T: core::str::FromStr>(s: &str) -> Result<T, ProcNetErrorKind> {
+    s.parse().map_err(|_| ProcNetErrorKind::InvalidNumber)
+}
+
+fn next<'a>(fields: &mut SplitWhitespace<'a>) -> Result<&'a str, ProcNetErrorKind> {
+    fields.next().ok_or(ProcNetErrorKind::MissingField)
+}
+
+/// Returns the next line which is not blank, with its line number.
+fn next_line<'a>(lines: &mut Enumerate<Lines<'a>>) -> Option<(usize, &'a str)> {
+    lines
+        .find(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| (i + 1, line))
+}
+
+/// A route of `/proc/net/route`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ipv4Route<'a> {
+    /// The interface name, such as `eth0`
+    pub iface: &'a str,
+    /// The destination network
+    pub destination: MaskedIpv4,
+    /// The gateway, or `0.0.0.0` for a directly connected network
+    pub gateway: Ipv4Addr,
+    /// The route flags
+    pub flags: RouteFlags,
+    /// The route metric
+    pub metric: u32,
+    /// The maximum segment size for TCP over this route, or 0 for the default
+    pub mtu: u32,
+    /// The TCP window size, or 0 for the default
+    pub window: u32,
+    /// The initial TCP round trip time, or 0 for the default
+    pub irtt: u32,
+}
+
+impl<'a> Ipv4Route<'a> {
+    /// Parses one route line. Use [`Ipv4Routes`] to parse a whole file, which also skips the
+    /// header.
+    pub fn parse(line: &'a str) -> Result<Self, ProcNetErrorKind> {
+        let mut fields = line.split_whitespace();
+        let iface = next(&mut fields)?;
+        let destination = host_hex_v4(next(&mut fields)?)?;
+        let gateway = host_hex_v4(next(&mut fields)?)?;
+        let flags = RouteFlags(hex(next(&mut fields)?, 4, ProcNetErrorKind::InvalidNumber)? as u32);
+        let _ref_count: u32 = decimal(next(&mut fields)?)?;
+        let _use_count: u32 = decimal(next(&mut fields)?)?;
+        let metric = decimal(next(&mut fields)?)?;
+        let mask = host_hex_v4(next(&mut fields)?)?;
+        let mask = Ipv4Mask::from_bytes(mask.octets()).ok_or(ProcNetErrorKind::InvalidMask)?;
+        Ok(Self {
+            iface,
+            destination: MaskedIpv4::new(destination, mask),
+            gateway,
+            flags,
+            metric,
+            mtu: decimal(next(&mut fields)?)?,
+            window: decimal(next(&mut fields)?)?,
+            irtt: decimal(next(&mut fields)?)?,
+        })
+    }
+}
+
+/// An iterator over the routes of `/proc/net/route`. The header is skipped.
+#[derive(Clone, Debug)]
+pub struct Ipv4Routes<'a> {
+    lines: Enumerate<Lines<'a>>,
+}
+
+impl<'a> Ipv4Routes<'a> {
+    /// Constructs an iterator over the routes of `text`.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().enumerate(),
+        }
+    }
+}
+
+impl<'a> Iterator for Ipv4Routes<'a> {
+    type Item = Result<Ipv4Route<'a>, ProcNetError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, text) = loop {
+            let (line, text) = next_line(&mut self.lines)?;
+            if !text.starts_with("Iface") {
+                break (line, text);
+            }
+        };
+        Some(Ipv4Route::parse(text).map_err(|kind| ProcNetError { kind, line }))
+    }
+}
+
+/// A route of `/proc/net/ipv6_route`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ipv6Route<'a> {
+    /// The interface name, such as `eth0`
+    pub iface: &'a str,
+    /// The destination network
+    pub destination: MaskedIpv6,
+    /// The source network for source-specific routes, otherwise `::/0`
+    pub source: MaskedIpv6,
+    /// The next hop, or `::` for a directly connected network
+    pub next_hop: Ipv6Addr,
+    /// The route metric
+    pub metric: u32,
+    /// The route flags
+    pub flags: RouteFlags,
+}
+
+impl<'a> Ipv6Route<'a> {
+    /// Parses one route line.
+    pub fn parse(line: &'a str) -> Result<Self, ProcNetErrorKind> {
+        let mut fields = line.split_whitespace();
+        let destination = packed_hex_v6(next(&mut fields)?)?;
+        let destination = MaskedIpv6::cidr(destination, hex_len(next(&mut fields)?)?);
+        let source = packed_hex_v6(next(&mut fields)?)?;
+        let source = MaskedIpv6::cidr(source, hex_len(next(&mut fields)?)?);
+        let next_hop = packed_hex_v6(next(&mut fields)?)?;
+        let metric = hex_u32(next(&mut fields)?)?;
+        let _ref_count = hex_u32(next(&mut fields)?)?;
+        let _use_count = hex_u32(next(&mut fields)?)?;
+        Ok(Self {
+            flags: RouteFlags(hex_u32(next(&mut fields)?)?),
+            iface: next(&mut fields)?,
+            destination,
+            source,
+            next_hop,
+            metric,
+        })
+    }
+}
+
+/// An iterator over the routes of `/proc/net/ipv6_route`.
+#[derive(Clone, Debug)]
+pub struct Ipv6Routes<'a> {
+    lines: Enumerate<Lines<'a>>,
+}
+
+impl<'a> Ipv6Routes<'a> {
+    /// Constructs an iterator over the routes of `text`.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().enumerate(),
+        }
+    }
+}
+
+impl<'a> Iterator for Ipv6Routes<'a> {
+    type Item = Result<Ipv6Route<'a>, ProcNetError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, text) = next_line(&mut self.lines)?;
+        Some(Ipv6Route::parse(text).map_err(|kind| ProcNetError { kind, line }))
+    }
+}
+
+/// An address of `/proc/net/if_inet6`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ipv6IfAddr<'a> {
+    /// The interface name, such as `eth0`
+    pub iface: &'a str,
+    /// The interface index
+    pub if_index: u32,
+    /// The address and the prefix length of its network
+    pub address: MaskedIpv6,
+    /// The address scope
+    pub scope: Ipv6AddrScope,
+    /// The address flags
+    pub flags: Ipv6AddrFlags,
+}
+
+impl<'a> Ipv6IfAddr<'a> {
+    /// Parses one address line.
+    pub fn parse(line: &'a str) -> Result<Self, ProcNetErrorKind> {
+        let mut fields = line.split_whitespace();
+        let address = packed_hex_v6(next(&mut fields)?)?;
+        let index = next(&mut fields)?;
+        // The index is printed with at least 2 digits.
+        let if_index = hex(index, index.len().max(2), ProcNetErrorKind::InvalidNumber)?;
+        let address = MaskedIpv6::cidr(address, hex_len(next(&mut fields)?)?);
+        let scope = hex(next(&mut fields)?, 2, ProcNetErrorKind::InvalidNumber)? as u8;
+        let flags = hex(next(&mut fields)?, 2, ProcNetErrorKind::InvalidNumber)? as u8;
+        Ok(Self {
+            iface: next(&mut fields)?,
+            if_index: u32::try_from(if_index).map_err(|_| ProcNetErrorKind::InvalidNumber)?,
+            address,
+            scope: Ipv6AddrScope(scope),
+            flags: Ipv6AddrFlags(flags),
+        })
+    }
+}
+
+/// An iterator over the addresses of `/proc/net/if_inet6`.
+#[derive(Clone, Debug)]
+pub struct Ipv6IfAddrs<'a> {
+    lines: Enumerate<Lines<'a>>,
+}
+
+impl<'a> Ipv6IfAddrs<'a> {
+    /// Constructs an iterator over the addresses of `text`.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().enumerate(),
+        }
+    }
+}
+
+impl<'a> Iterator for Ipv6IfAddrs<'a> {
+    type Item = Result<Ipv6IfAddr<'a>, ProcNetError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (line, text) = next_line(&mut self.lines)?;
+        Some(Ipv6IfAddr::parse(text).map_err(|kind| ProcNetError { kind, line }))
+    }
+}
+
+/// A route of `/proc/net/fib_trie`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct FibRoute<'a> {
+    /// The routing table, such as `Main`, `Local` or `Id 100`
+    pub table: &'a str,
+    /// The destination network. The address is the trie leaf, so it may have host bits set.
+    pub destination: MaskedIpv4,
+    /// The route scope
+    pub scope: RouteScope,
+    /// The route type
+    pub kind: RouteType,
+    /// The type of service, or 0 for any
+    pub tos: u8,
+}
+
+/// An iterator over the routes of `/proc/net/fib_trie`.
+///
+/// The file lists each table as a trie, where each leaf address is followed by one line per
+/// route to it, such as `/24 link UNICAST`. Internal trie nodes are skipped.
+#[derive(Clone, Debug)]
+pub struct FibRoutes<'a> {
+    lines: Enumerate<Lines<'a>>,
+    table: Option<&'a str>,
+    leaf: Option<Ipv4Addr>,
+}
+
+impl<'a> FibRoutes<'a> {
+    /// Constructs an iterator over the routes of `text`.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().enumerate(),
+            table: None,
+            leaf: None,
+        }
+    }
+    /// Parses a route line such as `/32 host LOCAL` or `/24 link UNICAST tos=16`.
+    fn route(&self, line: &str) -> Result<FibRoute<'a>, ProcNetErrorKind> {
+        let (Some(table), Some(leaf)) = (self.table, self.leaf) else {
+            return Err(ProcNetErrorKind::UnexpectedLine);
+        };
+        let mut fields = line.split_whitespace();
+        let len = next(&mut fields)?.strip_prefix('/');
+        let len = len
+            .and_then(|len| len.parse().ok())
+            .filter(|&len| len <= 32);
+        let len = len.ok_or(ProcNetErrorKind::InvalidMask)?;
+        let scope = match next(&mut fields)? {
+            "universe" => RouteScope::UNIVERSE,
+            "site" => RouteScope::SITE,
+            "link" => RouteScope::LINK,
+            "host" => RouteScope::HOST,
+            "nowhere" => RouteScope::NOWHERE,
+            scope => RouteScope(decimal(scope)?),
+        };
+        let kind = match next(&mut fields)? {
+            "type" => RouteType(decimal(next(&mut fields)?)?),
+            name => match RouteType::NAMES.iter().position(|n| *n == name) {
+                Some(i) => RouteType(i as u8),
+                None => return Err(ProcNetErrorKind::InvalidNumber),
+            },
+        };
+        let tos = match fields.next().map(|tos| tos.strip_prefix("tos=")) {
+            None => 0,
+            Some(Some(tos)) => decimal(tos)?,
+            Some(None) => return Err(ProcNetErrorKind::UnexpectedLine),
+        };
+        Ok(FibRoute {
+            table,
+            destination: MaskedIpv4::cidr(leaf, len),
+            scope,
+            kind,
+            tos,
+        })
+    }
+}
+
+impl<'a> Iterator for FibRoutes<'a> {
+    type Item = Result<FibRoute<'a>, ProcNetError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((line, text)) = next_line(&mut self.lines) {
+            let error = |kind| Some(Err(ProcNetError { kind, line }));
+            let trimmed = text.trim_start();
+            if let Some(table) = text
+                .strip_suffix(':')
+                .filter(|_| trimmed.len() == text.len())
+            {
+                self.table = Some(table);
+                self.leaf = None;
+            } else if trimmed.starts_with("+--") {
+                self.leaf = None;
+            } else if let Some(leaf) = trimmed.strip_prefix("|--") {
+                match leaf.trim().parse() {
+                    Ok(leaf) => self.leaf = Some(leaf),
+                    Err(_) => return error(ProcNetErrorKind::InvalidAddress),
+                }
+            } else if trimmed.starts_with('/') {
+                return Some(
+                    self.route(trimmed)
+                        .map_err(|kind| ProcNetError { kind, line }),
+                );
+            } else {
+                return error(ProcNetErrorKind::UnexpectedLine);
+            }
+        }
+        None
+    }
+}
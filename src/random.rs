@@ -0,0 +1,296 @@
+use crate::{Ipv4Addr, Ipv6Addr, MaskedIpv4, MaskedIpv6};
+
+#[cfg(feature = "rand")]
+use crate::{IpAddr, MaskedIp};
+#[cfg(feature = "rand")]
+use rand::{Rng, RngExt};
+
+/// A keyed Feistel network over the domain `0..2^bits`. Odd widths are handled by running the
+/// network over one extra bit and cycle-walking until the output falls back inside the domain.
+#[derive(Copy, Clone, Debug)]
+struct Feistel {
+    bits: u32,
+    half: u32,
+    key: u64,
+}
+
+impl Feistel {
+    const ROUNDS: u64 = 4;
+    fn new(bits: u32, key: u64) -> Self {
+        Self {
+            bits,
+            half: bits.div_ceil(2),
+            key,
+        }
+    }
+    /// splitmix64's finalizer, keyed by round.
+    fn round(&self, round: u64, x: u64) -> u64 {
+        let mut z = x ^ self.key ^ round.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    fn encrypt(&self, x: u128) -> u128 {
+        let mask = u64::MAX.checked_shr(64 - self.half).unwrap_or(0);
+        let (mut l, mut r) = ((x >> self.half) as u64, x as u64 & mask);
+        for round in 0..Self::ROUNDS {
+            (l, r) = (r, l ^ (self.round(round, r) & mask));
+        }
+        ((l as u128) << self.half) | r as u128
+    }
+    fn permute(&self, x: u128) -> u128 {
+        let mut y = self.encrypt(x);
+        while y.checked_shr(self.bits).unwrap_or(0) != 0 {
+            y = self.encrypt(y);
+        }
+        y
+    }
+}
+
+/// Visits every index in `0..2^bits` exactly once, in the order given by a [`Feistel`] permutation.
+#[derive(Copy, Clone, Debug)]
+struct Walk {
+    feistel: Feistel,
+    next: Option<u128>,
+}
+
+impl Walk {
+    fn new(bits: u32, key: u64) -> Self {
+        Self {
+            feistel: Feistel::new(bits, key),
+            next: Some(0),
+        }
+    }
+}
+
+impl Iterator for Walk {
+    type Item = u128;
+    fn next(&mut self) -> Option<u128> {
+        let index = self.next?;
+        let last = u128::MAX.checked_shr(128 - self.feistel.bits).unwrap_or(0);
+        self.next = if index == last { None } else { Some(index + 1) };
+        Some(self.feistel.permute(index))
+    }
+}
+
+/// An iterator over every address in a [`MaskedIpv4`] in a pseudo-random order. Created by [`MaskedIpv4::shuffled`].
+#[derive(Copy, Clone, Debug)]
+pub struct ShuffledIpv4 {
+    base: u32,
+    walk: Walk,
+}
+
+/// An iterator over every address in a [`MaskedIpv6`] in a pseudo-random order. Created by [`MaskedIpv6::shuffled`].
+#[derive(Copy, Clone, Debug)]
+pub struct ShuffledIpv6 {
+    base: u128,
+    walk: Walk,
+}
+
+/// An iterator over every subnet of a given length in a [`MaskedIpv4`] in a pseudo-random order.
+/// Created by [`MaskedIpv4::shuffled_subnets`].
+#[derive(Copy, Clone, Debug)]
+pub struct ShuffledSubnetsIpv4 {
+    base: u32,
+    len: u8,
+    walk: Walk,
+}
+
+/// An iterator over every subnet of a given length in a [`MaskedIpv6`] in a pseudo-random order.
+/// Created by [`MaskedIpv6::shuffled_subnets`].
+#[derive(Copy, Clone, Debug)]
+pub struct ShuffledSubnetsIpv6 {
+    base: u128,
+    len: u8,
+    walk: Walk,
+}
+
+impl Iterator for ShuffledIpv4 {
+    type Item = Ipv4Addr;
+    fn next(&mut self) -> Option<Ipv4Addr> {
+        let offset = self.walk.next()?;
+        Some(Ipv4Addr::from_bits(self.base | offset as u32))
+    }
+}
+
+impl Iterator for ShuffledIpv6 {
+    type Item = Ipv6Addr;
+    fn next(&mut self) -> Option<Ipv6Addr> {
+        let offset = self.walk.next()?;
+        Some(Ipv6Addr::from_bits(self.base | offset))
+    }
+}
+
+impl Iterator for ShuffledSubnetsIpv4 {
+    type Item = MaskedIpv4;
+    fn next(&mut self) -> Option<MaskedIpv4> {
+        let index = self.walk.next()? as u32;
+        let offset = index.checked_shl(32 - self.len as u32).unwrap_or(0);
+        Some(MaskedIpv4::cidr(
+            Ipv4Addr::from_bits(self.base | offset),
+            self.len,
+        ))
+    }
+}
+
+impl Iterator for ShuffledSubnetsIpv6 {
+    type Item = MaskedIpv6;
+    fn next(&mut self) -> Option<MaskedIpv6> {
+        let index = self.walk.next()?;
+        let offset = index.checked_shl(128 - self.len as u32).unwrap_or(0);
+        Some(MaskedIpv6::cidr(
+            Ipv6Addr::from_bits(self.base | offset),
+            self.len,
+        ))
+    }
+}
+
+impl MaskedIpv4 {
+    /// Returns an iterator which visits every address in this network exactly once, in an order
+    /// determined by `key`. The iterator does not allocate, and only stores its position.
+    pub fn shuffled(&self, key: u64) -> ShuffledIpv4 {
+        ShuffledIpv4 {
+            base: self.network_address().to_bits(),
+            walk: Walk::new(self.host_bits() as u32, key),
+        }
+    }
+    /// Returns an iterator which visits every subnet of length `len` in this network exactly once,
+    /// in an order determined by `key`. The subnets never overlap.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `len` is shorter than this network's mask, or > 32
+    pub fn shuffled_subnets(&self, len: u8, key: u64) -> ShuffledSubnetsIpv4 {
+        assert!(
+            (self.network_bits()..=32).contains(&len),
+            "Invalid subnet length"
+        );
+        ShuffledSubnetsIpv4 {
+            base: self.network_address().to_bits(),
+            len,
+            walk: Walk::new((len - self.network_bits()) as u32, key),
+        }
+    }
+    /// Returns a uniformly random usable host address in this network. The network and broadcast
+    /// addresses are never returned, unless the mask length is 31 or 32.
+    #[cfg(feature = "rand")]
+    pub fn random_host<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv4Addr {
+        let (first, last) = (self.network_address(), self.broadcast_address());
+        let (first, last) = if self.network_bits() <= 30 {
+            (first.to_bits() + 1, last.to_bits() - 1)
+        } else {
+            (first.to_bits(), last.to_bits())
+        };
+        Ipv4Addr::from_bits(rng.random_range(first..=last))
+    }
+    /// Returns a uniformly random subnet of length `len` within this network, or None if `len`
+    /// is shorter than this network's mask or > 32.
+    #[cfg(feature = "rand")]
+    pub fn random_subnet<R: Rng + ?Sized>(&self, len: u8, rng: &mut R) -> Option<MaskedIpv4> {
+        if !(self.network_bits()..=32).contains(&len) {
+            return None;
+        }
+        let last = u32::MAX.checked_shr(32 - (len - self.network_bits()) as u32);
+        let index = rng.random_range(0..=last.unwrap_or(0));
+        let offset = index.checked_shl(32 - len as u32).unwrap_or(0);
+        let ip = Ipv4Addr::from_bits(self.network_address().to_bits() | offset);
+        Some(MaskedIpv4::cidr(ip, len))
+    }
+    /// Returns an iterator over every subnet of length `len` within this network in a random
+    /// order. The subnets never overlap.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `len` is shorter than this network's mask, or > 32
+    #[cfg(feature = "rand")]
+    pub fn random_subnets<R: Rng + ?Sized>(&self, len: u8, rng: &mut R) -> ShuffledSubnetsIpv4 {
+        self.shuffled_subnets(len, rng.next_u64())
+    }
+}
+
+impl MaskedIpv6 {
+    /// Returns an iterator which visits every address in this network exactly once, in an order
+    /// determined by `key`. The iterator does not allocate, and only stores its position.
+    pub fn shuffled(&self, key: u64) -> ShuffledIpv6 {
+        ShuffledIpv6 {
+            base: self.network_address().to_bits(),
+            walk: Walk::new(self.host_bits() as u32, key),
+        }
+    }
+    /// Returns an iterator which visits every subnet of length `len` in this network exactly once,
+    /// in an order determined by `key`. The subnets never overlap.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `len` is shorter than this network's mask, or > 128
+    pub fn shuffled_subnets(&self, len: u8, key: u64) -> ShuffledSubnetsIpv6 {
+        assert!(
+            (self.network_bits()..=128).contains(&len),
+            "Invalid subnet length"
+        );
+        ShuffledSubnetsIpv6 {
+            base: self.network_address().to_bits(),
+            len,
+            walk: Walk::new((len - self.network_bits()) as u32, key),
+        }
+    }
+    /// Returns a uniformly random usable host address in this network. The subnet-router anycast
+    /// (network) address is never returned, unless the mask length is 127 or 128.
+    #[cfg(feature = "rand")]
+    pub fn random_host<R: Rng + ?Sized>(&self, rng: &mut R) -> Ipv6Addr {
+        let first = self.network_address().to_bits();
+        let last = first
+            | u128::MAX
+                .checked_shr(self.network_bits() as u32)
+                .unwrap_or(0);
+        let first = if self.network_bits() <= 126 {
+            first + 1
+        } else {
+            first
+        };
+        Ipv6Addr::from_bits(rng.random_range(first..=last))
+    }
+    /// Returns a uniformly random subnet of length `len` within this network, or None if `len`
+    /// is shorter than this network's mask or > 128.
+    #[cfg(feature = "rand")]
+    pub fn random_subnet<R: Rng + ?Sized>(&self, len: u8, rng: &mut R) -> Option<MaskedIpv6> {
+        if !(self.network_bits()..=128).contains(&len) {
+            return None;
+        }
+        let last = u128::MAX.checked_shr(128 - (len - self.network_bits()) as u32);
+        let index = rng.random_range(0..=last.unwrap_or(0));
+        let offset = index.checked_shl(128 - len as u32).unwrap_or(0);
+        let ip = Ipv6Addr::from_bits(self.network_address().to_bits() | offset);
+        Some(MaskedIpv6::cidr(ip, len))
+    }
+    /// Returns an iterator over every subnet of length `len` within this network in a random
+    /// order. The subnets never overlap.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `len` is shorter than this network's mask, or > 128
+    #[cfg(feature = "rand")]
+    pub fn random_subnets<R: Rng + ?Sized>(&self, len: u8, rng: &mut R) -> ShuffledSubnetsIpv6 {
+        self.shuffled_subnets(len, rng.next_u64())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl MaskedIp {
+    /// Returns a uniformly random usable host address in this network.
+    /// See [`MaskedIpv4::random_host`] and [`MaskedIpv6::random_host`].
+    pub fn random_host<R: Rng + ?Sized>(&self, rng: &mut R) -> IpAddr {
+        match self {
+            Self::V4(m) => IpAddr::V4(m.random_host(rng)),
+            Self::V6(m) => IpAddr::V6(m.random_host(rng)),
+        }
+    }
+    /// Returns a uniformly random subnet of length `len` within this network, or None if `len`
+    /// is shorter than this network's mask or longer than the address.
+    pub fn random_subnet<R: Rng + ?Sized>(&self, len: u8, rng: &mut R) -> Option<MaskedIp> {
+        match self {
+            Self::V4(m) => m.random_subnet(len, rng).map(Self::V4),
+            Self::V6(m) => m.random_subnet(len, rng).map(Self::V6),
+        }
+    }
+}
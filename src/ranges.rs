@@ -0,0 +1,485 @@
+use crate::{Ipv4Addr, Ipv6Addr, MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use core::fmt::{Debug, Display, Formatter, Result as FmtResult};
+use core::slice;
+use core::str::FromStr;
+
+/// Iterator over the smallest set of aligned blocks which exactly covers an inclusive range of
+/// addresses, in ascending order. Addresses are stored in the low `width` bits of a u128 and
+/// blocks are yielded as (network address, prefix length).
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct CidrBlocks {
+    next: Option<u128>,
+    end: u128,
+    width: u8,
+}
+
+impl CidrBlocks {
+    pub(crate) fn new(start: u128, end: u128, width: u8) -> Self {
+        Self {
+            next: (start <= end).then_some(start),
+            end,
+            width,
+        }
+    }
+}
+
+impl Iterator for CidrBlocks {
+    type Item = (u128, u8);
+    fn next(&mut self) -> Option<(u128, u8)> {
+        let start = self.next?;
+        // The block may be no larger than the alignment of its start, nor extend past the end.
+        let align = start.trailing_zeros().min(self.width as u32);
+        let span = self.end - start;
+        let fits = match span.checked_add(1) {
+            Some(count) => 127 - count.leading_zeros(),
+            None => 128,
+        };
+        let host_bits = align.min(fits);
+        let size = 1u128.checked_shl(host_bits).unwrap_or(0);
+        self.next = match start.checked_add(size) {
+            Some(next) if size != 0 && next <= self.end => Some(next),
+            _ => None,
+        };
+        Some((start, self.width - host_bits as u8))
+    }
+}
+
+/// An iterator over the subnets of a [`MaskedIpv4`], in ascending order. Created by [`MaskedIpv4::subnets`].
+#[derive(Copy, Clone, Debug)]
+pub struct Ipv4Subnets {
+    blocks: SubnetBlocks,
+}
+
+/// An iterator over the subnets of a [`MaskedIpv6`], in ascending order. Created by [`MaskedIpv6::subnets`].
+#[derive(Copy, Clone, Debug)]
+pub struct Ipv6Subnets {
+    blocks: SubnetBlocks,
+}
+
+/// Counts through the subnets of a network as (network address, prefix length).
+#[derive(Copy, Clone, Debug)]
+struct SubnetBlocks {
+    next: Option<u128>,
+    last: u128,
+    len: u8,
+    width: u8,
+}
+
+impl SubnetBlocks {
+    fn new(base: u128, base_len: u8, len: u8, width: u8) -> Self {
+        let host_mask = u128::MAX.checked_shr(128 - (width - base_len) as u32);
+        let last = base | host_mask.unwrap_or(0);
+        Self {
+            next: (len >= base_len && len <= width).then_some(base),
+            last,
+            len,
+            width,
+        }
+    }
+}
+
+impl Iterator for SubnetBlocks {
+    type Item = (u128, u8);
+    fn next(&mut self) -> Option<(u128, u8)> {
+        let addr = self.next?;
+        let size = 1u128.checked_shl((self.width - self.len) as u32);
+        self.next = size
+            .and_then(|size| addr.checked_add(size))
+            .filter(|&next| next <= self.last);
+        Some((addr, self.len))
+    }
+}
+
+impl Iterator for Ipv4Subnets {
+    type Item = MaskedIpv4;
+    fn next(&mut self) -> Option<MaskedIpv4> {
+        self.blocks.next().map(to_ipv4)
+    }
+}
+
+impl Iterator for Ipv6Subnets {
+    type Item = MaskedIpv6;
+    fn next(&mut self) -> Option<MaskedIpv6> {
+        self.blocks.next().map(to_ipv6)
+    }
+}
+
+impl MaskedIpv4 {
+    /// Returns an iterator over every subnet of length `new_len` in this network, in ascending order.
+    /// The iterator is empty if `new_len` is shorter than this network's mask, or > 32.
+    pub fn subnets(&self, new_len: u8) -> Ipv4Subnets {
+        let base = self.network_address().to_bits() as u128;
+        Ipv4Subnets {
+            blocks: SubnetBlocks::new(base, self.network_bits(), new_len, 32),
+        }
+    }
+}
+
+impl MaskedIpv6 {
+    /// Returns an iterator over every subnet of length `new_len` in this network, in ascending order.
+    /// The iterator is empty if `new_len` is shorter than this network's mask, or > 128.
+    pub fn subnets(&self, new_len: u8) -> Ipv6Subnets {
+        let base = self.network_address().to_bits();
+        Ipv6Subnets {
+            blocks: SubnetBlocks::new(base, self.network_bits(), new_len, 128),
+        }
+    }
+}
+
+/// An inclusive range of IPv4 addresses, which need not be aligned to a network.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Ipv4Range {
+    start: Ipv4Addr,
+    end: Ipv4Addr,
+}
+
+/// An iterator over the smallest list of networks which exactly covers an [`Ipv4Range`], in
+/// ascending order. Created by [`Ipv4Range::cidrs`].
+#[derive(Copy, Clone, Debug)]
+pub struct Ipv4RangeCidrs {
+    blocks: CidrBlocks,
+}
+
+impl Iterator for Ipv4RangeCidrs {
+    type Item = MaskedIpv4;
+    fn next(&mut self) -> Option<MaskedIpv4> {
+        self.blocks.next().map(to_ipv4)
+    }
+}
+
+impl Ipv4Range {
+    /// Constructs a range from `start` to `end`, inclusive. Returns None if `start` is after `end`.
+    pub const fn new(start: Ipv4Addr, end: Ipv4Addr) -> Option<Self> {
+        if start.to_bits() <= end.to_bits() {
+            Some(Self { start, end })
+        } else {
+            None
+        }
+    }
+    /// Constructs a range of `count` addresses beginning at `start`. Returns None if `count` is 0,
+    /// or the range would extend past `255.255.255.255`.
+    pub const fn from_count(start: Ipv4Addr, count: u64) -> Option<Self> {
+        let last = match (start.to_bits() as u64).checked_add(count) {
+            Some(last) if count != 0 && last <= 1 << 32 => last,
+            _ => return None,
+        };
+        Some(Self {
+            start,
+            end: Ipv4Addr::from_bits((last - 1) as u32),
+        })
+    }
+    /// Returns the first address of this range.
+    pub const fn start(&self) -> Ipv4Addr {
+        self.start
+    }
+    /// Returns the last address of this range.
+    pub const fn end(&self) -> Ipv4Addr {
+        self.end
+    }
+    /// Returns the number of addresses in this range.
+    pub const fn count(&self) -> u64 {
+        (self.end.to_bits() - self.start.to_bits()) as u64 + 1
+    }
+    /// Returns true if `ip` is within this range.
+    pub const fn contains(&self, ip: Ipv4Addr) -> bool {
+        self.start.to_bits() <= ip.to_bits() && ip.to_bits() <= self.end.to_bits()
+    }
+    /// Returns this range as a network, or None if it is not exactly one aligned network.
+    pub const fn to_masked(&self) -> Option<MaskedIpv4> {
+        let count = self.count();
+        if count.is_power_of_two() && (self.start.to_bits() as u64).is_multiple_of(count) {
+            Some(MaskedIpv4::cidr(
+                self.start,
+                32 - count.trailing_zeros() as u8,
+            ))
+        } else {
+            None
+        }
+    }
+    /// Returns an iterator over the smallest list of networks which exactly covers this range,
+    /// in ascending order.
+    pub fn cidrs(&self) -> Ipv4RangeCidrs {
+        let (start, end) = (self.start.to_bits() as u128, self.end.to_bits() as u128);
+        Ipv4RangeCidrs {
+            blocks: CidrBlocks::new(start, end, 32),
+        }
+    }
+}
+
+impl From<MaskedIpv4> for Ipv4Range {
+    fn from(masked: MaskedIpv4) -> Self {
+        Self {
+            start: masked.network_address(),
+            end: masked.broadcast_address(),
+        }
+    }
+}
+
+impl Display for Ipv4Range {
+    /// Writes the range as `start-end`.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}-{}", self.start, self.end)
+    }
+}
+
+impl Debug for Ipv4Range {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        Display::fmt(self, f)
+    }
+}
+
+/// Error when failing to parse an Ipv4Range.
+#[derive(Debug)]
+pub struct InvalidIpv4Range;
+
+impl FromStr for Ipv4Range {
+    type Err = InvalidIpv4Range;
+    /// Parses a range written as `start-end`, where `start` is not after `end`.
+    fn from_str(s: &str) -> Result<Self, InvalidIpv4Range> {
+        let (start, end) = s.split_once('-').ok_or(InvalidIpv4Range)?;
+        let start = start.parse().map_err(|_| InvalidIpv4Range)?;
+        let end = end.parse().map_err(|_| InvalidIpv4Range)?;
+        Self::new(start, end).ok_or(InvalidIpv4Range)
+    }
+}
+
+/// Walks the gaps which `ranges` leave in `cursor..=end`, as aligned blocks. The ranges must be
+/// sorted by start. Overlapping ranges are allowed.
+#[derive(Clone, Debug)]
+struct Gaps<R> {
+    ranges: R,
+    cursor: Option<u128>,
+    end: u128,
+    width: u8,
+    blocks: Option<CidrBlocks>,
+}
+
+impl<R> Gaps<R> {
+    fn new(ranges: R, (start, end): (u128, u128), width: u8) -> Self {
+        Self {
+            ranges,
+            cursor: Some(start),
+            end,
+            width,
+            blocks: None,
+        }
+    }
+}
+
+impl<R: Iterator<Item = (u128, u128)>> Iterator for Gaps<R> {
+    type Item = (u128, u8);
+    fn next(&mut self) -> Option<(u128, u8)> {
+        loop {
+            if let Some(block) = self.blocks.as_mut().and_then(Iterator::next) {
+                return Some(block);
+            }
+            self.blocks = None;
+            let cursor = self.cursor?;
+            match self.ranges.next() {
+                Some((_, end)) if end < cursor => {}
+                Some((start, end)) if start <= self.end => {
+                    if start > cursor {
+                        self.blocks = Some(CidrBlocks::new(cursor, start - 1, self.width));
+                    }
+                    self.cursor = end.checked_add(1).filter(|&next| next <= self.end);
+                }
+                // Either the ranges are exhausted, or the rest start after the end.
+                _ => {
+                    self.blocks = Some(CidrBlocks::new(cursor, self.end, self.width));
+                    self.cursor = None;
+                }
+            }
+        }
+    }
+}
+
+fn to_ipv4((addr, len): (u128, u8)) -> MaskedIpv4 {
+    MaskedIpv4::cidr(Ipv4Addr::from_bits(addr as u32), len)
+}
+
+fn to_ipv6((addr, len): (u128, u8)) -> MaskedIpv6 {
+    MaskedIpv6::cidr(Ipv6Addr::from_bits(addr), len)
+}
+
+pub(crate) fn to_ip((addr, len): (u128, u8), width: u8) -> MaskedIp {
+    if width == 32 {
+        MaskedIp::V4(to_ipv4((addr, len)))
+    } else {
+        MaskedIp::V6(to_ipv6((addr, len)))
+    }
+}
+
+type SliceRanges<'a, T> = core::iter::Map<slice::Iter<'a, T>, fn(&T) -> (u128, u128)>;
+type IpSliceRanges<'a> =
+    core::iter::FilterMap<slice::Iter<'a, MaskedIp>, fn(&MaskedIp) -> Option<(u128, u128)>>;
+
+/// An iterator over what remains of a [`MaskedIpv4`] after removing another network, in
+/// ascending order. Created by [`MaskedIpv4::exclude`].
+#[derive(Clone, Debug)]
+pub struct Ipv4Exclude {
+    gaps: Gaps<core::option::IntoIter<(u128, u128)>>,
+}
+
+/// An iterator over what remains of a [`MaskedIpv6`] after removing another network, in
+/// ascending order. Created by [`MaskedIpv6::exclude`].
+#[derive(Clone, Debug)]
+pub struct Ipv6Exclude {
+    gaps: Gaps<core::option::IntoIter<(u128, u128)>>,
+}
+
+/// An iterator over what remains of a [`MaskedIp`] after removing another network, in
+/// ascending order. Created by [`MaskedIp::exclude`].
+#[derive(Clone, Debug)]
+pub struct IpExclude {
+    gaps: Gaps<core::option::IntoIter<(u128, u128)>>,
+}
+
+/// An iterator over what remains of a [`MaskedIpv4`] after removing a sorted list of networks, in
+/// ascending order. Created by [`MaskedIpv4::exclude_all`].
+#[derive(Clone, Debug)]
+pub struct Ipv4ExcludeAll<'a> {
+    gaps: Gaps<SliceRanges<'a, MaskedIpv4>>,
+}
+
+/// An iterator over what remains of a [`MaskedIpv6`] after removing a sorted list of networks, in
+/// ascending order. Created by [`MaskedIpv6::exclude_all`].
+#[derive(Clone, Debug)]
+pub struct Ipv6ExcludeAll<'a> {
+    gaps: Gaps<SliceRanges<'a, MaskedIpv6>>,
+}
+
+/// An iterator over what remains of a [`MaskedIp`] after removing a sorted list of networks, in
+/// ascending order. Created by [`MaskedIp::exclude_all`].
+#[derive(Clone, Debug)]
+pub struct IpExcludeAll<'a> {
+    gaps: Gaps<IpSliceRanges<'a>>,
+}
+
+impl Iterator for Ipv4Exclude {
+    type Item = MaskedIpv4;
+    fn next(&mut self) -> Option<MaskedIpv4> {
+        self.gaps.next().map(to_ipv4)
+    }
+}
+
+impl Iterator for Ipv6Exclude {
+    type Item = MaskedIpv6;
+    fn next(&mut self) -> Option<MaskedIpv6> {
+        self.gaps.next().map(to_ipv6)
+    }
+}
+
+impl Iterator for IpExclude {
+    type Item = MaskedIp;
+    fn next(&mut self) -> Option<MaskedIp> {
+        let width = self.gaps.width;
+        self.gaps.next().map(|block| to_ip(block, width))
+    }
+}
+
+impl Iterator for Ipv4ExcludeAll<'_> {
+    type Item = MaskedIpv4;
+    fn next(&mut self) -> Option<MaskedIpv4> {
+        self.gaps.next().map(to_ipv4)
+    }
+}
+
+impl Iterator for Ipv6ExcludeAll<'_> {
+    type Item = MaskedIpv6;
+    fn next(&mut self) -> Option<MaskedIpv6> {
+        self.gaps.next().map(to_ipv6)
+    }
+}
+
+impl Iterator for IpExcludeAll<'_> {
+    type Item = MaskedIp;
+    fn next(&mut self) -> Option<MaskedIp> {
+        let width = self.gaps.width;
+        self.gaps.next().map(|block| to_ip(block, width))
+    }
+}
+
+impl MaskedIpv4 {
+    /// Returns an iterator over the fewest networks which cover this network without `other`, in
+    /// ascending order. If the networks do not overlap, this yields only this network (with host
+    /// bits cleared). If `other` contains this network, this yields nothing.
+    pub fn exclude(&self, other: &MaskedIpv4) -> Ipv4Exclude {
+        Ipv4Exclude {
+            gaps: Gaps::new(Some(other.bounds()).into_iter(), self.bounds(), 32),
+        }
+    }
+    /// Returns an iterator over the fewest networks which cover this network without any of
+    /// `excluded`, in ascending order, in a single pass.
+    ///
+    /// `excluded` must be sorted, as by `sort()`. Overlapping entries are allowed. If it is not
+    /// sorted, the output is still sorted and non-overlapping, but may not exclude every entry.
+    pub fn exclude_all<'a>(&self, excluded: &'a [MaskedIpv4]) -> Ipv4ExcludeAll<'a> {
+        let ranges: SliceRanges<MaskedIpv4> = excluded.iter().map(MaskedIpv4::bounds);
+        Ipv4ExcludeAll {
+            gaps: Gaps::new(ranges, self.bounds(), 32),
+        }
+    }
+}
+
+impl MaskedIpv6 {
+    /// Returns an iterator over the fewest networks which cover this network without `other`, in
+    /// ascending order. If the networks do not overlap, this yields only this network (with host
+    /// bits cleared). If `other` contains this network, this yields nothing.
+    pub fn exclude(&self, other: &MaskedIpv6) -> Ipv6Exclude {
+        Ipv6Exclude {
+            gaps: Gaps::new(Some(other.bounds()).into_iter(), self.bounds(), 128),
+        }
+    }
+    /// Returns an iterator over the fewest networks which cover this network without any of
+    /// `excluded`, in ascending order, in a single pass.
+    ///
+    /// `excluded` must be sorted, as by `sort()`. Overlapping entries are allowed. If it is not
+    /// sorted, the output is still sorted and non-overlapping, but may not exclude every entry.
+    pub fn exclude_all<'a>(&self, excluded: &'a [MaskedIpv6]) -> Ipv6ExcludeAll<'a> {
+        let ranges: SliceRanges<MaskedIpv6> = excluded.iter().map(MaskedIpv6::bounds);
+        Ipv6ExcludeAll {
+            gaps: Gaps::new(ranges, self.bounds(), 128),
+        }
+    }
+}
+
+impl MaskedIp {
+    /// Returns the first and last addresses of this network, and the address width.
+    fn bounds_and_width(&self) -> ((u128, u128), u8) {
+        match self {
+            Self::V4(m) => (m.bounds(), 32),
+            Self::V6(m) => (m.bounds(), 128),
+        }
+    }
+    /// Returns an iterator over the fewest networks which cover this network without `other`, in
+    /// ascending order. A network of the other family removes nothing.
+    /// See [`MaskedIpv4::exclude`] and [`MaskedIpv6::exclude`].
+    pub fn exclude(&self, other: &MaskedIp) -> IpExclude {
+        let (bounds, width) = self.bounds_and_width();
+        let (other_bounds, other_width) = other.bounds_and_width();
+        let other = Some(other_bounds).filter(|_| width == other_width);
+        IpExclude {
+            gaps: Gaps::new(other.into_iter(), bounds, width),
+        }
+    }
+    /// Returns an iterator over the fewest networks which cover this network without any of
+    /// `excluded`, in ascending order, in a single pass. Networks of the other family are ignored.
+    /// See [`MaskedIpv4::exclude_all`] and [`MaskedIpv6::exclude_all`].
+    pub fn exclude_all<'a>(&self, excluded: &'a [MaskedIp]) -> IpExcludeAll<'a> {
+        let (bounds, width) = self.bounds_and_width();
+        let filter: fn(&MaskedIp) -> Option<(u128, u128)> = match self {
+            Self::V4(_) => |m| match m {
+                Self::V4(m) => Some(m.bounds()),
+                Self::V6(_) => None,
+            },
+            Self::V6(_) => |m| match m {
+                Self::V4(_) => None,
+                Self::V6(m) => Some(m.bounds()),
+            },
+        };
+        IpExcludeAll {
+            gaps: Gaps::new(excluded.iter().filter_map(filter), bounds, width),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+use crate::{MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+/// How one network relates to another. Host bits are ignored, so only the networks are compared.
+///
+/// Returned by [`MaskedIpv4::relation`], [`MaskedIpv6::relation`] and [`MaskedIp::relation`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum PrefixRelation {
+    /// Both networks have the same address and mask.
+    Equal,
+    /// This network strictly contains the other network.
+    Contains,
+    /// This network is strictly contained by the other network.
+    ContainedBy,
+    /// The networks do not overlap, but the last address of one is immediately followed by the
+    /// first address of the other.
+    Adjacent,
+    /// The networks do not overlap and are not adjacent. Networks of different families are
+    /// always disjoint.
+    Disjoint,
+}
+
+/// Returns true if `next` is the address immediately after `last`.
+const fn follows(last: u128, next: u128) -> bool {
+    matches!(last.checked_add(1), Some(after) if after == next)
+}
+
+/// Relates two inclusive ranges which are known to be aligned prefixes.
+const fn relate(a: (u128, u128), b: (u128, u128)) -> PrefixRelation {
+    let ((a_start, a_end), (b_start, b_end)) = (a, b);
+    if a_start == b_start && a_end == b_end {
+        PrefixRelation::Equal
+    } else if a_start <= b_start && b_end <= a_end {
+        PrefixRelation::Contains
+    } else if b_start <= a_start && a_end <= b_end {
+        PrefixRelation::ContainedBy
+    } else if follows(a_end, b_start) || follows(b_end, a_start) {
+        PrefixRelation::Adjacent
+    } else {
+        PrefixRelation::Disjoint
+    }
+}
+
+/// Error returned by [`MaskedIp::covering`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum CoveringError {
+    /// No networks were provided.
+    Empty,
+    /// Both IPv4 and IPv6 networks were provided.
+    MixedFamilies,
+}
+
+impl Display for CoveringError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::Empty => "no networks to cover",
+            Self::MixedFamilies => "cannot cover both IPv4 and IPv6 networks",
+        })
+    }
+}
+
+impl PrefixRelation {
+    /// Returns true if the networks share at least one address.
+    pub const fn overlaps(self) -> bool {
+        matches!(self, Self::Equal | Self::Contains | Self::ContainedBy)
+    }
+}
+
+impl MaskedIpv4 {
+    /// Returns the first and last addresses of this network.
+    pub(crate) const fn bounds(&self) -> (u128, u128) {
+        (
+            self.network_address().to_bits() as u128,
+            self.broadcast_address().to_bits() as u128,
+        )
+    }
+    /// Returns how this network relates to `other`.
+    pub const fn relation(&self, other: &MaskedIpv4) -> PrefixRelation {
+        relate(self.bounds(), other.bounds())
+    }
+    /// Returns true if the networks share at least one address.
+    pub const fn overlaps(&self, other: &MaskedIpv4) -> bool {
+        self.relation(other).overlaps()
+    }
+    /// Returns true if this network is within `other`, including when they are equal.
+    pub const fn is_subnet_of(&self, other: &MaskedIpv4) -> bool {
+        matches!(
+            self.relation(other),
+            PrefixRelation::Equal | PrefixRelation::ContainedBy
+        )
+    }
+    /// Returns true if `other` is within this network, including when they are equal.
+    pub const fn is_supernet_of(&self, other: &MaskedIpv4) -> bool {
+        other.is_subnet_of(self)
+    }
+    /// Returns true if every address of `other` is in this network. Same as [`is_supernet_of`](Self::is_supernet_of).
+    pub const fn contains_prefix(&self, other: &MaskedIpv4) -> bool {
+        self.is_supernet_of(other)
+    }
+    /// Returns the smallest network which contains every address or network in `iter`, or None
+    /// if `iter` is empty. Addresses are treated as /32 networks. Host bits of the result are 0.
+    pub fn covering<T: Into<MaskedIpv4>>(iter: impl IntoIterator<Item = T>) -> Option<Self> {
+        iter.into_iter().map(Into::into).fold(None, |cover, next| {
+            let Some(cover) = cover else {
+                return Some(next.network());
+            };
+            let common = cover.ip.common_prefix_len(next.network_address());
+            let len = common.min(cover.network_bits()).min(next.network_bits());
+            Some(Self::cidr(cover.ip, len).network())
+        })
+    }
+}
+
+impl MaskedIpv6 {
+    /// Returns the first and last addresses of this network.
+    pub(crate) const fn bounds(&self) -> (u128, u128) {
+        let start = self.network_address().to_bits();
+        let host_mask = !self.mask.to_bits();
+        (start, start | host_mask)
+    }
+    /// Returns how this network relates to `other`.
+    pub const fn relation(&self, other: &MaskedIpv6) -> PrefixRelation {
+        relate(self.bounds(), other.bounds())
+    }
+    /// Returns true if the networks share at least one address.
+    pub const fn overlaps(&self, other: &MaskedIpv6) -> bool {
+        self.relation(other).overlaps()
+    }
+    /// Returns true if this network is within `other`, including when they are equal.
+    pub const fn is_subnet_of(&self, other: &MaskedIpv6) -> bool {
+        matches!(
+            self.relation(other),
+            PrefixRelation::Equal | PrefixRelation::ContainedBy
+        )
+    }
+    /// Returns true if `other` is within this network, including when they are equal.
+    pub const fn is_supernet_of(&self, other: &MaskedIpv6) -> bool {
+        other.is_subnet_of(self)
+    }
+    /// Returns true if every address of `other` is in this network. Same as [`is_supernet_of`](Self::is_supernet_of).
+    pub const fn contains_prefix(&self, other: &MaskedIpv6) -> bool {
+        self.is_supernet_of(other)
+    }
+    /// Returns the smallest network which contains every address or network in `iter`, or None
+    /// if `iter` is empty. Addresses are treated as /128 networks. Host bits of the result are 0.
+    pub fn covering<T: Into<MaskedIpv6>>(iter: impl IntoIterator<Item = T>) -> Option<Self> {
+        iter.into_iter().map(Into::into).fold(None, |cover, next| {
+            let Some(cover) = cover else {
+                return Some(next.network());
+            };
+            let common = cover.ip.common_prefix_len(next.network_address());
+            let len = common.min(cover.network_bits()).min(next.network_bits());
+            Some(Self::cidr(cover.ip, len).network())
+        })
+    }
+}
+
+impl MaskedIp {
+    /// Returns how this network relates to `other`. Networks of different families are [`PrefixRelation::Disjoint`].
+    pub const fn relation(&self, other: &MaskedIp) -> PrefixRelation {
+        match (self, other) {
+            (Self::V4(a), Self::V4(b)) => a.relation(b),
+            (Self::V6(a), Self::V6(b)) => a.relation(b),
+            _ => PrefixRelation::Disjoint,
+        }
+    }
+    /// Returns true if the networks share at least one address.
+    pub const fn overlaps(&self, other: &MaskedIp) -> bool {
+        self.relation(other).overlaps()
+    }
+    /// Returns true if this network is within `other`, including when they are equal.
+    pub const fn is_subnet_of(&self, other: &MaskedIp) -> bool {
+        matches!(
+            self.relation(other),
+            PrefixRelation::Equal | PrefixRelation::ContainedBy
+        )
+    }
+    /// Returns true if `other` is within this network, including when they are equal.
+    pub const fn is_supernet_of(&self, other: &MaskedIp) -> bool {
+        other.is_subnet_of(self)
+    }
+    /// Returns true if every address of `other` is in this network. Same as [`is_supernet_of`](Self::is_supernet_of).
+    pub const fn contains_prefix(&self, other: &MaskedIp) -> bool {
+        self.is_supernet_of(other)
+    }
+    /// Returns the smallest network which contains every address or network in `iter`. Addresses
+    /// are treated as /32 or /128 networks. Host bits of the result are 0.
+    pub fn covering<T: Into<MaskedIp>>(
+        iter: impl IntoIterator<Item = T>,
+    ) -> Result<Self, CoveringError> {
+        let mut cover = None;
+        for next in iter {
+            cover = Some(match (cover, next.into()) {
+                (None, next) => next.network(),
+                (Some(Self::V4(a)), Self::V4(b)) => Self::V4(MaskedIpv4::covering([a, b]).unwrap()),
+                (Some(Self::V6(a)), Self::V6(b)) => Self::V6(MaskedIpv6::covering([a, b]).unwrap()),
+                _ => return Err(CoveringError::MixedFamilies),
+            });
+        }
+        cover.ok_or(CoveringError::Empty)
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{Ipv4Addr, Ipv4Range, Ipv4RangeCidrs, Ipv6Addr, MaskedIp, MaskedIpv6};
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::iter::Enumerate;
+use core::str::Lines;
+
+/// The resource delegated by a [`RirRecord`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RirResource {
+    /// A block of `count` autonomous system numbers beginning at `first`.
+    Asn {
+        /// The first AS number
+        first: u32,
+        /// The number of AS numbers
+        count: u32,
+    },
+    /// A range of IPv4 addresses, which need not be aligned to a network.
+    Ipv4(Ipv4Range),
+    /// An IPv6 network.
+    Ipv6(MaskedIpv6),
+}
+
+/// The status of a [`RirRecord`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RirStatus {
+    /// Delegated to a local registry for further assignment.
+    Allocated,
+    /// Assigned to an end user.
+    Assigned,
+    /// Held by the registry and not yet delegated.
+    Available,
+    /// Held by the registry and not available for delegation.
+    Reserved,
+}
+
+impl Display for RirStatus {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::Allocated => "allocated",
+            Self::Assigned => "assigned",
+            Self::Available => "available",
+            Self::Reserved => "reserved",
+        })
+    }
+}
+
+/// A calendar date of a [`RirRecord`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct RirDate {
+    /// The year, such as 2026
+    pub year: u16,
+    /// The month, from 1 to 12
+    pub month: u8,
+    /// The day of the month, from 1 to 31
+    pub day: u8,
+}
+
+impl Display for RirDate {
+    /// Writes the date as `YYYYMMDD`, as in the statistics files.
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:04}{:02}{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// The kind of a [`RirError`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RirErrorKind {
+    /// The record has fewer than 7 fields.
+    MissingField,
+    /// The type is not `asn`, `ipv4` or `ipv6`.
+    InvalidType,
+    /// The start is not a valid address or AS number for the type.
+    InvalidStart,
+    /// The value is not a valid count or prefix length for the type, or the range extends past
+    /// the end of the address space.
+    InvalidValue,
+    /// The date is not empty, `00000000`, or a valid `YYYYMMDD` date.
+    InvalidDate,
+    /// The status is not `allocated`, `assigned`, `available` or `reserved`.
+    InvalidStatus,
+}
+
+impl Display for RirErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(match self {
+            Self::MissingField => "missing field",
+            Self::InvalidType => "invalid resource type",
+            Self::InvalidStart => "invalid start",
+            Self::InvalidValue => "invalid value",
+            Self::InvalidDate => "invalid date",
+            Self::InvalidStatus => "invalid status",
+        })
+    }
+}
+
+/// Error when failing to parse a record of a statistics file.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RirError {
+    /// What went wrong
+    pub kind: RirErrorKind,
+    /// The line number of the record, starting at 1
+    pub line: usize,
+}
+
+impl Display for RirError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "line {}: {}", self.line, self.kind)
+    }
+}
+
+/// One record of an RIR statistics exchange file, such as the `delegated-*-extended-latest`
+/// files published by each regional internet registry.
+///
+/// A record is a line of the form `registry|cc|type|start|value|date|status[|opaque-id[|...]]`.
+/// Fields after the opaque ID are ignored.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RirRecord<'a> {
+    /// The registry which holds the resource, such as `ripencc`
+    pub registry: &'a str,
+    /// The ISO 3166 country code, or `ZZ` or an empty string for undelegated resources
+    pub country: &'a str,
+    /// The delegated resource
+    pub resource: RirResource,
+    /// The date of delegation, or None if the record has no date
+    pub date: Option<RirDate>,
+    /// The status of the resource
+    pub status: RirStatus,
+    /// The opaque ID of the holder, shared by every record of the same holder. None for files
+    /// in the non-extended format.
+    pub opaque_id: Option<&'a str>,
+}
+
+fn parse_date(s: &str) -> Result<Option<RirDate>, RirErrorKind> {
+    if s.is_empty() || s == "00000000" {
+        return Ok(None);
+    }
+    if s.len() != 8 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(RirErrorKind::InvalidDate);
+    }
+    let date = RirDate {
+        year: s[..4].parse().map_err(|_| RirErrorKind::InvalidDate)?,
+        month: s[4..6].parse().map_err(|_| RirErrorKind::InvalidDate)?,
+        day: s[6..].parse().map_err(|_| RirErrorKind::InvalidDate)?,
+    };
+    if !(1..=12).contains(&date.month) || !(1..=31).contains(&date.day) {
+        return Err(RirErrorKind::InvalidDate);
+    }
+    Ok(Some(date))
+}
+
+impl<'a> RirRecord<'a> {
+    /// Parses one record line. Use [`RirRecords`] to parse a whole file, which also skips the
+    /// version line, summary lines and comments.
+    pub fn parse(line: &'a str) -> Result<Self, RirErrorKind> {
+        let mut fields = line.trim_end().split('|');
+        let mut next = || fields.next().ok_or(RirErrorKind::MissingField);
+        let (registry, country, kind) = (next()?, next()?, next()?);
+        let (start, value, date, status) = (next()?, next()?, next()?, next()?);
+        let opaque_id = fields.next();
+        let resource = match kind {
+            "asn" => RirResource::Asn {
+                first: start.parse().map_err(|_| RirErrorKind::InvalidStart)?,
+                count: value.parse().map_err(|_| RirErrorKind::InvalidValue)?,
+            },
+            "ipv4" => {
+                let start = start
+                    .parse::<Ipv4Addr>()
+                    .map_err(|_| RirErrorKind::InvalidStart)?;
+                let count = value.parse().map_err(|_| RirErrorKind::InvalidValue)?;
+                let range = Ipv4Range::from_count(start, count);
+                RirResource::Ipv4(range.ok_or(RirErrorKind::InvalidValue)?)
+            }
+            "ipv6" => {
+                let start = start
+                    .parse::<Ipv6Addr>()
+                    .map_err(|_| RirErrorKind::InvalidStart)?;
+                let len = value
+                    .parse::<u8>()
+                    .ok()
+                    .filter(|&len| len <= 128)
+                    .ok_or(RirErrorKind::InvalidValue)?;
+                RirResource::Ipv6(MaskedIpv6::cidr(start, len))
+            }
+            _ => return Err(RirErrorKind::InvalidType),
+        };
+        let status = match status {
+            "allocated" => RirStatus::Allocated,
+            "assigned" => RirStatus::Assigned,
+            "available" => RirStatus::Available,
+            "reserved" => RirStatus::Reserved,
+            _ => return Err(RirErrorKind::InvalidStatus),
+        };
+        Ok(Self {
+            registry,
+            country,
+            resource,
+            date: parse_date(date)?,
+            status,
+            opaque_id,
+        })
+    }
+    /// Returns an iterator over the networks of this record. An IPv4 range which is not aligned
+    /// to one network is split into the smallest list of networks covering it. AS number
+    /// records have no networks.
+    pub fn prefixes(&self) -> RirPrefixes {
+        RirPrefixes {
+            state: match self.resource {
+                RirResource::Asn { .. } => PrefixState::Done,
+                RirResource::Ipv4(range) => PrefixState::V4(range.cidrs()),
+                RirResource::Ipv6(net) => PrefixState::V6(net),
+            },
+        }
+    }
+}
+
+/// An iterator over the networks of a [`RirRecord`]. Created by [`RirRecord::prefixes`].
+#[derive(Copy, Clone, Debug)]
+pub struct RirPrefixes {
+    state: PrefixState,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum PrefixState {
+    V4(Ipv4RangeCidrs),
+    V6(MaskedIpv6),
+    Done,
+}
+
+impl Iterator for RirPrefixes {
+    type Item = MaskedIp;
+    fn next(&mut self) -> Option<MaskedIp> {
+        match &mut self.state {
+            PrefixState::V4(cidrs) => cidrs.next().map(MaskedIp::V4),
+            PrefixState::V6(net) => {
+                let net = *net;
+                self.state = PrefixState::Done;
+                Some(MaskedIp::V6(net))
+            }
+            PrefixState::Done => None,
+        }
+    }
+}
+
+/// An iterator over the records of an RIR statistics exchange file, in both the standard and
+/// extended formats. The version line, summary lines, comments and blank lines are skipped.
+#[derive(Clone, Debug)]
+pub struct RirRecords<'a> {
+    lines: Enumerate<Lines<'a>>,
+}
+
+impl<'a> RirRecords<'a> {
+    /// Constructs an iterator over the records of `text`.
+    pub fn new(text: &'a str) -> Self {
+        Self {
+            lines: text.lines().enumerate(),
+        }
+    }
+}
+
+impl<'a> Iterator for RirRecords<'a> {
+    type Item = Result<RirRecord<'a>, RirError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        for (i, line) in &mut self.lines {
+            let mut fields = line.split('|');
+            let first = fields.next().unwrap_or_default();
+            // The version line starts with the format version, such as `2` or `2.3`.
+            let version = first.starts_with(|c: char| c.is_ascii_digit());
+            if line.trim().is_empty() || first.starts_with('#') || version {
+                continue;
+            }
+            if fields.nth(4).is_some_and(|f| f.trim_end() == "summary") {
+                continue;
+            }
+            return Some(RirRecord::parse(line).map_err(|kind| RirError { kind, line: i + 1 }));
+        }
+        None
+    }
+}
@@ -0,0 +1,687 @@
+//! Encoding and decoding of rtnetlink route and address messages, without sockets or
+//! allocation.
+//!
+//! Netlink integers are in the byte order of the host, so messages are read and written in the
+//! byte order of the target. Attributes which are not represented, such as `RTA_CACHEINFO`, are
+//! skipped when parsing and are not written back.
+
+use crate::{IpAddr, Ipv4Addr, Ipv6Addr, MaskedIp, MaskedIpv4, MaskedIpv6, RouteScope, RouteType};
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+
+const HEADER_LEN: usize = 16;
+const RTMSG_LEN: usize = 12;
+const IFADDRMSG_LEN: usize = 8;
+
+const NLMSG_DONE: u16 = 3;
+
+const AF_INET: u8 = 2;
+const AF_INET6: u8 = 10;
+
+const RTA_DST: u16 = 1;
+const RTA_SRC: u16 = 2;
+const RTA_IIF: u16 = 3;
+const RTA_OIF: u16 = 4;
+const RTA_GATEWAY: u16 = 5;
+const RTA_PRIORITY: u16 = 6;
+const RTA_PREFSRC: u16 = 7;
+const RTA_TABLE: u16 = 15;
+
+const IFA_ADDRESS: u16 = 1;
+const IFA_LOCAL: u16 = 2;
+const IFA_LABEL: u16 = 3;
+const IFA_BROADCAST: u16 = 4;
+const IFA_FLAGS: u16 = 8;
+
+/// The table number written in the route header for tables above 255, which are given in full
+/// by `RTA_TABLE`.
+const RT_TABLE_COMPAT: u8 = 252;
+
+/// The type of a netlink message, as in `linux/rtnetlink.h`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RtnlMessageType(pub u16);
+
+impl RtnlMessageType {
+    /// An address was added, or is listed in a dump.
+    pub const NEWADDR: Self = Self(20);
+    /// An address was removed.
+    pub const DELADDR: Self = Self(21);
+    /// A request for addresses.
+    pub const GETADDR: Self = Self(22);
+    /// A route was added, or is listed in a dump.
+    pub const NEWROUTE: Self = Self(24);
+    /// A route was removed.
+    pub const DELROUTE: Self = Self(25);
+    /// A request for routes.
+    pub const GETROUTE: Self = Self(26);
+}
+
+/// The flags of a netlink message header, as in `linux/netlink.h`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NetlinkFlags(pub u16);
+
+impl NetlinkFlags {
+    /// The message is a request.
+    pub const REQUEST: Self = Self(0x1);
+    /// The message is part of a multipart reply.
+    pub const MULTI: Self = Self(0x2);
+    /// Requests an acknowledgement.
+    pub const ACK: Self = Self(0x4);
+    /// Requests that the request be echoed back.
+    pub const ECHO: Self = Self(0x8);
+    /// Requests every matching entry, for get requests.
+    pub const DUMP: Self = Self(0x300);
+    /// Replaces an existing entry, for new requests.
+    pub const REPLACE: Self = Self(0x100);
+    /// Fails if the entry already exists, for new requests.
+    pub const EXCL: Self = Self(0x200);
+    /// Creates the entry if it does not exist, for new requests.
+    pub const CREATE: Self = Self(0x400);
+    /// Adds to the end of the list, for new requests.
+    pub const APPEND: Self = Self(0x800);
+
+    /// Returns true if every flag of `other` is set.
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl core::ops::BitOr for NetlinkFlags {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// The protocol which installed a route, as in `linux/rtnetlink.h`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RouteProtocol(pub u8);
+
+impl RouteProtocol {
+    /// Unknown
+    pub const UNSPEC: Self = Self(0);
+    /// Installed by an ICMP redirect
+    pub const REDIRECT: Self = Self(1);
+    /// Installed by the kernel, such as for a configured address
+    pub const KERNEL: Self = Self(2);
+    /// Installed during boot, the default for `ip route`
+    pub const BOOT: Self = Self(3);
+    /// Installed by an administrator
+    pub const STATIC: Self = Self(4);
+    /// Installed by a DHCP client
+    pub const DHCP: Self = Self(16);
+}
+
+/// Error when encoding or decoding an rtnetlink message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum NetlinkError {
+    /// The buffer is shorter than a header or the message length.
+    Truncated,
+    /// A message or attribute length is shorter than its header.
+    InvalidLength,
+    /// The message is not a route or address message.
+    UnsupportedType(u16),
+    /// The address family is not IPv4 or IPv6.
+    UnsupportedFamily(u8),
+    /// An attribute has the wrong size for its type, or a label is not UTF-8 or is too long to
+    /// encode.
+    InvalidAttribute(u16),
+    /// A prefix length is too long for the address family.
+    InvalidPrefixLength(u8),
+    /// An address has a different family from the message.
+    MixedFamilies,
+    /// The output buffer is too small. Holds the number of bytes needed.
+    BufferTooSmall(usize),
+}
+
+impl Display for NetlinkError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            Self::Truncated => f.write_str("netlink message is truncated"),
+            Self::InvalidLength => f.write_str("invalid netlink length"),
+            Self::UnsupportedType(t) => write!(f, "unsupported netlink message type {t}"),
+            Self::UnsupportedFamily(af) => write!(f, "unsupported address family {af}"),
+            Self::InvalidAttribute(t) => write!(f, "invalid netlink attribute {t}"),
+            Self::InvalidPrefixLength(len) => write!(f, "invalid prefix length {len}"),
+            Self::MixedFamilies => f.write_str("address family does not match the message"),
+            Self::BufferTooSmall(n) => write!(f, "buffer is too small, {n} bytes needed"),
+        }
+    }
+}
+
+/// The header of a netlink message, other than its length.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct NetlinkHeader {
+    /// The message type
+    pub kind: RtnlMessageType,
+    /// The message flags
+    pub flags: NetlinkFlags,
+    /// The sequence number, chosen by the sender of a request and echoed in replies
+    pub seq: u32,
+    /// The port ID of the sender, 0 for the kernel
+    pub pid: u32,
+}
+
+const fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> u16 {
+    u16::from_ne_bytes([bytes[at], bytes[at + 1]])
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_ne_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Splits the first message off `bytes`, returning its header, its body and the length of the
+/// message including padding.
+fn split_message(bytes: &[u8]) -> Result<(NetlinkHeader, &[u8], usize), NetlinkError> {
+    if bytes.len() < HEADER_LEN {
+        return Err(NetlinkError::Truncated);
+    }
+    let len = u32_at(bytes, 0) as usize;
+    if len < HEADER_LEN {
+        return Err(NetlinkError::InvalidLength);
+    }
+    let message = bytes.get(..len).ok_or(NetlinkError::Truncated)?;
+    let header = NetlinkHeader {
+        kind: RtnlMessageType(u16_at(bytes, 4)),
+        flags: NetlinkFlags(u16_at(bytes, 6)),
+        seq: u32_at(bytes, 8),
+        pid: u32_at(bytes, 12),
+    };
+    Ok((header, &message[HEADER_LEN..], align(len).min(bytes.len())))
+}
+
+/// An iterator over the attributes of a message, as (type, payload).
+struct Attributes<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Iterator for Attributes<'a> {
+    type Item = Result<(u16, &'a [u8]), NetlinkError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        if self.bytes.len() < 4 {
+            self.bytes = &[];
+            return Some(Err(NetlinkError::Truncated));
+        }
+        let len = u16_at(self.bytes, 0) as usize;
+        // The top bits mark nested and network byte order attributes.
+        let kind = u16_at(self.bytes, 2) & 0x3fff;
+        let Some(payload) = self.bytes.get(4..len.max(4)).filter(|_| len >= 4) else {
+            self.bytes = &[];
+            return Some(Err(NetlinkError::InvalidLength));
+        };
+        self.bytes = self.bytes.get(align(len)..).unwrap_or_default();
+        Some(Ok((kind, payload)))
+    }
+}
+
+fn family_len(family: u8) -> Result<u8, NetlinkError> {
+    match family {
+        AF_INET => Ok(32),
+        AF_INET6 => Ok(128),
+        _ => Err(NetlinkError::UnsupportedFamily(family)),
+    }
+}
+
+fn parse_addr(family: u8, kind: u16, payload: &[u8]) -> Result<IpAddr, NetlinkError> {
+    match (family, payload.len()) {
+        (AF_INET, 4) => Ok(IpAddr::V4(Ipv4Addr::from_octets(
+            payload.try_into().unwrap(),
+        ))),
+        (AF_INET6, 16) => Ok(IpAddr::V6(Ipv6Addr::from_octets(
+            payload.try_into().unwrap(),
+        ))),
+        _ => Err(NetlinkError::InvalidAttribute(kind)),
+    }
+}
+
+fn parse_u32(kind: u16, payload: &[u8]) -> Result<u32, NetlinkError> {
+    match payload.len() {
+        4 => Ok(u32_at(payload, 0)),
+        _ => Err(NetlinkError::InvalidAttribute(kind)),
+    }
+}
+
+/// Builds a network of `family` from an optional address and a prefix length.
+fn network(family: u8, addr: Option<IpAddr>, len: u8) -> Result<MaskedIp, NetlinkError> {
+    if len > family_len(family)? {
+        return Err(NetlinkError::InvalidPrefixLength(len));
+    }
+    Ok(match addr {
+        Some(IpAddr::V4(ip)) => MaskedIp::V4(MaskedIpv4::cidr(ip, len)),
+        Some(IpAddr::V6(ip)) => MaskedIp::V6(MaskedIpv6::cidr(ip, len)),
+        None if family == AF_INET => MaskedIp::V4(MaskedIpv4::cidr(Ipv4Addr::from_bits(0), len)),
+        None => MaskedIp::V6(MaskedIpv6::cidr(Ipv6Addr::from_bits(0), len)),
+    })
+}
+
+/// Writes a message into a buffer, counting the bytes needed even once the buffer is full.
+struct Writer<'b> {
+    buf: &'b mut [u8],
+    len: usize,
+}
+
+impl Writer<'_> {
+    fn bytes(&mut self, bytes: &[u8]) {
+        if let Some(out) = self.buf.get_mut(self.len..self.len + bytes.len()) {
+            out.copy_from_slice(bytes);
+        }
+        self.len += bytes.len();
+    }
+    fn header(&mut self, header: &NetlinkHeader) {
+        // The length is filled in by `finish`.
+        self.bytes(&[0; 4]);
+        self.bytes(&header.kind.0.to_ne_bytes());
+        self.bytes(&header.flags.0.to_ne_bytes());
+        self.bytes(&header.seq.to_ne_bytes());
+        self.bytes(&header.pid.to_ne_bytes());
+    }
+    /// Writes an attribute whose payload is the concatenation of `parts`. Fails if the payload
+    /// is too long for the attribute's 16-bit length.
+    fn attribute(&mut self, kind: u16, parts: &[&[u8]]) -> Result<(), NetlinkError> {
+        let len: usize = parts.iter().map(|part| part.len()).sum();
+        let header_len =
+            u16::try_from(len + 4).map_err(|_| NetlinkError::InvalidAttribute(kind))?;
+        self.bytes(&header_len.to_ne_bytes());
+        self.bytes(&kind.to_ne_bytes());
+        for part in parts {
+            self.bytes(part);
+        }
+        self.bytes(&[0; 3][..align(len) - len]);
+        Ok(())
+    }
+    fn addr(&mut self, kind: u16, family: u8, addr: IpAddr) -> Result<(), NetlinkError> {
+        match (family, addr) {
+            (AF_INET, IpAddr::V4(ip)) => self.attribute(kind, &[&ip.octets()]),
+            (AF_INET6, IpAddr::V6(ip)) => self.attribute(kind, &[&ip.octets()]),
+            _ => Err(NetlinkError::MixedFamilies),
+        }
+    }
+    fn finish(self) -> Result<usize, NetlinkError> {
+        if self.len > self.buf.len() {
+            return Err(NetlinkError::BufferTooSmall(self.len));
+        }
+        self.buf[..4].copy_from_slice(&(self.len as u32).to_ne_bytes());
+        Ok(self.len)
+    }
+}
+
+/// Returns the address family and address of a network.
+fn split_network(net: MaskedIp) -> (u8, IpAddr) {
+    match net {
+        MaskedIp::V4(net) => (AF_INET, IpAddr::V4(net.ip)),
+        MaskedIp::V6(net) => (AF_INET6, IpAddr::V6(net.ip)),
+    }
+}
+
+/// The header of a new request, with the `REQUEST`, `ACK`, `CREATE` and `EXCL` flags.
+const fn new_request(kind: RtnlMessageType) -> NetlinkHeader {
+    let flags = NetlinkFlags::REQUEST.0 | NetlinkFlags::ACK.0;
+    NetlinkHeader {
+        kind,
+        flags: NetlinkFlags(flags | NetlinkFlags::CREATE.0 | NetlinkFlags::EXCL.0),
+        seq: 0,
+        pid: 0,
+    }
+}
+
+/// A `RTM_NEWROUTE`, `RTM_DELROUTE` or `RTM_GETROUTE` message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct RouteMessage {
+    /// The message header
+    pub header: NetlinkHeader,
+    /// The destination network, from `RTA_DST` and the destination length. The address family
+    /// of the message is the family of this network.
+    pub destination: MaskedIp,
+    /// The source network of a source-specific route, from `RTA_SRC`
+    pub source: Option<MaskedIp>,
+    /// The gateway, from `RTA_GATEWAY`
+    pub gateway: Option<IpAddr>,
+    /// The preferred source address, from `RTA_PREFSRC`
+    pub preferred_source: Option<IpAddr>,
+    /// The input interface index, from `RTA_IIF`
+    pub input_interface: Option<u32>,
+    /// The output interface index, from `RTA_OIF`
+    pub output_interface: Option<u32>,
+    /// The route metric, from `RTA_PRIORITY`
+    pub priority: Option<u32>,
+    /// The routing table, from `RTA_TABLE` if present
+    pub table: u32,
+    /// The type of service
+    pub tos: u8,
+    /// The protocol which installed the route
+    pub protocol: RouteProtocol,
+    /// The route scope
+    pub scope: RouteScope,
+    /// The route type
+    pub kind: RouteType,
+    /// The `RTM_F_*` flags
+    pub flags: u32,
+}
+
+impl RouteMessage {
+    /// The main routing table.
+    pub const MAIN_TABLE: u32 = 254;
+
+    /// Constructs a `RTM_NEWROUTE` request for a unicast route to `destination` in the main
+    /// table, with the `REQUEST`, `ACK`, `CREATE` and `EXCL` flags.
+    pub const fn new(destination: MaskedIp) -> Self {
+        Self {
+            header: new_request(RtnlMessageType::NEWROUTE),
+            destination,
+            source: None,
+            gateway: None,
+            preferred_source: None,
+            input_interface: None,
+            output_interface: None,
+            priority: None,
+            table: Self::MAIN_TABLE,
+            tos: 0,
+            protocol: RouteProtocol::BOOT,
+            scope: RouteScope::UNIVERSE,
+            kind: RouteType::UNICAST,
+            flags: 0,
+        }
+    }
+    /// Parses the first message of `bytes`, which must be a route message.
+    pub fn parse(bytes: &[u8]) -> Result<Self, NetlinkError> {
+        let (header, body, _) = split_message(bytes)?;
+        Self::parse_body(header, body)
+    }
+    fn parse_body(header: NetlinkHeader, body: &[u8]) -> Result<Self, NetlinkError> {
+        if !matches!(header.kind.0, 24..=26) {
+            return Err(NetlinkError::UnsupportedType(header.kind.0));
+        }
+        if body.len() < RTMSG_LEN {
+            return Err(NetlinkError::InvalidLength);
+        }
+        let family = body[0];
+        family_len(family)?;
+        let mut message = Self {
+            header,
+            destination: network(family, None, 0)?,
+            source: None,
+            gateway: None,
+            preferred_source: None,
+            input_interface: None,
+            output_interface: None,
+            priority: None,
+            table: body[4] as u32,
+            tos: body[3],
+            protocol: RouteProtocol(body[5]),
+            scope: RouteScope(body[6]),
+            kind: RouteType(body[7]),
+            flags: u32_at(body, 8),
+        };
+        let (mut dst, mut src) = (None, None);
+        let attributes = Attributes {
+            bytes: &body[RTMSG_LEN..],
+        };
+        for attribute in attributes {
+            let (kind, payload) = attribute?;
+            match kind {
+                RTA_DST => dst = Some(parse_addr(family, kind, payload)?),
+                RTA_SRC => src = Some(parse_addr(family, kind, payload)?),
+                RTA_GATEWAY => message.gateway = Some(parse_addr(family, kind, payload)?),
+                RTA_PREFSRC => message.preferred_source = Some(parse_addr(family, kind, payload)?),
+                RTA_IIF => message.input_interface = Some(parse_u32(kind, payload)?),
+                RTA_OIF => message.output_interface = Some(parse_u32(kind, payload)?),
+                RTA_PRIORITY => message.priority = Some(parse_u32(kind, payload)?),
+                RTA_TABLE => message.table = parse_u32(kind, payload)?,
+                _ => {}
+            }
+        }
+        message.destination = network(family, dst, body[1])?;
+        if src.is_some() || body[2] != 0 {
+            message.source = Some(network(family, src, body[2])?);
+        }
+        Ok(message)
+    }
+    /// Writes the message to `buf`, returning the number of bytes written.
+    ///
+    /// Attributes are written in the order the kernel uses: `RTA_TABLE`, `RTA_DST`, `RTA_SRC`,
+    /// `RTA_IIF`, `RTA_PRIORITY`, `RTA_PREFSRC`, `RTA_GATEWAY` and `RTA_OIF`. `RTA_DST` is
+    /// omitted for a zero length destination.
+    ///
+    /// # Errors
+    /// Fails with [`NetlinkError::MixedFamilies`] if an address has a different family from the
+    /// destination, or [`NetlinkError::BufferTooSmall`] if `buf` is too small.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, NetlinkError> {
+        let (family, dst) = split_network(self.destination);
+        let mut w = Writer { buf, len: 0 };
+        w.header(&self.header);
+        let table = u8::try_from(self.table).unwrap_or(RT_TABLE_COMPAT);
+        let src_len = self.source.map_or(0, |src| src.network_bits());
+        w.bytes(&[
+            family,
+            self.destination.network_bits(),
+            src_len,
+            self.tos,
+            table,
+        ]);
+        w.bytes(&[self.protocol.0, self.scope.0, self.kind.0]);
+        w.bytes(&self.flags.to_ne_bytes());
+        w.attribute(RTA_TABLE, &[&self.table.to_ne_bytes()])?;
+        if self.destination.network_bits() > 0 {
+            w.addr(RTA_DST, family, dst)?;
+        }
+        if let Some(src) = self.source {
+            w.addr(RTA_SRC, family, split_network(src).1)?;
+        }
+        if let Some(iif) = self.input_interface {
+            w.attribute(RTA_IIF, &[&iif.to_ne_bytes()])?;
+        }
+        if let Some(priority) = self.priority {
+            w.attribute(RTA_PRIORITY, &[&priority.to_ne_bytes()])?;
+        }
+        if let Some(prefsrc) = self.preferred_source {
+            w.addr(RTA_PREFSRC, family, prefsrc)?;
+        }
+        if let Some(gateway) = self.gateway {
+            w.addr(RTA_GATEWAY, family, gateway)?;
+        }
+        if let Some(oif) = self.output_interface {
+            w.attribute(RTA_OIF, &[&oif.to_ne_bytes()])?;
+        }
+        w.finish()
+    }
+}
+
+/// A `RTM_NEWADDR`, `RTM_DELADDR` or `RTM_GETADDR` message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct AddressMessage<'a> {
+    /// The message header
+    pub header: NetlinkHeader,
+    /// The interface index
+    pub index: u32,
+    /// The address and prefix length, from `IFA_ADDRESS`. For point-to-point interfaces this is
+    /// the peer address. The address family of the message is the family of this address.
+    pub address: MaskedIp,
+    /// The local address, from `IFA_LOCAL`. Set for every IPv4 address, and equal to
+    /// `address` except on point-to-point interfaces.
+    pub local: Option<IpAddr>,
+    /// The IPv4 broadcast address, from `IFA_BROADCAST`
+    pub broadcast: Option<Ipv4Addr>,
+    /// The interface label, from `IFA_LABEL`, such as `eth0` or `eth0:1`
+    pub label: Option<&'a str>,
+    /// The address scope
+    pub scope: RouteScope,
+    /// The `IFA_F_*` flags, from `IFA_FLAGS` if present
+    pub flags: u32,
+}
+
+impl<'a> AddressMessage<'a> {
+    /// Constructs a `RTM_NEWADDR` request to add `address` to interface `index`, with the
+    /// `REQUEST`, `ACK`, `CREATE` and `EXCL` flags. For IPv4 addresses `local` is set to the
+    /// address, as `ip address add` does.
+    pub const fn new(index: u32, address: MaskedIp) -> Self {
+        let local = match address {
+            MaskedIp::V4(net) => Some(IpAddr::V4(net.ip)),
+            MaskedIp::V6(_) => None,
+        };
+        Self {
+            header: new_request(RtnlMessageType::NEWADDR),
+            index,
+            address,
+            local,
+            broadcast: None,
+            label: None,
+            scope: RouteScope::UNIVERSE,
+            flags: 0,
+        }
+    }
+    /// Parses the first message of `bytes`, which must be an address message. The label
+    /// borrows from `bytes`.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, NetlinkError> {
+        let (header, body, _) = split_message(bytes)?;
+        Self::parse_body(header, body)
+    }
+    fn parse_body(header: NetlinkHeader, body: &'a [u8]) -> Result<Self, NetlinkError> {
+        if !matches!(header.kind.0, 20..=22) {
+            return Err(NetlinkError::UnsupportedType(header.kind.0));
+        }
+        if body.len() < IFADDRMSG_LEN {
+            return Err(NetlinkError::InvalidLength);
+        }
+        let family = body[0];
+        let mut message = Self {
+            header,
+            index: u32_at(body, 4),
+            address: network(family, None, 0)?,
+            local: None,
+            broadcast: None,
+            label: None,
+            scope: RouteScope(body[3]),
+            flags: body[2] as u32,
+        };
+        let mut address = None;
+        let attributes = Attributes {
+            bytes: &body[IFADDRMSG_LEN..],
+        };
+        for attribute in attributes {
+            let (kind, payload) = attribute?;
+            match kind {
+                IFA_ADDRESS => address = Some(parse_addr(family, kind, payload)?),
+                IFA_LOCAL => message.local = Some(parse_addr(family, kind, payload)?),
+                IFA_BROADCAST => match parse_addr(family, kind, payload)? {
+                    IpAddr::V4(ip) => message.broadcast = Some(ip),
+                    IpAddr::V6(_) => return Err(NetlinkError::InvalidAttribute(kind)),
+                },
+                IFA_LABEL => {
+                    let label = payload.split(|&b| b == 0).next().unwrap_or_default();
+                    let label = core::str::from_utf8(label);
+                    message.label = Some(label.map_err(|_| NetlinkError::InvalidAttribute(kind))?);
+                }
+                IFA_FLAGS => message.flags = parse_u32(kind, payload)?,
+                _ => {}
+            }
+        }
+        // An IPv4 address may be given only as the local address.
+        message.address = network(family, address.or(message.local), body[1])?;
+        Ok(message)
+    }
+    /// Writes the message to `buf`, returning the number of bytes written.
+    ///
+    /// Attributes are written in the order the kernel uses: `IFA_ADDRESS`, `IFA_LOCAL`,
+    /// `IFA_BROADCAST`, `IFA_LABEL` and `IFA_FLAGS`.
+    ///
+    /// # Errors
+    /// Fails with [`NetlinkError::MixedFamilies`] if an address has a different family from
+    /// `address`, [`NetlinkError::InvalidAttribute`] if the label is longer than 65530 bytes, or
+    /// [`NetlinkError::BufferTooSmall`] if `buf` is too small.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, NetlinkError> {
+        let (family, address) = split_network(self.address);
+        let mut w = Writer { buf, len: 0 };
+        w.header(&self.header);
+        let prefix_len = self.address.network_bits();
+        w.bytes(&[family, prefix_len, self.flags as u8, self.scope.0]);
+        w.bytes(&self.index.to_ne_bytes());
+        w.addr(IFA_ADDRESS, family, address)?;
+        if let Some(local) = self.local {
+            w.addr(IFA_LOCAL, family, local)?;
+        }
+        if let Some(broadcast) = self.broadcast {
+            w.addr(IFA_BROADCAST, family, IpAddr::V4(broadcast))?;
+        }
+        if let Some(label) = self.label {
+            w.attribute(IFA_LABEL, &[label.as_bytes(), &[0]])?;
+        }
+        w.attribute(IFA_FLAGS, &[&self.flags.to_ne_bytes()])?;
+        w.finish()
+    }
+}
+
+/// A route or address message.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum RtnlMessage<'a> {
+    /// A route message
+    Route(RouteMessage),
+    /// An address message
+    Address(AddressMessage<'a>),
+}
+
+impl<'a> RtnlMessage<'a> {
+    /// Parses the first message of `bytes`.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, NetlinkError> {
+        let (header, body, _) = split_message(bytes)?;
+        Self::parse_body(header, body)
+    }
+    fn parse_body(header: NetlinkHeader, body: &'a [u8]) -> Result<Self, NetlinkError> {
+        match header.kind.0 {
+            20..=22 => AddressMessage::parse_body(header, body).map(Self::Address),
+            24..=26 => RouteMessage::parse_body(header, body).map(Self::Route),
+            kind => Err(NetlinkError::UnsupportedType(kind)),
+        }
+    }
+    /// Writes the message to `buf`, returning the number of bytes written.
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, NetlinkError> {
+        match self {
+            Self::Route(route) => route.encode(buf),
+            Self::Address(address) => address.encode(buf),
+        }
+    }
+}
+
+/// An iterator over the messages of a buffer received from a netlink socket, such as the reply
+/// to a dump request. Iteration ends at `NLMSG_DONE`, the end of the buffer, or a message whose
+/// length is invalid. Other messages which are not route or address messages, such as
+/// `NLMSG_ERROR`, yield [`NetlinkError::UnsupportedType`].
+#[derive(Clone, Debug)]
+pub struct RtnlMessages<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> RtnlMessages<'a> {
+    /// Constructs an iterator over the messages of `bytes`.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
+    }
+}
+
+impl<'a> Iterator for RtnlMessages<'a> {
+    type Item = Result<RtnlMessage<'a>, NetlinkError>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let (header, body, len) = match split_message(self.bytes) {
+            Ok(message) => message,
+            Err(e) => {
+                self.bytes = &[];
+                return Some(Err(e));
+            }
+        };
+        if header.kind.0 == NLMSG_DONE {
+            self.bytes = &[];
+            return None;
+        }
+        self.bytes = &self.bytes[len..];
+        Some(RtnlMessage::parse_body(header, body))
+    }
+}
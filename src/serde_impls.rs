@@ -0,0 +1,70 @@
+//! Serde support. All types are serialized as strings, using the same syntax accepted by their
+//! [`FromStr`] implementations. Masked IPs are always written in CIDR notation.
+
+use crate::{IpAddr, Ipv4Addr, Ipv4Mask, Ipv6Addr, Ipv6Mask, MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use core::fmt::{Display, Formatter, Result as FmtResult};
+use core::marker::PhantomData;
+use core::str::FromStr;
+use serde::de::{Deserialize, Deserializer, Error, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    marker: PhantomData<T>,
+}
+
+impl<T> FromStrVisitor<T> {
+    const fn new(expecting: &'static str) -> Self {
+        Self {
+            expecting,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T: FromStr> Visitor<'_> for FromStrVisitor<T> {
+    type Value = T;
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        f.write_str(self.expecting)
+    }
+    fn visit_str<E: Error>(self, v: &str) -> Result<T, E> {
+        v.parse()
+            .map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+    }
+}
+
+/// Serializes `value` as a string, using the alternate (CIDR) form where one exists.
+fn serialize_display<S: Serializer>(
+    value: &impl Display,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(&format_args!("{:#}", value))
+}
+
+macro_rules! impl_serde_via_str {
+    ($($ty:ty => $expecting:literal,)*) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serialize_display(self, serializer)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_str(FromStrVisitor::new($expecting))
+            }
+        }
+    )*};
+}
+
+impl_serde_via_str! {
+    Ipv4Addr => "an IPv4 address",
+    Ipv6Addr => "an IPv6 address",
+    IpAddr => "an IP address",
+    Ipv4Mask => "an IPv4 subnet mask",
+    Ipv6Mask => "an IPv6 subnet mask",
+    MaskedIpv4 => "an IPv4 address and subnet mask",
+    MaskedIpv6 => "an IPv6 address and subnet mask",
+    MaskedIp => "an IP address and subnet mask",
+}
@@ -0,0 +1,52 @@
+//! Well-known address ranges, used to bias generated test data towards interesting addresses.
+
+use crate::{Ipv4Addr, Ipv6Addr, MaskedIpv4, MaskedIpv6};
+
+/// Special-purpose IPv4 ranges, mostly from the IANA special-purpose address registry.
+pub(crate) const SPECIAL_V4: [MaskedIpv4; 14] = [
+    MaskedIpv4::cidr(Ipv4Addr::new(0, 0, 0, 0), 8),
+    MaskedIpv4::cidr(Ipv4Addr::new(10, 0, 0, 0), 8),
+    MaskedIpv4::cidr(Ipv4Addr::new(100, 64, 0, 0), 10),
+    MaskedIpv4::cidr(Ipv4Addr::new(127, 0, 0, 0), 8),
+    MaskedIpv4::cidr(Ipv4Addr::new(169, 254, 0, 0), 16),
+    MaskedIpv4::cidr(Ipv4Addr::new(172, 16, 0, 0), 12),
+    MaskedIpv4::cidr(Ipv4Addr::new(192, 0, 2, 0), 24),
+    MaskedIpv4::cidr(Ipv4Addr::new(192, 168, 0, 0), 16),
+    MaskedIpv4::cidr(Ipv4Addr::new(198, 18, 0, 0), 15),
+    MaskedIpv4::cidr(Ipv4Addr::new(198, 51, 100, 0), 24),
+    MaskedIpv4::cidr(Ipv4Addr::new(203, 0, 113, 0), 24),
+    MaskedIpv4::cidr(Ipv4Addr::new(224, 0, 0, 0), 4),
+    MaskedIpv4::cidr(Ipv4Addr::new(240, 0, 0, 0), 4),
+    MaskedIpv4::cidr(Ipv4Addr::new(255, 255, 255, 255), 32),
+];
+
+/// Special-purpose IPv6 ranges, mostly from the IANA special-purpose address registry.
+pub(crate) const SPECIAL_V6: [MaskedIpv6; 11] = [
+    MaskedIpv6::cidr(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0), 128),
+    MaskedIpv6::cidr(Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1), 128),
+    MaskedIpv6::cidr(Ipv6Addr::new(0, 0, 0, 0, 0, 0xffff, 0, 0), 96),
+    MaskedIpv6::cidr(Ipv6Addr::new(0x64, 0xff9b, 0, 0, 0, 0, 0, 0), 96),
+    MaskedIpv6::cidr(Ipv6Addr::new(0x100, 0, 0, 0, 0, 0, 0, 0), 64),
+    MaskedIpv6::cidr(Ipv6Addr::new(0x2001, 0, 0, 0, 0, 0, 0, 0), 32),
+    MaskedIpv6::cidr(Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32),
+    MaskedIpv6::cidr(Ipv6Addr::new(0x2002, 0, 0, 0, 0, 0, 0, 0), 16),
+    MaskedIpv6::cidr(Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 0), 7),
+    MaskedIpv6::cidr(Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0), 10),
+    MaskedIpv6::cidr(Ipv6Addr::new(0xff00, 0, 0, 0, 0, 0, 0, 0), 8),
+];
+
+/// Mask lengths at the edges of the valid range for IPv4.
+pub(crate) const EDGE_LENS_V4: [u8; 5] = [0, 1, 30, 31, 32];
+
+/// Mask lengths at the edges of the valid range for IPv6.
+pub(crate) const EDGE_LENS_V6: [u8; 6] = [0, 1, 64, 126, 127, 128];
+
+/// Returns the address in `net` with the host bits taken from `bits`.
+pub(crate) fn host_in_v4(net: MaskedIpv4, bits: u32) -> Ipv4Addr {
+    net.network_address() | (bits & !net.mask.to_bits())
+}
+
+/// Returns the address in `net` with the host bits taken from `bits`.
+pub(crate) fn host_in_v6(net: MaskedIpv6, bits: u128) -> Ipv6Addr {
+    net.network_address() | (bits & !net.mask.to_bits())
+}
@@ -0,0 +1,132 @@
+//! [`proptest`] strategies for every type in this crate.
+//!
+//! The address and mask strategies are biased: roughly half of the generated values come from
+//! special-purpose ranges (loopback, private, documentation, multicast, ...) or have mask lengths
+//! at the edge of the valid range, such as /0, /31, /32, /127 and /128. The remaining values are
+//! uniformly distributed. The [`Arbitrary`] implementations use the same strategies.
+
+use crate::special::{EDGE_LENS_V4, EDGE_LENS_V6, SPECIAL_V4, SPECIAL_V6, host_in_v4, host_in_v6};
+use crate::{IpAddr, Ipv4Addr, Ipv4Mask, Ipv6Addr, Ipv6Mask, MaskedIp, MaskedIpv4, MaskedIpv6};
+
+use proptest::arbitrary::Arbitrary;
+use proptest::prelude::*;
+use proptest::sample::select;
+use proptest::strategy::BoxedStrategy;
+
+/// A uniformly distributed IPv4 address.
+pub fn any_ipv4_addr() -> impl Strategy<Value = Ipv4Addr> + Clone {
+    any::<u32>().prop_map(Ipv4Addr::from_bits)
+}
+
+/// A uniformly distributed IPv6 address.
+pub fn any_ipv6_addr() -> impl Strategy<Value = Ipv6Addr> + Clone {
+    any::<u128>().prop_map(Ipv6Addr::from_bits)
+}
+
+/// An IPv4 address inside one of the special-purpose ranges.
+pub fn special_ipv4_addr() -> impl Strategy<Value = Ipv4Addr> + Clone {
+    (select(&SPECIAL_V4[..]), any::<u32>()).prop_map(|(net, bits)| host_in_v4(net, bits))
+}
+
+/// An IPv6 address inside one of the special-purpose ranges.
+pub fn special_ipv6_addr() -> impl Strategy<Value = Ipv6Addr> + Clone {
+    (select(&SPECIAL_V6[..]), any::<u128>()).prop_map(|(net, bits)| host_in_v6(net, bits))
+}
+
+/// An IPv4 address, biased towards special-purpose ranges.
+pub fn ipv4_addr() -> impl Strategy<Value = Ipv4Addr> + Clone {
+    prop_oneof![any_ipv4_addr(), special_ipv4_addr()]
+}
+
+/// An IPv6 address, biased towards special-purpose ranges, including IPv4-mapped addresses.
+pub fn ipv6_addr() -> impl Strategy<Value = Ipv6Addr> + Clone {
+    prop_oneof![any_ipv6_addr(), special_ipv6_addr()]
+}
+
+/// An IP address of either family, biased towards special-purpose ranges.
+pub fn ip_addr() -> impl Strategy<Value = IpAddr> + Clone {
+    prop_oneof![
+        ipv4_addr().prop_map(IpAddr::V4),
+        ipv6_addr().prop_map(IpAddr::V6)
+    ]
+}
+
+/// A valid IPv4 subnet mask, biased towards /0, /1, /30, /31 and /32.
+pub fn ipv4_mask() -> impl Strategy<Value = Ipv4Mask> + Clone {
+    prop_oneof![0..=32u8, select(&EDGE_LENS_V4[..])].prop_map(Ipv4Mask::new)
+}
+
+/// A valid IPv6 subnet mask, biased towards /0, /1, /64, /126, /127 and /128.
+pub fn ipv6_mask() -> impl Strategy<Value = Ipv6Mask> + Clone {
+    prop_oneof![0..=128u8, select(&EDGE_LENS_V6[..])].prop_map(Ipv6Mask::new)
+}
+
+/// A [`MaskedIpv4`] with a biased address and mask. The address may have host bits set.
+pub fn masked_ipv4() -> impl Strategy<Value = MaskedIpv4> + Clone {
+    prop_oneof![
+        (ipv4_addr(), ipv4_mask()).prop_map(|(ip, mask)| MaskedIpv4::new(ip, mask)),
+        // A special-purpose network itself, or a host inside it.
+        (select(&SPECIAL_V4[..]), any::<u32>(), any::<bool>()).prop_map(|(net, bits, host)| {
+            if host {
+                MaskedIpv4::new(host_in_v4(net, bits), net.mask)
+            } else {
+                net
+            }
+        }),
+    ]
+}
+
+/// A [`MaskedIpv6`] with a biased address and mask. The address may have host bits set.
+pub fn masked_ipv6() -> impl Strategy<Value = MaskedIpv6> + Clone {
+    prop_oneof![
+        (ipv6_addr(), ipv6_mask()).prop_map(|(ip, mask)| MaskedIpv6::new(ip, mask)),
+        (select(&SPECIAL_V6[..]), any::<u128>(), any::<bool>()).prop_map(|(net, bits, host)| {
+            if host {
+                MaskedIpv6::new(host_in_v6(net, bits), net.mask)
+            } else {
+                net
+            }
+        }),
+    ]
+}
+
+/// A [`MaskedIp`] of either family.
+pub fn masked_ip() -> impl Strategy<Value = MaskedIp> + Clone {
+    prop_oneof![
+        masked_ipv4().prop_map(MaskedIp::V4),
+        masked_ipv6().prop_map(MaskedIp::V6)
+    ]
+}
+
+/// A [`MaskedIpv4`] with all host bits set to 0.
+pub fn ipv4_network() -> impl Strategy<Value = MaskedIpv4> + Clone {
+    masked_ipv4().prop_map(|m| m.network())
+}
+
+/// A [`MaskedIpv6`] with all host bits set to 0.
+pub fn ipv6_network() -> impl Strategy<Value = MaskedIpv6> + Clone {
+    masked_ipv6().prop_map(|m| m.network())
+}
+
+macro_rules! impl_arbitrary {
+    ($($ty:ty => $strategy:ident,)*) => {$(
+        impl Arbitrary for $ty {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<$ty>;
+            fn arbitrary_with(_: ()) -> Self::Strategy {
+                $strategy().boxed()
+            }
+        }
+    )*};
+}
+
+impl_arbitrary! {
+    Ipv4Addr => ipv4_addr,
+    Ipv6Addr => ipv6_addr,
+    IpAddr => ip_addr,
+    Ipv4Mask => ipv4_mask,
+    Ipv6Mask => ipv6_mask,
+    MaskedIpv4 => masked_ipv4,
+    MaskedIpv6 => masked_ipv6,
+    MaskedIp => masked_ip,
+}
@@ -0,0 +1,122 @@
+use crate::*;
+
+use alloc::string::ToString;
+
+fn office_acl() -> Acl {
+    let mut acl = Acl::new(Action::Deny);
+    acl.extend([
+        // 0: block a bad host
+        Rule::new(Action::Deny, cidr!("10.0.0.66/32"), cidr!("0.0.0.0/0")),
+        // 1: web from the office
+        Rule::new(Action::Allow, cidr!("10.0.0.0/8"), cidr!("192.0.2.0/24"))
+            .with_protocol(Protocol::TCP)
+            .with_dst_ports(PortRange::new(80, 443)),
+        // 2: dns anywhere
+        Rule::new(Action::Allow, cidr!("0.0.0.0/0"), cidr!("0.0.0.0/0"))
+            .with_protocol(Protocol::UDP)
+            .with_dst_ports(PortRange::single(53)),
+        // 3: ping over v6
+        Rule::new(Action::Allow, cidr!("::/0"), cidr!("2001:db8::/32"))
+            .with_protocol(Protocol::ICMPV6),
+    ]);
+    acl
+}
+
+#[test]
+fn evaluate() {
+    let acl = office_acl();
+    let web = Packet::tcp(ip!("10.1.2.3"), 50000, ip!("192.0.2.10"), 443);
+    assert_eq!(acl.first_match(&web), Some(1));
+    assert_eq!(acl.evaluate(&web), Action::Allow);
+
+    let bad = Packet::tcp(ip!("10.0.0.66"), 50000, ip!("192.0.2.10"), 443);
+    assert_eq!(acl.first_match(&bad), Some(0));
+    assert_eq!(acl.evaluate(&bad), Action::Deny);
+
+    let ssh = Packet::tcp(ip!("10.1.2.3"), 50000, ip!("192.0.2.10"), 22);
+    assert_eq!(acl.first_match(&ssh), None);
+    assert_eq!(acl.evaluate(&ssh), Action::Deny);
+
+    let dns = Packet::udp(ip!("198.51.100.1"), 5353, ip!("8.8.8.8"), 53);
+    assert_eq!(acl.evaluate(&dns), Action::Allow);
+    // a port condition never matches a packet without ports
+    let odd = Packet::new(ip!("198.51.100.1"), ip!("8.8.8.8"), Protocol::UDP);
+    assert_eq!(acl.first_match(&odd), None);
+
+    let ping = Packet::new(ip!("fe80::1"), ip!("2001:db8::1"), Protocol::ICMPV6);
+    assert_eq!(acl.first_match(&ping), Some(3));
+    // v4 rules never match v6 packets
+    let v6_web = Packet::tcp(ip!("::ffff:10.0.0.1"), 1, ip!("2001:db8::1"), 80);
+    assert_eq!(acl.first_match(&v6_web), None);
+
+    assert_eq!(Acl::new(Action::Allow).evaluate(&ssh), Action::Allow);
+}
+#[test]
+fn covers() {
+    let base = Rule::new(Action::Allow, cidr!("10.0.0.0/8"), cidr!("0.0.0.0/0"));
+    let tcp = base.with_protocol(Protocol::TCP);
+    let https = tcp.with_dst_ports(PortRange::single(443));
+    assert!(base.covers(&tcp));
+    assert!(tcp.covers(&https));
+    assert!(!https.covers(&tcp));
+    assert!(!tcp.covers(&base));
+    assert!(!https.covers(&https.with_protocol(Protocol::UDP)));
+    let narrow = Rule {
+        src: cidr!("10.1.0.0/16"),
+        ..https
+    };
+    assert!(https.covers(&narrow));
+    assert!(!narrow.covers(&https));
+    let v6 = Rule::new(Action::Allow, cidr!("::/0"), cidr!("::/0"));
+    assert!(!base.covers(&v6));
+}
+#[test]
+fn analyze() {
+    let mut acl = office_acl();
+    assert_eq!(acl.analyze(), []);
+    // 4: shadowed by the bad host rule
+    acl.push(Rule::new(
+        Action::Allow,
+        cidr!("10.0.0.66/32"),
+        cidr!("192.0.2.1/32"),
+    ));
+    // 5: redundant with dns anywhere
+    acl.push(
+        Rule::new(Action::Allow, cidr!("10.0.0.0/8"), cidr!("8.8.8.8/32"))
+            .with_protocol(Protocol::UDP)
+            .with_dst_ports(PortRange::single(53)),
+    );
+    // 6: only partly covered by rule 1
+    acl.push(
+        Rule::new(Action::Deny, cidr!("10.0.0.0/8"), cidr!("192.0.2.0/24"))
+            .with_protocol(Protocol::TCP),
+    );
+    let warnings = acl.analyze();
+    assert_eq!(
+        warnings,
+        [
+            AclWarning::Shadowed { rule: 4, by: 0 },
+            AclWarning::Redundant { rule: 5, by: 2 }
+        ]
+    );
+    assert_eq!(
+        warnings[0].to_string(),
+        "rule 4 is shadowed by rule 0 and never matches"
+    );
+}
+#[test]
+fn ports() {
+    let range = PortRange::new(1024, 65535);
+    assert!(range.contains(1024));
+    assert!(!range.contains(1023));
+    assert!(PortRange::ANY.contains_range(&range));
+    assert_eq!(range.to_string(), "1024-65535");
+    assert_eq!(PortRange::single(22).to_string(), "22");
+    assert_eq!(Protocol::TCP.to_string(), "tcp");
+    assert_eq!(Protocol(47).to_string(), "47");
+}
+#[test]
+#[should_panic]
+fn inverted_port_range() {
+    PortRange::new(2, 1);
+}
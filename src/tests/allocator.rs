@@ -0,0 +1,101 @@
+use crate::*;
+
+use alloc::vec;
+
+fn v4(s: &str) -> MaskedIpv4 {
+    s.parse().unwrap()
+}
+fn v6(s: &str) -> MaskedIpv6 {
+    s.parse().unwrap()
+}
+#[test]
+fn allocate_vlsm_first_fit() {
+    let mut pool = Ipv4SubnetAllocator::new(v4("10.0.0.0/22"));
+    assert_eq!(pool.allocate(26).unwrap(), v4("10.0.0.0/26"));
+    assert_eq!(pool.allocate(28).unwrap(), v4("10.0.0.64/28"));
+    assert_eq!(pool.allocate(23).unwrap(), v4("10.0.2.0/23"));
+    assert_eq!(pool.allocate(24).unwrap(), v4("10.0.1.0/24"));
+    assert_eq!(
+        pool.free_space(),
+        vec![v4("10.0.0.80/28"), v4("10.0.0.96/27"), v4("10.0.0.128/25")]
+    );
+    assert_eq!(pool.allocate(24), Err(AllocError::Exhausted));
+    assert_eq!(pool.allocate(21), Err(AllocError::InvalidLength));
+    assert_eq!(pool.allocate(33), Err(AllocError::InvalidLength));
+}
+#[test]
+fn allocate_best_fit_keeps_large_blocks() {
+    let mut first = Ipv4SubnetAllocator::new(v4("192.168.0.0/24"));
+    let mut best = Ipv4SubnetAllocator::with_policy(v4("192.168.0.0/24"), AllocPolicy::BestFit);
+    for pool in [&mut first, &mut best] {
+        pool.allocate(26).unwrap();
+        pool.allocate(28).unwrap();
+        pool.release(v4("192.168.0.0/26")).unwrap();
+    }
+    // Free: .0/26, .80/28, .96/27, .128/25
+    assert_eq!(first.allocate(28).unwrap(), v4("192.168.0.0/28"));
+    assert_eq!(best.allocate(28).unwrap(), v4("192.168.0.80/28"));
+}
+#[test]
+fn release_merges_buddies() {
+    let mut pool = Ipv4SubnetAllocator::new(v4("10.1.2.3/24"));
+    assert_eq!(pool.pool(), v4("10.1.2.0/24"));
+    let a = pool.allocate(26).unwrap();
+    let b = pool.allocate(26).unwrap();
+    let c = pool.allocate(25).unwrap();
+    assert!(pool.free_space().is_empty());
+    assert!(pool.is_allocated(b));
+    pool.release(a).unwrap();
+    pool.release(c).unwrap();
+    assert_eq!(pool.free_space(), vec![a, c]);
+    pool.release(b).unwrap();
+    assert_eq!(pool.free_space(), vec![v4("10.1.2.0/24")]);
+    assert!(pool.allocated().is_empty());
+    assert_eq!(pool.release(b), Err(AllocError::NotAllocated));
+}
+#[test]
+fn reserve_specific_prefixes() {
+    let mut pool = Ipv4SubnetAllocator::new(v4("10.0.0.0/24"));
+    pool.reserve(v4("10.0.0.64/27")).unwrap();
+    pool.reserve(v4("10.0.0.255/32")).unwrap();
+    assert_eq!(
+        pool.reserve(v4("10.0.0.64/26")),
+        Err(AllocError::Unavailable)
+    );
+    assert_eq!(
+        pool.reserve(v4("10.0.0.80/28")),
+        Err(AllocError::Unavailable)
+    );
+    assert_eq!(pool.reserve(v4("10.0.1.0/28")), Err(AllocError::OutOfPool));
+    assert_eq!(pool.reserve(v4("10.0.0.0/23")), Err(AllocError::OutOfPool));
+    assert_eq!(pool.allocate(26).unwrap(), v4("10.0.0.0/26"));
+    assert_eq!(pool.allocate(26).unwrap(), v4("10.0.0.128/26"));
+    assert_eq!(
+        pool.allocated(),
+        vec![
+            v4("10.0.0.0/26"),
+            v4("10.0.0.64/27"),
+            v4("10.0.0.128/26"),
+            v4("10.0.0.255/32")
+        ]
+    );
+    pool.release(v4("10.0.0.255/32")).unwrap();
+    pool.release(v4("10.0.0.64/27")).unwrap();
+    assert_eq!(
+        pool.free_space(),
+        vec![v4("10.0.0.64/26"), v4("10.0.0.192/26")]
+    );
+}
+#[test]
+fn allocate_v6() {
+    let mut pool = Ipv6SubnetAllocator::with_policy(v6("::/0"), AllocPolicy::BestFit);
+    assert_eq!(pool.allocate(128).unwrap(), v6("::/128"));
+    assert_eq!(pool.allocate(1).unwrap(), v6("8000::/1"));
+    pool.reserve(v6("2001:db8::/32")).unwrap();
+    assert_eq!(pool.allocate(48).unwrap(), v6("0:0:1::/48"));
+    pool.release(v6("::/128")).unwrap();
+    pool.release(v6("2001:db8::/32")).unwrap();
+    pool.release(v6("0:0:1::/48")).unwrap();
+    pool.release(v6("8000::/1")).unwrap();
+    assert_eq!(pool.free_space(), vec![v6("::/0")]);
+}
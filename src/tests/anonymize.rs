@@ -0,0 +1,98 @@
+use crate::*;
+
+use aes::Aes128;
+use aes::cipher::{BlockEncrypt, KeyInit};
+
+/// The key used by the Crypto-PAn reference implementation's sample program.
+const KEY: [u8; 32] = [
+    21, 34, 23, 141, 51, 164, 207, 128, 19, 10, 91, 22, 73, 144, 125, 16, 216, 152, 143, 131, 121,
+    121, 101, 39, 98, 87, 76, 45, 42, 132, 34, 2,
+];
+
+struct Aes(Aes128);
+
+impl BlockCipher for Aes {
+    fn encrypt_block(&self, block: [u8; 16]) -> [u8; 16] {
+        let mut block = block.into();
+        self.0.encrypt_block(&mut block);
+        block.into()
+    }
+}
+
+fn reference_anonymizer() -> CryptoPan<Aes> {
+    let cipher = Aes(Aes128::new(KEY[..16].into()));
+    CryptoPan::new(cipher, KEY[16..].try_into().unwrap())
+}
+#[test]
+fn crypto_pan_reference_vectors() {
+    let pan = reference_anonymizer();
+    for (orig, anon) in [
+        ("128.11.68.132", "135.242.180.132"),
+        ("129.118.74.4", "134.136.186.123"),
+        ("130.132.252.244", "133.68.164.234"),
+        ("141.223.7.43", "141.167.8.160"),
+        ("141.233.145.108", "141.129.237.235"),
+        ("152.163.225.39", "151.140.114.167"),
+        ("156.29.3.236", "147.225.12.42"),
+        ("165.247.96.84", "162.9.99.234"),
+        ("166.107.77.190", "160.132.178.185"),
+        ("192.102.249.13", "252.138.62.131"),
+    ] {
+        let orig: Ipv4Addr = orig.parse().unwrap();
+        assert_eq!(pan.anonymize_ipv4(orig), anon.parse::<Ipv4Addr>().unwrap());
+    }
+}
+#[test]
+fn crypto_pan_preserves_prefixes() {
+    let pan = reference_anonymizer();
+    let v4 = [
+        Ipv4Addr::new(10, 0, 0, 1),
+        Ipv4Addr::new(10, 0, 0, 2),
+        Ipv4Addr::new(10, 0, 1, 1),
+        Ipv4Addr::new(10, 128, 0, 1),
+        Ipv4Addr::new(192, 168, 1, 1),
+    ];
+    for a in v4 {
+        for b in v4 {
+            let common = (a.to_bits() ^ b.to_bits()).leading_zeros();
+            let (x, y) = (pan.anonymize_ipv4(a), pan.anonymize_ipv4(b));
+            assert_eq!((x.to_bits() ^ y.to_bits()).leading_zeros(), common);
+        }
+    }
+    let v6: [Ipv6Addr; 4] = [
+        "2001:db8::1".parse().unwrap(),
+        "2001:db8::2".parse().unwrap(),
+        "2001:db8:1::1".parse().unwrap(),
+        "fe80::1".parse().unwrap(),
+    ];
+    for a in v6 {
+        for b in v6 {
+            let common = (a.to_bits() ^ b.to_bits()).leading_zeros();
+            let (x, y) = (pan.anonymize_ipv6(a), pan.anonymize_ipv6(b));
+            assert_eq!((x.to_bits() ^ y.to_bits()).leading_zeros(), common);
+        }
+    }
+    assert_ne!(pan.anonymize_ipv6(v6[0]), v6[0]);
+}
+#[test]
+fn crypto_pan_with_closure() {
+    let pan = CryptoPan::new(
+        |block: [u8; 16]| u128::from_be_bytes(block).rotate_left(7).to_be_bytes(),
+        [7; 16],
+    );
+    let ip = IpAddr::V4(Ipv4Addr::new(198, 51, 100, 7));
+    assert_eq!(pan.anonymize(ip), pan.anonymize(ip));
+}
+#[test]
+fn truncate() {
+    let truncate = Truncate::new(24, 48);
+    assert_eq!(
+        truncate.anonymize_ipv4(Ipv4Addr::new(203, 0, 113, 77)),
+        Ipv4Addr::new(203, 0, 113, 0)
+    );
+    let ip: IpAddr = "2001:db8:aaaa:bbbb::1".parse().unwrap();
+    assert_eq!(
+        truncate.anonymize(ip),
+        "2001:db8:aaaa::".parse::<IpAddr>().unwrap()
+    );
+}
@@ -0,0 +1,77 @@
+use crate::*;
+
+use alloc::string::ToString;
+
+#[test]
+fn ipv4_bitmask() {
+    let mask: Ipv4BitMask = "255.0.255.0".parse().unwrap();
+    assert_eq!(mask, Ipv4BitMask::from_bits(0xff00_ff00));
+    assert_eq!(mask.count_ones(), 16);
+    assert!(!mask.is_contiguous());
+    assert_eq!(Ipv4Mask::try_from(mask), Err(NonContiguousMask));
+    assert_eq!(!mask, Ipv4BitMask::from_bytes([0, 255, 0, 255]));
+    assert_eq!(mask.to_string(), "255.0.255.0");
+    assert_eq!(ipv4!("10.20.30.40") & mask, ipv4!("10.0.30.0"));
+
+    let mask = Ipv4BitMask::from(Ipv4Mask::new(20));
+    assert_eq!(mask.to_string(), "255.255.240.0");
+    assert!(mask.is_contiguous());
+    assert_eq!(Ipv4Mask::try_from(mask), Ok(Ipv4Mask::new(20)));
+    assert_eq!(
+        Ipv4Mask::try_from(Ipv4BitMask::from_bits(0)),
+        Ok(Ipv4Mask::new(0))
+    );
+    assert!("255.0.0".parse::<Ipv4BitMask>().is_err());
+}
+#[test]
+fn ipv6_bitmask() {
+    let mask: Ipv6BitMask = "ffff::ffff".parse().unwrap();
+    assert_eq!(mask.segments(), [0xffff, 0, 0, 0, 0, 0, 0, 0xffff]);
+    assert_eq!(mask.count_ones(), 32);
+    assert_eq!(Ipv6Mask::try_from(mask), Err(NonContiguousMask));
+    assert_eq!(ipv6!("2001:db8::1:2") & mask, ipv6!("2001::2"));
+    let mask = Ipv6BitMask::from(Ipv6Mask::new(64));
+    assert_eq!(mask.to_string(), "ffff:ffff:ffff:ffff::");
+    assert_eq!(mask.to_mask(), Some(Ipv6Mask::new(64)));
+}
+#[test]
+fn ipv4_match() {
+    // match every host ending in .1 in 10.0.0.0/8
+    let m: Ipv4Match = "10.0.0.1 255.0.0.255".parse().unwrap();
+    assert!(m.matches(ipv4!("10.0.0.1")));
+    assert!(m.matches(ipv4!("10.200.17.1")));
+    assert!(!m.matches(ipv4!("10.0.0.2")));
+    assert!(!m.matches(ipv4!("11.0.0.1")));
+    assert_eq!(m.match_count(), 1 << 16);
+    assert_eq!(m.to_string(), "10.0.0.1 255.0.0.255");
+    assert_eq!(m.to_masked(), None);
+    assert_eq!(MaskedIpv4::try_from(m), Err(NonContiguousMask));
+
+    // bits of the value outside the mask are ignored
+    let m = Ipv4Match::new(ipv4!("10.99.99.1"), m.mask);
+    assert!(m.matches(ipv4!("10.1.2.1")));
+
+    let net = cidr4!("192.0.2.0/24");
+    let m = Ipv4Match::from(net);
+    assert!(m.matches(ipv4!("192.0.2.77")));
+    assert!(!m.matches(ipv4!("192.0.3.77")));
+    assert_eq!(m.match_count(), 256);
+    assert_eq!(MaskedIpv4::try_from(m), Ok(net));
+    assert_eq!(Ipv4Match::from(cidr4!("0.0.0.0/0")).match_count(), 1 << 32);
+
+    assert!("10.0.0.1/8".parse::<Ipv4Match>().is_err());
+    assert!("10.0.0.1  255.0.0.0".parse::<Ipv4Match>().is_err());
+}
+#[test]
+fn ipv6_match() {
+    let m: Ipv6Match = "2001:db8:: ffff:ffff::ffff:ffff".parse().unwrap();
+    assert!(m.matches(ipv6!("2001:db8::1:0:0")));
+    assert!(!m.matches(ipv6!("2001:db8::1")));
+    assert_eq!(m.match_count(), Some(1 << 64));
+    assert_eq!(MaskedIpv6::try_from(m), Err(NonContiguousMask));
+
+    let m = Ipv6Match::from(cidr6!("fe80::/10"));
+    assert!(m.matches(ipv6!("febf::1")));
+    assert_eq!(m.to_masked(), Some(cidr6!("fe80::/10")));
+    assert_eq!(Ipv6Match::from(cidr6!("::/0")).match_count(), None);
+}
@@ -0,0 +1,88 @@
+use crate::*;
+
+use alloc::string::ToString;
+
+#[test]
+fn classes() {
+    assert_eq!(ipv4!("0.0.0.0").class(), Ipv4Class::A);
+    assert_eq!(ipv4!("127.255.255.255").class(), Ipv4Class::A);
+    assert_eq!(ipv4!("128.0.0.0").class(), Ipv4Class::B);
+    assert_eq!(ipv4!("191.255.0.1").class(), Ipv4Class::B);
+    assert_eq!(ipv4!("192.0.2.1").class(), Ipv4Class::C);
+    assert_eq!(ipv4!("223.1.1.1").class(), Ipv4Class::C);
+    assert_eq!(ipv4!("224.0.0.1").class(), Ipv4Class::D);
+    assert_eq!(ipv4!("239.255.255.255").class(), Ipv4Class::D);
+    assert_eq!(ipv4!("240.0.0.0").class(), Ipv4Class::E);
+    assert_eq!(ipv4!("255.255.255.255").class(), Ipv4Class::E);
+}
+#[test]
+fn classful_masks() {
+    assert_eq!(
+        Ipv4Mask::classful_default(ipv4!("10.1.2.3")),
+        Some(Ipv4Mask::new(8))
+    );
+    assert_eq!(
+        Ipv4Mask::classful_default(ipv4!("172.16.0.1")),
+        Some(Ipv4Mask::new(16))
+    );
+    assert_eq!(
+        Ipv4Mask::classful_default(ipv4!("192.168.1.1")),
+        Some(Ipv4Mask::new(24))
+    );
+    assert_eq!(Ipv4Mask::classful_default(ipv4!("224.0.0.5")), None);
+    assert_eq!(
+        MaskedIpv4::from_classful(ipv4!("172.16.5.4")),
+        Some(cidr4!("172.16.5.4/16"))
+    );
+    assert_eq!(MaskedIpv4::from_classful(ipv4!("250.0.0.1")), None);
+
+    assert_eq!(
+        MaskedIpv4::from_classful_str("10.0.0.1"),
+        Some(cidr4!("10.0.0.1/8"))
+    );
+    assert_eq!(
+        MaskedIpv4::from_classful_str("10.0.0.1/24"),
+        Some(cidr4!("10.0.0.1/24"))
+    );
+    assert_eq!(
+        MaskedIpv4::from_classful_str("10.0.0.1 255.255.0.0"),
+        Some(cidr4!("10.0.0.1/16"))
+    );
+    assert_eq!(MaskedIpv4::from_classful_str("224.0.0.1"), None);
+    assert_eq!(MaskedIpv4::from_classful_str("10.0.0"), None);
+}
+#[test]
+fn classful_warnings() {
+    assert_eq!(cidr4!("10.0.0.0/8").classful_warning(), None);
+    assert_eq!(cidr4!("192.168.1.0/24").classful_warning(), None);
+    assert_eq!(cidr4!("224.0.0.0/24").classful_warning(), None);
+    assert_eq!(
+        cidr4!("10.1.0.0/16").classful_warning(),
+        Some(ClassfulWarning::Subnet {
+            classful: cidr4!("10.0.0.0/8")
+        })
+    );
+    let supernet = cidr4!("192.168.0.0/16").classful_warning();
+    assert_eq!(
+        supernet,
+        Some(ClassfulWarning::Supernet {
+            classful: cidr4!("192.168.0.0/24")
+        })
+    );
+    assert_eq!(
+        supernet.unwrap().to_string(),
+        "supernet of classful network 192.168.0.0/24"
+    );
+    let crossing = cidr4!("128.0.0.0/1").classful_warning().unwrap();
+    assert_eq!(
+        crossing,
+        ClassfulWarning::CrossesClasses {
+            first: Ipv4Class::B,
+            last: Ipv4Class::E
+        }
+    );
+    assert_eq!(
+        crossing.to_string(),
+        "crosses class boundary from class B to class E"
+    );
+}
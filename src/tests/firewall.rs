@@ -0,0 +1,320 @@
+use crate::firewall::*;
+use crate::*;
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+fn nets(networks: &[MaskedIp]) -> AddressSpec {
+    AddressSpec::Networks {
+        negated: false,
+        networks: networks.to_vec(),
+    }
+}
+fn not(networks: &[MaskedIp]) -> AddressSpec {
+    AddressSpec::Networks {
+        negated: true,
+        networks: networks.to_vec(),
+    }
+}
+fn set(name: &str, negated: bool) -> AddressSpec {
+    AddressSpec::Set {
+        negated,
+        name: name.to_string(),
+    }
+}
+fn rule(src: AddressSpec, dst: AddressSpec) -> RuleAddresses {
+    RuleAddresses { src, dst }
+}
+use AddressSpec::Any;
+
+/// Emits every rule and parses it back, checking nothing is lost.
+fn round_trip(
+    rules: &[RuleAddresses],
+    format: fn(&RuleAddresses) -> Result<String, EmitError>,
+    parse: fn(&str) -> Result<RuleAddresses, FirewallError>,
+    prefix: &str,
+) {
+    for rule in rules {
+        let Ok(text) = format(rule) else { continue };
+        let line = alloc::format!("{prefix}{text}");
+        assert_eq!(&parse(&line).unwrap(), rule, "{line}");
+    }
+}
+
+#[test]
+fn iptables() {
+    let ruleset = iptables::parse_ruleset(include_str!("fixtures/iptables-save.txt")).unwrap();
+    assert!(ruleset.sets.is_empty());
+    assert_eq!(
+        ruleset.rules,
+        [
+            rule(Any, Any),
+            rule(
+                nets(&[cidr!("10.0.0.0/8")]),
+                nets(&[cidr!("192.0.2.10/32")])
+            ),
+            rule(not(&[cidr!("192.168.0.0/16")]), Any),
+            rule(nets(&[cidr!("172.16.0.0/12")]), Any),
+            rule(set("blocklist", false), Any),
+            rule(
+                nets(&[cidr!("10.1.0.0/16"), cidr!("10.2.0.0/16")]),
+                nets(&[cidr!("198.51.100.0/24")])
+            ),
+            rule(Any, set("servers", true)),
+            rule(
+                nets(&[cidr!("fe80::/10")]),
+                nets(&[cidr!("2001:db8::1/128")])
+            ),
+        ]
+    );
+    // the older syntax puts `!` after the option
+    assert_eq!(
+        iptables::parse_rule("-A INPUT --source ! 10.0.0.1 -j DROP").unwrap(),
+        rule(not(&[cidr!("10.0.0.1/32")]), Any)
+    );
+    assert_eq!(
+        iptables::format_addresses(&ruleset.rules[2]).unwrap(),
+        "! -s 192.168.0.0/16"
+    );
+    assert_eq!(
+        iptables::format_addresses(&ruleset.rules[6]).unwrap(),
+        "-m set ! --match-set servers dst"
+    );
+    assert_eq!(iptables::format_addresses(&ruleset.rules[0]).unwrap(), "");
+    round_trip(
+        &ruleset.rules,
+        iptables::format_addresses,
+        iptables::parse_rule,
+        "-A INPUT ",
+    );
+
+    let err = iptables::parse_ruleset("*filter\n-A INPUT -s example.com -j DROP\n").unwrap_err();
+    assert_eq!(err.kind, FirewallErrorKind::InvalidAddress);
+    assert_eq!(err.to_string(), "line 2: invalid address `example.com`");
+    let err = iptables::parse_rule("-A INPUT -d").unwrap_err();
+    assert_eq!(err.kind, FirewallErrorKind::MissingAddress);
+    let err = iptables::parse_rule("-A INPUT -s 10.0.0.0/255.0.255.0").unwrap_err();
+    assert_eq!(err.kind, FirewallErrorKind::NonContiguousMask);
+}
+#[test]
+fn nftables() {
+    let ruleset = nftables::parse_ruleset(include_str!("fixtures/nftables.conf")).unwrap();
+    assert_eq!(
+        ruleset.rules,
+        [
+            rule(Any, Any),
+            rule(set("blocked", false), Any),
+            rule(
+                nets(&[cidr!("10.0.0.0/8")]),
+                nets(&[cidr!("192.0.2.10/32")])
+            ),
+            rule(not(&[cidr!("192.168.0.0/16"), cidr!("172.16.0.0/12")]), Any),
+            rule(nets(&[cidr!("fe80::/10")]), set("servers6", false)),
+            rule(Any, nets(&[cidr!("203.0.113.0/24")])),
+        ]
+    );
+    assert_eq!(ruleset.sets.len(), 2);
+    assert_eq!(ruleset.sets[0].name, "blocked");
+    assert_eq!(
+        ruleset.sets[0].networks,
+        [
+            cidr!("10.66.0.0/16"),
+            cidr!("192.0.2.1/32"),
+            cidr!("198.51.100.10/31"),
+            cidr!("198.51.100.12/31"),
+            cidr!("203.0.113.99/32"),
+        ]
+    );
+    assert_eq!(
+        ruleset.sets[1].networks,
+        [cidr!("2001:db8::/64"), cidr!("2001:db8:1::1/128")]
+    );
+
+    assert_eq!(
+        nftables::format_addresses(&ruleset.rules[3]).unwrap(),
+        "ip saddr != { 192.168.0.0/16, 172.16.0.0/12 }"
+    );
+    assert_eq!(
+        nftables::format_addresses(&ruleset.rules[4]).unwrap(),
+        "ip6 saddr fe80::/10 ip6 daddr @servers6"
+    );
+    assert_eq!(
+        nftables::format_addresses(&ruleset.rules[1]),
+        Err(EmitError::UnknownFamily)
+    );
+    assert_eq!(
+        nftables::format_addresses(&rule(nets(&[cidr!("10.0.0.0/8")]), nets(&[cidr!("::/0")]))),
+        Err(EmitError::MixedFamilies)
+    );
+    assert_eq!(
+        nftables::format_set(&ruleset.sets[1]).unwrap(),
+        "set servers6 {\n\ttype ipv6_addr\n\tflags interval\n\telements = { 2001:db8::/64, 2001:db8:1::1 }\n}"
+    );
+    round_trip(
+        &ruleset.rules,
+        nftables::format_addresses,
+        nftables::parse_rule,
+        "",
+    );
+    let sets = vec![ruleset.sets[0].clone()];
+    let text = nftables::format_set(&sets[0]).unwrap();
+    let text = alloc::format!("table ip t {{\n{text}\n}}");
+    assert_eq!(nftables::parse_ruleset(&text).unwrap().sets, sets);
+
+    let err = nftables::parse_ruleset("table ip t {\nchain c {\nip saddr 10.0.0.0/33 drop\n}\n}")
+        .unwrap_err();
+    assert_eq!((err.line, err.token.as_str()), (3, "10.0.0.0/33"));
+
+    // Set elements must be a braced list
+    let err = nftables::parse_ruleset("add element ip t s").unwrap_err();
+    assert_eq!(
+        (err.kind, err.token.as_str()),
+        (FirewallErrorKind::MissingAddress, "s")
+    );
+    let err = nftables::parse_ruleset("add element ip t s 10.0.0.1, 10.0.0.2 }").unwrap_err();
+    assert_eq!(err.kind, FirewallErrorKind::MissingAddress);
+}
+#[test]
+fn pf() {
+    let ruleset = pf::parse_ruleset(include_str!("fixtures/pf.conf")).unwrap();
+    assert_eq!(
+        ruleset.rules,
+        [
+            rule(Any, Any),
+            rule(
+                nets(&[cidr!("10.0.0.0/8")]),
+                nets(&[cidr!("192.0.2.10/32")])
+            ),
+            rule(set("bruteforce", false), Any),
+            rule(not(&[cidr!("192.168.0.0/16")]), Any),
+            rule(
+                nets(&[cidr!("10.1.0.0/16"), cidr!("10.2.0.0/16")]),
+                set("servers", false)
+            ),
+            rule(
+                nets(&[cidr!("198.51.100.10/31"), cidr!("198.51.100.12/31")]),
+                set("servers", true)
+            ),
+            rule(
+                nets(&[cidr!("fe80::/10")]),
+                nets(&[cidr!("2001:db8::1/128")])
+            ),
+        ]
+    );
+    assert_eq!(ruleset.sets[0].name, "bruteforce");
+    assert!(ruleset.sets[0].networks.is_empty());
+    assert_eq!(
+        ruleset.sets[1].networks,
+        [
+            cidr!("192.0.2.10/32"),
+            cidr!("192.0.2.20/32"),
+            cidr!("2001:db8::10/128")
+        ]
+    );
+    assert_eq!(
+        pf::format_table(&ruleset.sets[1]),
+        "table <servers> { 192.0.2.10, 192.0.2.20, 2001:db8::10 }"
+    );
+    assert_eq!(
+        pf::format_addresses(&ruleset.rules[3]).unwrap(),
+        "from ! 192.168.0.0/16 to any"
+    );
+    assert_eq!(
+        pf::format_addresses(&ruleset.rules[5]).unwrap(),
+        "from { 198.51.100.10/31, 198.51.100.12/31 } to ! <servers>"
+    );
+    assert_eq!(
+        pf::format_addresses(&rule(not(&[cidr!("10.0.0.0/8"), cidr!("11.0.0.0/8")]), Any)),
+        Err(EmitError::Negation)
+    );
+    round_trip(
+        &ruleset.rules,
+        pf::format_addresses,
+        pf::parse_rule,
+        "pass ",
+    );
+
+    let err = pf::parse_rule("pass from { 10.0.0.0/8, !10.1.0.0/16 }").unwrap_err();
+    assert_eq!(err.kind, FirewallErrorKind::Unsupported);
+    let err = pf::parse_rule("pass from $lan to any").unwrap_err();
+    assert_eq!(err.token, "$lan");
+    assert!(pf::parse_rule("pass from ! any").is_err());
+}
+#[test]
+fn cisco() {
+    let ruleset = cisco::parse_ruleset(include_str!("fixtures/cisco.txt")).unwrap();
+    assert_eq!(
+        ruleset.rules,
+        [
+            rule(nets(&[cidr!("192.0.2.0/24")]), Any),
+            rule(nets(&[cidr!("198.51.100.7/32")]), Any),
+            rule(
+                nets(&[cidr!("10.0.0.0/8")]),
+                nets(&[cidr!("192.0.2.10/32")])
+            ),
+            rule(Any, nets(&[cidr!("192.168.0.0/16")])),
+            rule(nets(&[cidr!("10.1.0.0/16")]), set("SERVERS", false)),
+            rule(Any, Any),
+            rule(Any, nets(&[cidr!("192.0.2.80/32")])),
+            rule(nets(&[cidr!("172.16.0.0/12")]), Any),
+            rule(
+                nets(&[cidr!("fe80::/10")]),
+                nets(&[cidr!("2001:db8::1/128")])
+            ),
+            rule(Any, Any),
+        ]
+    );
+    assert_eq!(
+        cisco::format_addresses(&ruleset.rules[2]).unwrap(),
+        "10.0.0.0 0.255.255.255 host 192.0.2.10"
+    );
+    assert_eq!(
+        cisco::format_addresses(&ruleset.rules[8]).unwrap(),
+        "fe80::/10 host 2001:db8::1"
+    );
+    assert_eq!(
+        cisco::format_addresses(&rule(not(&[cidr!("10.0.0.0/8")]), Any)),
+        Err(EmitError::Negation)
+    );
+    round_trip(
+        &ruleset.rules,
+        cisco::format_addresses,
+        cisco::parse_rule,
+        "permit ip ",
+    );
+
+    let err = cisco::parse_rule("access-list 101 permit ip 10.0.0.0 0.255.0.255 any").unwrap_err();
+    assert_eq!(err.kind, FirewallErrorKind::NonContiguousMask);
+    let err = cisco::parse_rule("permit tcp 10.0.0.0 any").unwrap_err();
+    assert_eq!(err.kind, FirewallErrorKind::MissingAddress);
+    for line in [
+        "permit tcp any range",
+        "permit tcp any range 1",
+        "access-list 101 permit tcp any eq",
+    ] {
+        let err = cisco::parse_rule(line).unwrap_err();
+        assert_eq!(err.kind, FirewallErrorKind::MissingAddress, "{line}");
+    }
+}
+#[test]
+fn between_formats() {
+    // iptables rules translate to pf and back without loss
+    let ruleset = iptables::parse_ruleset(include_str!("fixtures/iptables-save.txt")).unwrap();
+    let pf: Vec<_> = ruleset
+        .rules
+        .iter()
+        .map(|rule| pf::format_addresses(rule).unwrap())
+        .collect();
+    assert_eq!(
+        pf[5],
+        "from { 10.1.0.0/16, 10.2.0.0/16 } to 198.51.100.0/24"
+    );
+    for (text, rule) in pf.iter().zip(&ruleset.rules) {
+        assert_eq!(&pf::parse_rule(text).unwrap(), rule);
+    }
+    assert_eq!(
+        cisco::format_addresses(&ruleset.rules[5]),
+        Err(EmitError::List)
+    );
+}
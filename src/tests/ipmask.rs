@@ -195,6 +195,25 @@ fn build_all_v6_masks_from_u128() {
 //     assert_eq!(net.mask.len(), 16);
 // }
 #[test]
+fn host_and_network_counts() {
+    let net = |s: &str| s.parse::<MaskedIpv4>().unwrap();
+    assert_eq!(net("192.0.2.0/24").host_count(), 254);
+    assert_eq!(net("192.0.2.0/24").host_count_u64(), 254);
+    assert_eq!(net("192.0.2.0/31").host_count(), 2);
+    assert_eq!(net("192.0.2.0/31").host_count_u64(), 2);
+    assert_eq!(net("192.0.2.1/32").host_count(), 1);
+    assert_eq!(net("192.0.2.1/32").host_count_u64(), 1);
+    assert_eq!(net("0.0.0.0/0").host_count_u64(), (1 << 32) - 2);
+    assert_eq!(net("192.0.2.0/24").network_count(26), 4);
+    assert_eq!(net("192.0.2.0/24").network_count_u64(26), 4);
+    assert_eq!(net("192.0.2.0/24").network_count_u64(24), 1);
+    assert_eq!(net("0.0.0.0/0").network_count_u64(32), 1 << 32);
+
+    let net = |s: &str| s.parse::<MaskedIpv6>().unwrap();
+    assert_eq!(net("2001:db8::/64").host_count(), 1 << 64);
+    assert_eq!(net("2001:db8::/127").host_count(), 2);
+}
+#[test]
 fn masked_ipv4_contains() {
     const TEN_FOUR_12: &str = "10.0.0.4 255.240.0.0";
     let net: MaskedIpv4 = TEN_FOUR_12.parse().unwrap();
@@ -240,6 +259,58 @@ fn invalid_maskedv4_network() {
     assert!(MaskedIpv4::from_network_str("192.168.1.1 255.255.255.255 ").is_none());
     assert!(MaskedIpv4::from_network_str("192.168.1.1 255.255.255").is_none());
 }
+#[test]
+fn const_masked_tables() {
+    static ALLOWED: [MaskedIpv4; 3] = [
+        MaskedIpv4::cidr(Ipv4Addr::new(10, 0, 0, 0), 8),
+        MaskedIpv4::new(
+            Ipv4Addr::new(192, 168, 0, 0),
+            Ipv4Mask::from_octets([255, 255, 0, 0]),
+        ),
+        MaskedIpv4::new(Ipv4Addr::new(172, 16, 0, 0), Ipv4Mask::new(12)),
+    ];
+    const DOCS: MaskedIpv6 = MaskedIpv6::new(
+        Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1),
+        Ipv6Mask::from_octets([255, 255, 255, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+    );
+    const NETWORK: Ipv4Addr = ALLOWED[1].network_address();
+    const BROADCAST: Ipv4Addr = ALLOWED[2].broadcast_address();
+    const CONTAINS: bool = ALLOWED[0].contains(Ipv4Addr::new(10, 1, 2, 3));
+    const V6_NETWORK: Ipv6Addr = DOCS.network_address();
+    const V6_SEGMENTS: [u16; 8] = DOCS.mask.segments();
+    const HOSTS: u64 = ALLOWED[0].host_count_u64();
+    const V6_BITS: u8 = MaskedIp::V6(DOCS).host_bits();
+    const WILDCARD: Option<Ipv4Mask> = Ipv4Mask::from_wildcard([0, 0, 0, 255]);
+    assert_eq!(NETWORK, Ipv4Addr::new(192, 168, 0, 0));
+    assert_eq!(BROADCAST, Ipv4Addr::new(172, 31, 255, 255));
+    const { assert!(CONTAINS) };
+    assert!(!ALLOWED[2].contains(Ipv4Addr::new(172, 32, 0, 0)));
+    assert_eq!(V6_NETWORK, "2001:db8::".parse().unwrap());
+    assert_eq!(V6_SEGMENTS, [0xFFFF, 0xFFFF, 0, 0, 0, 0, 0, 0]);
+    assert_eq!(HOSTS, (1 << 24) - 2);
+    assert_eq!(V6_BITS, 96);
+    assert_eq!(WILDCARD, Some(Ipv4Mask::new(24)));
+    assert_eq!(
+        Ipv6Mask::from_bits(u128::MAX << 8),
+        Some(Ipv6Mask::new(120))
+    );
+    assert_eq!(Ipv6Mask::from_bits(1), None);
+}
+#[test]
+#[should_panic(expected = "IPv4 mask bits must be contiguous")]
+fn non_contiguous_v4_octets() {
+    let _ = Ipv4Mask::from_octets([255, 0, 255, 0]);
+}
+#[test]
+#[should_panic(expected = "IPv6 mask bits must be contiguous")]
+fn non_contiguous_v6_octets() {
+    let _ = Ipv6Mask::from_octets([0, 255, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+}
+#[test]
+#[should_panic(expected = "IPv4 mask length must be <= 32")]
+fn v4_mask_too_long() {
+    let _ = MaskedIpv4::cidr(Ipv4Addr::new(10, 0, 0, 0), 33);
+}
 // #[test]
 // fn build_maskedip() {
 //     const S: &str = "192.168.1.1/25";
@@ -0,0 +1,93 @@
+use crate::*;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Longest match by linear scan, preferring the lowest index among equal lengths.
+fn linear_v4(nets: &[MaskedIpv4], addr: Ipv4Addr) -> Option<usize> {
+    let mut best: Option<usize> = None;
+    for (i, net) in nets.iter().enumerate() {
+        if net.contains(addr) && best.is_none_or(|b| net.network_bits() > nets[b].network_bits()) {
+            best = Some(i);
+        }
+    }
+    best
+}
+
+#[test]
+fn ipv4_longest_match() {
+    let nets = [
+        cidr4!("10.0.0.0/8"),
+        cidr4!("10.1.0.0/16"),
+        cidr4!("10.1.2.0/24"),
+        cidr4!("10.1.2.128/25"),
+        cidr4!("10.1.2.3/32"),
+        cidr4!("192.0.2.0/24"),
+        cidr4!("192.0.2.77/24"),
+        cidr4!("172.16.0.0/12"),
+    ];
+    let m = Ipv4Matcher::new(nets);
+    assert_eq!(m.len(), 8);
+    assert_eq!(m.matches(ipv4!("10.9.9.9")), Some(0));
+    assert_eq!(m.matches(ipv4!("10.1.9.9")), Some(1));
+    assert_eq!(m.matches(ipv4!("10.1.2.4")), Some(2));
+    assert_eq!(m.matches(ipv4!("10.1.2.200")), Some(3));
+    assert_eq!(m.matches(ipv4!("10.1.2.3")), Some(4));
+    assert_eq!(m.matches(ipv4!("192.0.2.1")), Some(5));
+    assert_eq!(m.matches(ipv4!("172.31.255.255")), Some(7));
+    assert_eq!(m.matches(ipv4!("172.32.0.0")), None);
+    assert!(!m.contains(ipv4!("11.0.0.0")));
+
+    let addrs: Vec<Ipv4Addr> = (0..1000u32)
+        .map(|i| Ipv4Addr::from_bits(0x0a01_0000 + i * 7 + ((i % 3) << 24)))
+        .collect();
+    let mut out = vec![None; addrs.len()];
+    m.matches_batch(&addrs, &mut out);
+    for (addr, got) in addrs.iter().zip(&out) {
+        assert_eq!(*got, linear_v4(&nets, *addr), "{addr}");
+    }
+}
+#[test]
+fn edge_prefixes() {
+    let m = Ipv4Matcher::new([cidr4!("255.255.255.255/32"), cidr4!("0.0.0.0/0")]);
+    assert_eq!(m.matches(ipv4!("255.255.255.255")), Some(0));
+    assert_eq!(m.matches(ipv4!("255.255.255.254")), Some(1));
+    assert_eq!(m.matches(ipv4!("0.0.0.0")), Some(1));
+
+    let m = Ipv4Matcher::new(Vec::<MaskedIpv4>::new());
+    assert!(m.is_empty());
+    assert_eq!(m.matches(ipv4!("1.2.3.4")), None);
+
+    // a short prefix listed after a longer one must not overwrite it
+    let m = Ipv4Matcher::new([cidr4!("10.0.0.1/32"), cidr4!("10.0.0.0/8")]);
+    assert_eq!(m.matches(ipv4!("10.0.0.1")), Some(0));
+    assert_eq!(m.matches(ipv4!("10.0.0.2")), Some(1));
+}
+#[test]
+fn ipv6_longest_match() {
+    let nets = [
+        cidr6!("2001:db8::/32"),
+        cidr6!("2001:db8:1::/48"),
+        cidr6!("2001:db8:1::1/128"),
+        cidr6!("2001:db8:1:0:8000::/65"),
+        cidr6!("::/0"),
+        cidr6!("fe80::/10"),
+    ];
+    let m = Ipv6Matcher::new(nets);
+    assert_eq!(m.matches(ipv6!("2001:db8:2::1")), Some(0));
+    assert_eq!(m.matches(ipv6!("2001:db8:1::2")), Some(1));
+    assert_eq!(m.matches(ipv6!("2001:db8:1::1")), Some(2));
+    assert_eq!(m.matches(ipv6!("2001:db8:1:0:ffff::")), Some(3));
+    assert_eq!(m.matches(ipv6!("::1")), Some(4));
+    assert_eq!(m.matches(ipv6!("febf:ffff::")), Some(5));
+
+    let addrs = [ipv6!("2001:db8:1::1"), ipv6!("fe80::1"), ipv6!("2002::")];
+    let mut out = [None; 3];
+    m.matches_batch(&addrs, &mut out);
+    assert_eq!(out, [Some(2), Some(5), Some(4)]);
+}
+#[test]
+#[should_panic]
+fn batch_length_mismatch() {
+    Ipv4Matcher::new([cidr4!("10.0.0.0/8")]).matches_batch(&[ipv4!("10.0.0.1")], &mut []);
+}
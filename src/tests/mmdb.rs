@@ -0,0 +1,252 @@
+use crate::*;
+
+use alloc::collections::BTreeMap;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+fn country(code: &str) -> MmdbValue {
+    let mut map = BTreeMap::new();
+    map.insert("iso_code".to_string(), code.into());
+    let mut names = BTreeMap::new();
+    names.insert("en".to_string(), MmdbValue::from(code));
+    map.insert("names".to_string(), MmdbValue::Map(names));
+    MmdbValue::Map(map)
+}
+
+#[test]
+fn ipv6_database() {
+    let mut w = MmdbWriter::ipv6("Test-Country")
+        .with_description("en", "Test database")
+        .with_language("en")
+        .with_build_epoch(1_700_000_000);
+    w.insert(cidr4!("10.0.0.0/8"), country("AA"));
+    w.insert(cidr4!("10.1.0.0/16"), country("BB"));
+    w.insert(cidr6!("2001:db8::/32"), country("CC"));
+    w.insert(cidr6!("2001:db8:1::/48"), country("AA"));
+    let bytes = w.to_bytes().unwrap();
+    let db = MmdbReader::new(&bytes).unwrap();
+
+    let meta = db.metadata();
+    assert_eq!(meta.database_type, "Test-Country");
+    assert_eq!(meta.ip_version, 6);
+    assert_eq!(meta.record_size, 24);
+    assert_eq!(meta.build_epoch, 1_700_000_000);
+    assert_eq!(meta.languages, ["en"]);
+    assert_eq!(meta.description["en"], "Test database");
+
+    // 10.1.0.0/16 splits 10.0.0.0/8, so the record containing 10.2.3.4 is smaller.
+    let (net, value) = db.lookup(ip!("10.2.3.4")).unwrap().unwrap();
+    assert_eq!(net, cidr!("10.2.0.0/15"));
+    let (net, _) = db.lookup(ip!("10.200.0.0")).unwrap().unwrap();
+    assert_eq!(net, cidr!("10.128.0.0/9"));
+    assert_eq!(
+        value.get("iso_code").and_then(MmdbValue::as_str),
+        Some("AA")
+    );
+    let (net, value) = db.lookup(ip!("10.1.3.4")).unwrap().unwrap();
+    assert_eq!(net, cidr!("10.1.0.0/16"));
+    assert_eq!(value, country("BB"));
+    let (net, value) = db.lookup(ip!("2001:db8:1::5")).unwrap().unwrap();
+    assert_eq!(net, cidr!("2001:db8:1::/48"));
+    assert_eq!(value, country("AA"));
+    let (net, _) = db.lookup(ip!("2001:db8:ffff::")).unwrap().unwrap();
+    assert_eq!(net, cidr!("2001:db8:8000::/33"));
+    assert_eq!(db.lookup(ip!("11.0.0.0")).unwrap(), None);
+    assert_eq!(db.lookup(ip!("2001:db9::")).unwrap(), None);
+    // IPv4 networks are stored under ::/96.
+    assert!(db.lookup(ip!("::10.0.0.1")).unwrap().is_some());
+    assert_eq!(db.lookup(ip!("::ffff:10.0.0.1")).unwrap(), None);
+}
+
+#[test]
+fn ipv4_database() {
+    let mut w = MmdbWriter::ipv4("Test-Asn");
+    w.insert(cidr4!("0.0.0.0/0"), MmdbValue::U32(0));
+    w.insert(cidr4!("192.0.2.77/24"), MmdbValue::U32(64496));
+    w.insert(cidr4!("192.0.2.0/24"), MmdbValue::U32(64497));
+    let bytes = w.to_bytes().unwrap();
+    let db = MmdbReader::new(&bytes).unwrap();
+    assert_eq!(db.metadata().ip_version, 4);
+    assert_eq!(
+        db.lookup(ip!("192.0.2.1")).unwrap(),
+        Some((cidr!("192.0.2.0/24"), MmdbValue::U32(64497)))
+    );
+    assert_eq!(
+        db.lookup(ip!("8.8.8.8")).unwrap(),
+        Some((cidr!("0.0.0.0/1"), MmdbValue::U32(0)))
+    );
+    assert_eq!(db.lookup(ip!("::1")), Err(MmdbError::Ipv6InIpv4Database));
+
+    let mut w = MmdbWriter::ipv4("Test-Asn");
+    w.insert(cidr6!("::/0"), MmdbValue::U32(0));
+    assert_eq!(w.to_bytes(), Err(MmdbError::Ipv6InIpv4Database));
+}
+
+#[test]
+fn ipv4_in_ipv6_default_route() {
+    let mut w = MmdbWriter::ipv6("Test");
+    w.insert(cidr6!("::/0"), MmdbValue::Bool(true));
+    let bytes = w.to_bytes().unwrap();
+    let db = MmdbReader::new(&bytes).unwrap();
+    assert_eq!(
+        db.lookup(ip!("1.2.3.4")).unwrap(),
+        Some((cidr!("0.0.0.0/0"), MmdbValue::Bool(true)))
+    );
+    assert_eq!(
+        db.lookup(ip!("2001:db8::")).unwrap(),
+        Some((cidr!("::/1"), MmdbValue::Bool(true)))
+    );
+}
+
+#[test]
+fn value_types_round_trip() {
+    let long = "x".repeat(70000);
+    let mut map = BTreeMap::new();
+    map.insert("string".to_string(), MmdbValue::from("hello"));
+    map.insert("empty".to_string(), MmdbValue::from(""));
+    map.insert("medium".to_string(), MmdbValue::from("y".repeat(300)));
+    map.insert("long".to_string(), MmdbValue::from(long.as_str()));
+    map.insert("double".to_string(), MmdbValue::Double(-1.5));
+    map.insert("float".to_string(), MmdbValue::Float(0.25));
+    map.insert("bytes".to_string(), MmdbValue::Bytes(vec![0, 1, 255]));
+    map.insert("u16".to_string(), MmdbValue::U16(0));
+    map.insert("u32".to_string(), MmdbValue::U32(0x0102_0304));
+    map.insert("u64".to_string(), MmdbValue::U64(u64::MAX));
+    map.insert("u128".to_string(), MmdbValue::U128(1 << 100));
+    map.insert("i32".to_string(), MmdbValue::I32(-2));
+    map.insert("true".to_string(), MmdbValue::Bool(true));
+    map.insert("false".to_string(), MmdbValue::Bool(false));
+    map.insert(
+        "array".to_string(),
+        MmdbValue::Array(vec![MmdbValue::U32(1), MmdbValue::Array(vec![])]),
+    );
+    let value = MmdbValue::Map(map);
+
+    let mut w = MmdbWriter::ipv6("Test");
+    w.insert(cidr6!("2001:db8::/32"), value.clone());
+    let bytes = w.to_bytes().unwrap();
+    let db = MmdbReader::new(&bytes).unwrap();
+    let (_, found) = db.lookup(ip!("2001:db8::1")).unwrap().unwrap();
+    assert_eq!(found, value);
+    assert_eq!(found.get("u64").and_then(MmdbValue::as_u64), Some(u64::MAX));
+    assert_eq!(
+        found.get("long").and_then(MmdbValue::as_str),
+        Some(long.as_str())
+    );
+}
+
+#[test]
+fn spec_encoding() {
+    // The example map from the specification: {"en": "Germany"}.
+    let mut w = MmdbWriter::ipv4("Test");
+    let mut map = BTreeMap::new();
+    map.insert("en".to_string(), MmdbValue::from("Germany"));
+    w.insert(cidr4!("0.0.0.0/1"), MmdbValue::Map(map));
+    let bytes = w.to_bytes().unwrap();
+    // One node of two 24-bit records, then 16 zero bytes, then the data section.
+    assert_eq!(&bytes[..6], [0, 0, 17, 0, 0, 1]);
+    assert_eq!(&bytes[6..22], [0; 16]);
+    assert_eq!(&bytes[22..34], b"\xe1\x42en\x47Germany");
+}
+
+#[test]
+fn pointers() {
+    let mut w = MmdbWriter::ipv4("Test");
+    let mut map = BTreeMap::new();
+    map.insert("a".to_string(), MmdbValue::from("bcdef"));
+    w.insert(cidr4!("0.0.0.0/1"), MmdbValue::Map(map));
+    let mut bytes = w.to_bytes().unwrap();
+    // Replace the value with {"key": "x"}, where the key is a pointer to a string after the map.
+    bytes[22..31].copy_from_slice(b"\xe1\x20\x05\x41x\x43key");
+    let db = MmdbReader::new(&bytes).unwrap();
+    let (_, value) = db.lookup(ip!("1.1.1.1")).unwrap().unwrap();
+    assert_eq!(value.get("key"), Some(&MmdbValue::from("x")));
+
+    // A pointer to a pointer is invalid.
+    bytes[22..31].copy_from_slice(b"\xe1\x20\x01\x41x\x43key");
+    let db = MmdbReader::new(&bytes).unwrap();
+    assert_eq!(db.lookup(ip!("1.1.1.1")), Err(MmdbError::InvalidData));
+}
+
+#[test]
+fn pointer_fan_out() {
+    let mut w = MmdbWriter::ipv4("Test");
+    w.insert(cidr4!("0.0.0.0/1"), MmdbValue::Bool(true));
+    let bytes = w.to_bytes().unwrap();
+    let marker = bytes
+        .windows(14)
+        .rposition(|w| w == b"\xab\xcd\xefMaxMind.com")
+        .unwrap();
+    // Each level is an array of two pointers to the next level, so the value expands to 2^40
+    // booleans. Decoding gives up once too many values have been read.
+    let levels = 40;
+    let mut data = Vec::new();
+    for level in 0..levels {
+        let next = 6 * (level + 1);
+        let pointer = [0x20 | (next >> 8) as u8, next as u8];
+        data.extend_from_slice(&[0x02, 0x04]);
+        data.extend_from_slice(&pointer);
+        data.extend_from_slice(&pointer);
+    }
+    data.extend_from_slice(&[0x01, 0x07]);
+    let bytes = [&bytes[..22], &data, &bytes[marker..]].concat();
+    let db = MmdbReader::new(&bytes).unwrap();
+    assert_eq!(db.lookup(ip!("1.1.1.1")), Err(MmdbError::InvalidData));
+}
+
+#[test]
+fn extended_types() {
+    let mut w = MmdbWriter::ipv4("Test");
+    w.insert(cidr4!("0.0.0.0/1"), MmdbValue::Bool(true));
+    let bytes = w.to_bytes().unwrap();
+    let marker = bytes
+        .windows(14)
+        .rposition(|w| w == b"\xab\xcd\xefMaxMind.com")
+        .unwrap();
+    // Extended types run from 8 to 15. An extended byte of 0 would be a map, and 249 and above
+    // overflow.
+    for extended in [0, 9, 249, 255] {
+        let data = [0x00, extended];
+        let bytes = [&bytes[..22], &data, &bytes[marker..]].concat();
+        let db = MmdbReader::new(&bytes).unwrap();
+        assert_eq!(
+            db.lookup(ip!("1.1.1.1")),
+            Err(MmdbError::InvalidData),
+            "{extended}"
+        );
+        let metadata = [b"\xab\xcd\xefMaxMind.com", &data[..]].concat();
+        assert_eq!(
+            MmdbReader::new(&metadata).err(),
+            Some(MmdbError::InvalidMetadata),
+            "{extended}"
+        );
+    }
+}
+
+#[test]
+fn errors() {
+    assert_eq!(MmdbReader::new(b"").err(), Some(MmdbError::MissingMetadata));
+    assert_eq!(
+        MmdbReader::new(b"\xab\xcd\xefMaxMind.com\xe0").err(),
+        Some(MmdbError::InvalidMetadata)
+    );
+    let bytes = MmdbWriter::ipv6("Test").to_bytes().unwrap();
+    assert!(MmdbReader::new(&bytes).is_ok());
+    // Truncating the tree leaves too little room before the metadata.
+    let marker = bytes.len() - bytes.iter().rev().position(|&b| b == 0xab).unwrap() - 1;
+    let truncated = [&bytes[..10], &bytes[marker..]].concat();
+    assert_eq!(
+        MmdbReader::new(&truncated).err(),
+        Some(MmdbError::InvalidTree)
+    );
+    // Change the major version, the first metadata value, to 3.
+    let key = b"binary_format_major_version";
+    let at = bytes.windows(key.len()).position(|w| w == key).unwrap() + key.len();
+    let mut bad = bytes.clone();
+    bad[at + 1] = 3;
+    assert_eq!(
+        MmdbReader::new(&bad).err(),
+        Some(MmdbError::UnsupportedVersion(3))
+    );
+}
@@ -1,2 +1,49 @@
+#[cfg(feature = "alloc")]
+mod acl;
+#[cfg(feature = "alloc")]
+mod allocator;
+mod anonymize;
+#[cfg(feature = "alloc")]
+mod bitmask;
+#[cfg(feature = "alloc")]
+mod classful;
+#[cfg(feature = "alloc")]
+mod firewall;
 mod ipbitwise;
 mod ipmask;
+#[cfg(feature = "alloc")]
+mod matcher;
+#[cfg(feature = "mmdb")]
+mod mmdb;
+#[cfg(feature = "alloc")]
+mod network;
+mod parse;
+#[cfg(feature = "alloc")]
+mod pool;
+#[cfg(feature = "postgres")]
+mod postgres;
+#[cfg(all(feature = "alloc", feature = "bytemuck"))]
+mod prefix_db;
+#[cfg(feature = "alloc")]
+mod prefix_list;
+#[cfg(feature = "alloc")]
+mod proc_net;
+#[cfg(all(
+    feature = "alloc",
+    feature = "proptest",
+    feature = "arbitrary",
+    feature = "serde"
+))]
+mod properties;
+#[cfg(all(feature = "alloc", feature = "rand"))]
+mod random;
+#[cfg(feature = "alloc")]
+mod ranges;
+#[cfg(feature = "alloc")]
+mod relation;
+#[cfg(feature = "alloc")]
+mod rir;
+#[cfg(feature = "alloc")]
+mod rtnetlink;
+#[cfg(feature = "serde")]
+mod serde;
@@ -0,0 +1,88 @@
+use crate::*;
+
+use alloc::string::ToString;
+
+#[test]
+fn strict_parsing() {
+    let net: Ipv4Network = "10.0.0.0/8".parse().unwrap();
+    assert_eq!(net.address(), ipv4!("10.0.0.0"));
+    assert_eq!(net.network_bits(), 8);
+    assert_eq!(net.to_string(), "10.0.0.0/8");
+    assert_eq!("10.0.0.0 255.0.0.0".parse::<Ipv4Network>(), Ok(net));
+    assert_eq!(
+        "10.0.0.5/8".parse::<Ipv4Network>(),
+        Err(InvalidNetwork::HostBitsSet)
+    );
+    assert_eq!(
+        "10.0.0.5/33".parse::<Ipv4Network>(),
+        Err(InvalidNetwork::Syntax)
+    );
+    assert_eq!(Ipv4Network::parse_truncate("10.0.0.5/8"), Ok(net));
+    assert_eq!(
+        Ipv4Network::parse_truncate("10.0.0.5"),
+        Err(InvalidNetwork::Syntax)
+    );
+
+    assert_eq!(
+        "2001:db8::1/32".parse::<Ipv6Network>(),
+        Err(InvalidNetwork::HostBitsSet)
+    );
+    let net6 = Ipv6Network::parse_truncate("2001:db8::1/32").unwrap();
+    assert_eq!(net6.to_string(), "2001:db8::/32");
+    assert!(net6.contains(ipv6!("2001:db8:ffff::1")));
+
+    assert_eq!(
+        "192.0.2.1/24".parse::<IpNetwork>(),
+        Err(InvalidNetwork::HostBitsSet)
+    );
+    assert_eq!(
+        "192.0.2.0/24".parse::<IpNetwork>().unwrap().to_masked(),
+        MaskedIp::V4(cidr4!("192.0.2.0/24"))
+    );
+    assert_eq!(
+        IpNetwork::parse_truncate("2001:db8::1/32"),
+        Ok(IpNetwork::V6(net6))
+    );
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(
+        Ipv4Network::try_from(cidr4!("10.1.2.3/16")),
+        Err(HostBitsSet)
+    );
+    assert_eq!(
+        Ipv4Network::truncate(cidr4!("10.1.2.3/16")).to_masked(),
+        cidr4!("10.1.0.0/16")
+    );
+    let net = Ipv4Network::try_from(cidr4!("10.1.0.0/16")).unwrap();
+    assert_eq!(MaskedIpv4::from(net), cidr4!("10.1.0.0/16"));
+    assert_eq!(Ipv4Network::cidr(ipv4!("10.1.0.0"), 16), Ok(net));
+    assert_eq!(Ipv4Network::cidr(ipv4!("10.1.0.0"), 15), Err(HostBitsSet));
+    assert_eq!(
+        Ipv4Network::from(ipv4!("192.0.2.1")).to_masked(),
+        cidr4!("192.0.2.1/32")
+    );
+
+    assert_eq!(
+        Ipv6Network::try_from(cidr6!("fe80::1/64")),
+        Err(HostBitsSet)
+    );
+    assert!(Ipv6Network::try_from(cidr6!("fe80::/64")).is_ok());
+    assert_eq!(
+        IpNetwork::try_from(MaskedIp::V6(cidr6!("fe80::1/64"))),
+        Err(HostBitsSet)
+    );
+    assert_eq!(
+        IpNetwork::truncate(MaskedIp::V6(cidr6!("fe80::1/64"))).to_masked(),
+        MaskedIp::V6(cidr6!("fe80::/64"))
+    );
+    assert_eq!(
+        IpNetwork::from(IpAddr::V6(ipv6!("::1"))).network_bits(),
+        128
+    );
+    assert_eq!(
+        InvalidNetwork::from(HostBitsSet),
+        InvalidNetwork::HostBitsSet
+    );
+}
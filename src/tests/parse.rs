@@ -0,0 +1,243 @@
+use crate::*;
+
+const V4: &[&str] = &[
+    "0.0.0.0",
+    "255.255.255.255",
+    "192.168.1.1",
+    "1.2.3.4",
+    "01.2.3.4",
+    "1.2.3.04",
+    "256.0.0.1",
+    "1.2.3",
+    "1.2.3.4.5",
+    "1.2.3.",
+    ".1.2.3",
+    "1..2.3",
+    "1.2.3.4 ",
+    " 1.2.3.4",
+    "1.2.3.a",
+    "1000.2.3.4",
+    "",
+    "::1",
+];
+
+const V6: &[&str] = &[
+    "::",
+    "::1",
+    "1::",
+    "1::2",
+    "2001:db8::ff00:42:8329",
+    "2001:0db8:0000:0000:0000:ff00:0042:8329",
+    "1:2:3:4:5:6:7:8",
+    "1:2:3:4:5:6:7::",
+    "::2:3:4:5:6:7:8",
+    "1:2:3::5:6:7:8",
+    "FFFF:ffff::AbCd",
+    "::ffff:192.0.2.1",
+    "::192.0.2.1",
+    "1:2:3:4:5:6:1.2.3.4",
+    "1:2:3:4:5::1.2.3.4",
+    "1:2:3:4:5:6:7:1.2.3.4",
+    "::1.2.3.4:5",
+    "::01.2.3.4",
+    "1:2:3:4:5:6:7:8:9",
+    "1:2:3:4:5:6:7",
+    "1::2::3",
+    ":::",
+    ":1::",
+    "1:",
+    ":1",
+    "1::2:",
+    "12345::",
+    "g::",
+    "1:2:3:4:5:6:7:8::",
+    "::1:2:3:4:5:6:7:8",
+    "",
+    ":",
+    "1.2.3.4",
+];
+
+#[test]
+fn const_addrs_match_from_str() {
+    for s in V4 {
+        let expected = s.parse::<Ipv4Addr>().ok();
+        assert_eq!(Ipv4Addr::from_str_const(s), expected, "{s:?}");
+    }
+    for s in V6 {
+        let expected = s.parse::<Ipv6Addr>().ok();
+        assert_eq!(Ipv6Addr::from_str_const(s), expected, "{s:?}");
+    }
+    for s in V4.iter().chain(V6) {
+        let expected = s.parse::<IpAddr>().ok();
+        assert_eq!(IpAddr::from_str_const(s), expected, "{s:?}");
+    }
+}
+#[test]
+fn const_masked_match_from_str() {
+    let masked = [
+        "10.0.0.0/8",
+        "10.0.0.1/32",
+        "10.0.0.1/33",
+        "10.0.0.1/",
+        "10.0.0.1/08",
+        "10.0.0.0/+8",
+        "10.0.0.0/0008",
+        "10.0.0.0/00000000032",
+        "10.0.0.0/+",
+        "10.0.0.0/++8",
+        "10.0.0.0/-8",
+        "10.0.0.0/+33",
+        "10.0.0.1/a",
+        "10.0.0.1 255.0.0.0",
+        "10.0.0.1 255.0.255.0",
+        "10.0.0.1  255.0.0.0",
+        "10.0.0.1",
+        "2001:db8::/32",
+        "2001:db8::1/128",
+        "2001:db8::/129",
+        "2001:db8::/+32",
+        "2001:db8::/0032",
+        "2001:db8::/000128",
+        "::/0",
+        "::ffff:1.2.3.4/96",
+        "2001:db8:: ffff::",
+        "2001:db8::",
+    ];
+    for s in masked {
+        assert_eq!(
+            MaskedIpv4::from_str_const(s),
+            s.parse::<MaskedIpv4>().ok(),
+            "{s:?}"
+        );
+        assert_eq!(
+            MaskedIpv6::from_str_const(s),
+            s.parse::<MaskedIpv6>().ok(),
+            "{s:?}"
+        );
+        assert_eq!(
+            MaskedIp::from_str_const(s),
+            s.parse::<MaskedIp>().ok(),
+            "{s:?}"
+        );
+    }
+    // MaskedIpv4's FromStr also accepts a `/len` after the space, but MaskedIp's does not.
+    assert_eq!(MaskedIpv4::from_str_const("10.0.0.1 /8"), None);
+    for s in [
+        "/24",
+        "/+24",
+        "/0024",
+        "255.255.0.0",
+        "255.0.255.0",
+        "/33",
+        "0.0.0.0",
+    ] {
+        assert_eq!(Ipv4Mask::from_str_const(s), s.parse().ok(), "{s:?}");
+    }
+    for s in [
+        "/64",
+        "/+64",
+        "/00064",
+        "ffff:ffff::",
+        "ffff::ffff",
+        "/129",
+        "::",
+    ] {
+        assert_eq!(Ipv6Mask::from_str_const(s), s.parse().ok(), "{s:?}");
+    }
+}
+#[test]
+fn literal_macros() {
+    const GATEWAY: Ipv4Addr = ipv4!("192.168.1.1");
+    static NETS: [MaskedIp; 3] = [
+        cidr!("10.0.0.0/8"),
+        cidr!("192.168.0.0 255.255.0.0"),
+        cidr!("fe80::/10"),
+    ];
+    assert_eq!(GATEWAY, Ipv4Addr::new(192, 168, 1, 1));
+    assert_eq!(ipv6!("::1"), Ipv6Addr::LOCALHOST);
+    assert_eq!(ip!("127.0.0.1"), IpAddr::V4(Ipv4Addr::LOCALHOST));
+    assert_eq!(cidr4!("10.1.2.3/8"), "10.1.2.3/8".parse().unwrap());
+    assert_eq!(cidr6!("2001:db8::/32"), "2001:db8::/32".parse().unwrap());
+    assert!(NETS[1].contains(ip!("192.168.44.1")));
+    assert_eq!(NETS[2].network_bits(), 10);
+}
+#[test]
+fn ascii_matches_from_str() {
+    for s in V4.iter().chain(V6) {
+        let b = s.as_bytes();
+        assert_eq!(Ipv4Addr::parse_ascii(b), s.parse(), "{s:?}");
+        assert_eq!(Ipv6Addr::parse_ascii(b), s.parse(), "{s:?}");
+        assert_eq!(IpAddr::parse_ascii(b), s.parse(), "{s:?}");
+    }
+    for s in [
+        "10.0.0.0/8",
+        "10.0.0.1 255.0.255.0",
+        "10.0.0.0/+8",
+        "10.0.0.0/0008",
+        "10.0.0.0/+",
+        "2001:db8::/32",
+        "2001:db8::/+32",
+        "2001:db8::/0032",
+        "::/129",
+        "",
+    ] {
+        let b = s.as_bytes();
+        assert_eq!(MaskedIpv4::parse_ascii(b).ok(), s.parse().ok(), "{s:?}");
+        assert_eq!(MaskedIpv6::parse_ascii(b).ok(), s.parse().ok(), "{s:?}");
+        assert_eq!(MaskedIp::parse_ascii(b).ok(), s.parse().ok(), "{s:?}");
+    }
+    assert_eq!(
+        Ipv4Mask::parse_ascii(b"255.255.0.0").ok(),
+        Some(Ipv4Mask::new(16))
+    );
+    assert!(Ipv4Mask::parse_ascii(b"255.0.255.0").is_err());
+    assert_eq!(Ipv6Mask::parse_ascii(b"/64").ok(), Some(Ipv6Mask::new(64)));
+    assert!(Ipv4Addr::parse_ascii(b"1.2.3.\xff").is_err());
+}
+#[test]
+fn scan_ascii() {
+    let line = b"192.0.2.1:443 -> 2001:db8::1, via fe80::1%eth0";
+    assert_eq!(Ipv4Addr::scan_ascii(line), Some((ipv4!("192.0.2.1"), 9)));
+    assert_eq!(IpAddr::scan_ascii(line), Some((ip!("192.0.2.1"), 9)));
+    assert_eq!(Ipv6Addr::scan_ascii(line), None);
+    assert_eq!(
+        IpAddr::scan_ascii(&line[17..]),
+        Some((ip!("2001:db8::1"), 11))
+    );
+    assert_eq!(
+        Ipv6Addr::scan_ascii(&line[34..]),
+        Some((ipv6!("fe80::1"), 7))
+    );
+    assert_eq!(
+        IpAddr::scan_ascii(b"::ffff:192.0.2.1."),
+        Some((ip!("::ffff:192.0.2.1"), 16))
+    );
+    assert_eq!(IpAddr::scan_ascii(b"1.2.3.4."), Some((ip!("1.2.3.4"), 7)));
+
+    // An address must not run into a letter or digit.
+    assert_eq!(Ipv4Addr::scan_ascii(b"1.2.3.456"), None);
+    assert_eq!(Ipv4Addr::scan_ascii(b"1.2.3.4x"), None);
+    assert_eq!(Ipv4Addr::scan_ascii(b"255.255.255.2555"), None);
+    assert_eq!(IpAddr::scan_ascii(b"dead:beefy"), None);
+    assert_eq!(IpAddr::scan_ascii(b""), None);
+
+    assert_eq!(
+        MaskedIp::scan_ascii(b"10.0.0.0/8, 2001:db8::/32"),
+        Some((cidr!("10.0.0.0/8"), 10))
+    );
+    assert_eq!(
+        MaskedIp::scan_ascii(b"2001:db8::/32]"),
+        Some((cidr!("2001:db8::/32"), 13))
+    );
+    assert_eq!(MaskedIpv4::scan_ascii(b"10.0.0.0 255.0.0.0"), None);
+    assert_eq!(MaskedIpv4::scan_ascii(b"10.0.0.0/33"), None);
+    assert_eq!(MaskedIpv4::scan_ascii(b"10.0.0.0/8x"), None);
+    assert_eq!(
+        MaskedIpv4::scan_ascii(b"10.0.0.0/+08 "),
+        Some((cidr4!("10.0.0.0/8"), 12))
+    );
+    assert_eq!(
+        MaskedIpv6::scan_ascii(b"::/128"),
+        Some((cidr6!("::/128"), 6))
+    );
+}
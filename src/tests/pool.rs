@@ -0,0 +1,101 @@
+use crate::*;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+#[test]
+fn allocate_skips_reserved() {
+    let net: MaskedIpv4 = "192.168.1.77/29".parse().unwrap();
+    let mut pool = AddressPool::new(net).unwrap();
+    pool.set_gateway(ip("192.168.1.73")).unwrap();
+    pool.exclude(ip("192.168.1.75"), ip("192.168.1.76"))
+        .unwrap();
+    assert_eq!(pool.available(), 3);
+    assert!(pool.is_reserved(ip("192.168.1.72")));
+    assert!(pool.is_reserved(ip("192.168.1.79")));
+    assert_eq!(pool.allocate(), Some(ip("192.168.1.74")));
+    assert_eq!(pool.allocate(), Some(ip("192.168.1.77")));
+    assert_eq!(pool.allocate(), Some(ip("192.168.1.78")));
+    assert_eq!(pool.allocate(), None);
+    pool.release(ip("192.168.1.77")).unwrap();
+    assert_eq!(pool.allocate(), Some(ip("192.168.1.77")));
+}
+#[test]
+fn allocate_specific_and_release() {
+    let mut pool = AddressPool::new("10.0.0.0/24".parse::<MaskedIp>().unwrap()).unwrap();
+    pool.allocate_specific(ip("10.0.0.200")).unwrap();
+    assert!(pool.is_allocated(ip("10.0.0.200")));
+    assert_eq!(
+        pool.allocate_specific(ip("10.0.0.200")),
+        Err(PoolError::InUse)
+    );
+    assert_eq!(
+        pool.allocate_specific(ip("10.0.0.0")),
+        Err(PoolError::Reserved)
+    );
+    assert_eq!(
+        pool.allocate_specific(ip("10.0.0.255")),
+        Err(PoolError::Reserved)
+    );
+    assert_eq!(
+        pool.allocate_specific(ip("10.0.1.1")),
+        Err(PoolError::OutOfPool)
+    );
+    assert_eq!(pool.allocate_specific(ip("::1")), Err(PoolError::OutOfPool));
+    assert_eq!(pool.set_gateway(ip("10.0.0.200")), Err(PoolError::InUse));
+    assert_eq!(pool.gateway(), None);
+    assert_eq!(
+        pool.exclude(ip("10.0.0.100"), ip("10.0.0.250")),
+        Err(PoolError::InUse)
+    );
+    assert_eq!(
+        pool.exclude(ip("10.0.0.9"), ip("10.0.0.8")),
+        Err(PoolError::InvalidRange)
+    );
+    assert!(pool.excluded().is_empty());
+    pool.release(ip("10.0.0.200")).unwrap();
+    assert_eq!(pool.release(ip("10.0.0.200")), Err(PoolError::NotAllocated));
+    assert!(!pool.is_allocated(ip("10.0.0.200")));
+    assert_eq!(pool.available(), 254);
+}
+#[test]
+fn point_to_point_and_v6_pools() {
+    let mut pool = AddressPool::new("10.0.0.0/31".parse::<MaskedIpv4>().unwrap()).unwrap();
+    assert_eq!(pool.allocate(), Some(ip("10.0.0.0")));
+    assert_eq!(pool.allocate(), Some(ip("10.0.0.1")));
+    assert_eq!(pool.allocate(), None);
+
+    let mut pool = AddressPool::new("2001:db8::/120".parse::<MaskedIpv6>().unwrap()).unwrap();
+    assert_eq!(pool.available(), 255);
+    assert_eq!(pool.allocate(), Some(ip("2001:db8::1")));
+    pool.allocate_specific(ip("2001:db8::ff")).unwrap();
+    let leases: Vec<_> = pool.allocated().collect();
+    assert_eq!(leases, vec![ip("2001:db8::1"), ip("2001:db8::ff")]);
+
+    assert_eq!(
+        AddressPool::new("2001:db8::/64".parse::<MaskedIpv6>().unwrap()),
+        Err(PoolError::TooLarge)
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let net: MaskedIpv4 = "172.16.0.0/26".parse().unwrap();
+    let mut pool = AddressPool::new(net).unwrap();
+    pool.set_gateway(ip("172.16.0.1")).unwrap();
+    pool.exclude(ip("172.16.0.2"), ip("172.16.0.9")).unwrap();
+    pool.allocate().unwrap();
+    pool.allocate_specific(ip("172.16.0.62")).unwrap();
+    let json = serde_json::to_string(&pool).unwrap();
+    let restored: AddressPool = serde_json::from_str(&json).unwrap();
+    assert_eq!(restored, pool);
+    assert!(restored.is_allocated(ip("172.16.0.10")));
+    assert!(restored.is_reserved(ip("172.16.0.5")));
+
+    let bad = json.replace("172.16.0.0/26", "172.16.0.0/25");
+    assert!(serde_json::from_str::<AddressPool>(&bad).is_err());
+}
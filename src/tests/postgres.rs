@@ -0,0 +1,155 @@
+use crate::postgres::*;
+use crate::*;
+
+use alloc::vec::Vec;
+use bytes::BytesMut;
+use postgres_types::{FromSql, IsNull, ToSql, Type};
+
+/// `inet_send`/`cidr_send` and `inet_out`/`cidr_out` output from a PostgreSQL 15 server.
+const FIXTURE: &str = include_str!("fixtures/postgres-inet.txt");
+
+fn unhex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn fixture() -> impl Iterator<Item = (PgNetType, Vec<u8>, &'static str)> {
+    FIXTURE.lines().map(|line| {
+        let mut fields = line.split(' ');
+        let ty = match fields.next().unwrap() {
+            "inet" => PgNetType::Inet,
+            _ => PgNetType::Cidr,
+        };
+        (ty, unhex(fields.next().unwrap()), fields.next().unwrap())
+    })
+}
+
+#[test]
+fn fixture_round_trip() {
+    for (ty, binary, text) in fixture() {
+        let (net, decoded_ty) = from_binary(&binary).unwrap();
+        assert_eq!(decoded_ty, ty, "{text}");
+        assert_eq!(to_binary(net, ty).unwrap(), binary, "{text}");
+        assert_eq!(to_text(net, ty).unwrap(), text);
+        assert_eq!(from_text(text, ty), Ok(net), "{text}");
+    }
+}
+
+#[test]
+fn text() {
+    assert_eq!(
+        from_text("192.0.2.1", PgNetType::Inet),
+        Ok(MaskedIp::V4(cidr4!("192.0.2.1/32")))
+    );
+    assert_eq!(
+        from_text("10.0.0.1/8", PgNetType::Cidr),
+        Err(PgNetError::HostBits)
+    );
+    assert_eq!(
+        from_text("10.0.0.0/33", PgNetType::Inet),
+        Err(PgNetError::InvalidPrefixLength(33))
+    );
+    assert_eq!(
+        from_text("::/129", PgNetType::Cidr),
+        Err(PgNetError::InvalidPrefixLength(129))
+    );
+    for bad in [
+        "",
+        "10.0.0.0/",
+        "10.0.0.0/+8",
+        "10.0.0.0/0008",
+        "10/8",
+        "10.0.0.0/8 ",
+    ] {
+        assert_eq!(
+            from_text(bad, PgNetType::Inet),
+            Err(PgNetError::InvalidText),
+            "{bad:?}"
+        );
+    }
+    assert_eq!(
+        to_text(MaskedIp::V4(cidr4!("10.0.0.1/8")), PgNetType::Cidr),
+        Err(PgNetError::HostBits)
+    );
+}
+
+#[test]
+fn binary_errors() {
+    assert_eq!(from_binary(&[2, 8, 0]), Err(PgNetError::InvalidLength));
+    assert_eq!(
+        from_binary(&[2, 8, 0, 16, 10, 0, 0, 0]),
+        Err(PgNetError::InvalidLength)
+    );
+    assert_eq!(
+        from_binary(&[3, 8, 0, 4, 10, 0, 0, 0]),
+        Err(PgNetError::InvalidLength)
+    );
+    assert_eq!(
+        from_binary(&[10, 8, 0, 4, 10, 0, 0, 0]),
+        Err(PgNetError::InvalidFamily(10))
+    );
+    assert_eq!(
+        from_binary(&[2, 33, 0, 4, 10, 0, 0, 0]),
+        Err(PgNetError::InvalidPrefixLength(33))
+    );
+    assert_eq!(
+        from_binary(&[2, 8, 1, 4, 10, 0, 0, 1]),
+        Err(PgNetError::HostBits)
+    );
+    assert_eq!(
+        to_binary(MaskedIp::V4(cidr4!("10.0.0.1/8")), PgNetType::Cidr),
+        Err(PgNetError::HostBits)
+    );
+}
+
+#[test]
+fn sql_traits() {
+    let inet = unhex("02180004c0000201");
+    let mut out = BytesMut::new();
+    assert!(matches!(
+        cidr4!("192.0.2.1/24").to_sql(&Type::INET, &mut out),
+        Ok(IsNull::No)
+    ));
+    assert_eq!(&out[..], inet);
+    assert!(
+        cidr4!("192.0.2.1/24")
+            .to_sql(&Type::CIDR, &mut BytesMut::new())
+            .is_err()
+    );
+    assert!(!<MaskedIpv4 as ToSql>::accepts(&Type::TEXT));
+    assert!(
+        cidr4!("192.0.2.0/24")
+            .to_sql_checked(&Type::TEXT, &mut BytesMut::new())
+            .is_err()
+    );
+    assert!(
+        IpAddr::V4(ipv4!("192.0.2.1"))
+            .to_sql_checked(&Type::CIDR, &mut BytesMut::new())
+            .is_ok()
+    );
+    assert!(<IpAddr as FromSql>::accepts(&Type::CIDR));
+
+    assert_eq!(
+        MaskedIpv4::from_sql(&Type::INET, &inet).unwrap(),
+        cidr4!("192.0.2.1/24")
+    );
+    assert_eq!(
+        MaskedIp::from_sql(&Type::INET, &inet).unwrap(),
+        MaskedIp::V4(cidr4!("192.0.2.1/24"))
+    );
+    assert!(MaskedIpv6::from_sql(&Type::INET, &inet).is_err());
+    assert!(IpAddr::from_sql(&Type::INET, &inet).is_err());
+
+    let host = unhex("0380001020010db8000000000000000000000001");
+    assert_eq!(
+        IpAddr::from_sql(&Type::INET, &host).unwrap(),
+        IpAddr::V6(ipv6!("2001:db8::1"))
+    );
+    let mut out = BytesMut::new();
+    IpAddr::V6(ipv6!("2001:db8::1"))
+        .to_sql(&Type::INET, &mut out)
+        .unwrap();
+    assert_eq!(&out[..], host);
+}
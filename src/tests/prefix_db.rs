@@ -0,0 +1,152 @@
+use crate::*;
+
+#[test]
+fn nested_prefixes() {
+    let mut w = PrefixDbWriter::new();
+    w.insert(cidr4!("10.0.0.0/8"), 1u32);
+    w.insert(cidr4!("10.1.0.0/16"), 2);
+    w.insert(cidr4!("10.1.2.0/24"), 3);
+    w.insert(cidr4!("10.1.2.128/25"), 2);
+    w.insert(cidr4!("10.200.0.0/16"), 4);
+    w.insert(cidr4!("255.255.255.255/32"), 5);
+    let bytes = w.to_bytes();
+    let db = PrefixDb::<u32>::new(&bytes).unwrap();
+    db.validate().unwrap();
+    assert_eq!(db.value_count(), 5);
+    assert_eq!(db.lookup_v4(ipv4!("9.255.255.255")), None);
+    assert_eq!(db.lookup_v4(ipv4!("10.0.0.0")), Some(1));
+    assert_eq!(db.lookup_v4(ipv4!("10.1.0.0")), Some(2));
+    assert_eq!(db.lookup_v4(ipv4!("10.1.2.0")), Some(3));
+    assert_eq!(db.lookup_v4(ipv4!("10.1.2.127")), Some(3));
+    assert_eq!(db.lookup_v4(ipv4!("10.1.2.128")), Some(2));
+    assert_eq!(db.lookup_v4(ipv4!("10.1.3.0")), Some(2));
+    assert_eq!(db.lookup_v4(ipv4!("10.2.0.0")), Some(1));
+    assert_eq!(db.lookup_v4(ipv4!("10.200.255.255")), Some(4));
+    assert_eq!(db.lookup_v4(ipv4!("10.201.0.0")), Some(1));
+    assert_eq!(db.lookup_v4(ipv4!("11.0.0.0")), None);
+    assert_eq!(db.lookup_v4(ipv4!("255.255.255.254")), None);
+    assert_eq!(db.lookup_v4(ipv4!("255.255.255.255")), Some(5));
+    assert_eq!(db.lookup(ipv6!("::").into()), None);
+}
+
+#[test]
+fn duplicates_and_host_bits() {
+    let mut w = PrefixDbWriter::new();
+    w.insert(cidr4!("192.0.2.77/24"), [1u8, 2]);
+    w.insert(cidr4!("192.0.2.0/24"), [3, 4]);
+    w.insert(cidr4!("0.0.0.0/0"), [3, 4]);
+    let bytes = w.to_bytes();
+    let db = PrefixDb::<[u8; 2]>::new(&bytes).unwrap();
+    db.validate().unwrap();
+    // Both prefixes share one value, and the whole space is one range.
+    assert_eq!(db.value_count(), 1);
+    assert_eq!(bytes.len(), 24 + 8 + 2);
+    assert_eq!(db.lookup_v4(ipv4!("192.0.2.1")), Some([3, 4]));
+    assert_eq!(db.lookup_v4(ipv4!("1.2.3.4")), Some([3, 4]));
+}
+
+#[test]
+fn ipv6() {
+    let mut w = PrefixDbWriter::new();
+    w.insert(cidr6!("2001:db8::/32"), 10u16);
+    w.insert(cidr6!("2001:db8:1::/48"), 20);
+    w.insert(cidr6!("ffff::/16"), 30);
+    w.insert(cidr4!("192.0.2.0/24"), 40);
+    let bytes = w.to_bytes();
+    let db = PrefixDb::<u16>::new(&bytes).unwrap();
+    db.validate().unwrap();
+    assert_eq!(db.lookup(ipv6!("2001:db8::1").into()), Some(10));
+    assert_eq!(db.lookup(ipv6!("2001:db8:1:ffff::").into()), Some(20));
+    assert_eq!(db.lookup(ipv6!("2001:db8:2::").into()), Some(10));
+    assert_eq!(db.lookup(ipv6!("2001:db9::").into()), None);
+    assert_eq!(
+        db.lookup(ipv6!("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff").into()),
+        Some(30)
+    );
+    assert_eq!(db.lookup(ipv4!("192.0.2.9").into()), Some(40));
+}
+
+#[test]
+fn empty() {
+    let bytes = PrefixDbWriter::<u64>::new().to_bytes();
+    assert_eq!(bytes.len(), 24);
+    let db = PrefixDb::<u64>::new(&bytes).unwrap();
+    db.validate().unwrap();
+    assert_eq!(db.value_count(), 0);
+    assert_eq!(db.lookup_v4(ipv4!("1.2.3.4")), None);
+    assert_eq!(db.lookup_v6(ipv6!("::1")), None);
+}
+
+#[test]
+fn unaligned() {
+    let mut w = PrefixDbWriter::new();
+    w.insert(cidr4!("10.0.0.0/8"), 7u64);
+    let mut buf = alloc::vec![0u8];
+    buf.extend_from_slice(&w.to_bytes());
+    let db = PrefixDb::<u64>::new(&buf[1..]).unwrap();
+    assert_eq!(db.lookup_v4(ipv4!("10.0.0.1")), Some(7));
+}
+
+#[test]
+fn errors() {
+    let mut w = PrefixDbWriter::new();
+    w.insert(cidr4!("10.0.0.0/8"), 1u32);
+    let bytes = w.to_bytes();
+
+    assert_eq!(
+        PrefixDb::<u32>::new(b"IPDB").err(),
+        Some(PrefixDbError::BadMagic)
+    );
+    let mut bad = bytes.clone();
+    bad[0] = b'X';
+    assert_eq!(
+        PrefixDb::<u32>::new(&bad).err(),
+        Some(PrefixDbError::BadMagic)
+    );
+    let mut bad = bytes.clone();
+    bad[4] = 2;
+    assert_eq!(
+        PrefixDb::<u32>::new(&bad).err(),
+        Some(PrefixDbError::UnsupportedVersion(2))
+    );
+    assert_eq!(
+        PrefixDb::<u16>::new(&bytes).err(),
+        Some(PrefixDbError::ValueSize {
+            expected: 2,
+            found: 4
+        })
+    );
+    assert_eq!(
+        PrefixDb::<u32>::new(&bytes[..bytes.len() - 1]).err(),
+        Some(PrefixDbError::Length)
+    );
+    let mut bad = bytes.clone();
+    bad.push(0);
+    assert_eq!(
+        PrefixDb::<u32>::new(&bad).err(),
+        Some(PrefixDbError::Length)
+    );
+    // Counts whose byte lengths overflow a 32-bit `usize`.
+    let mut bad = bytes.clone();
+    bad[12..20].fill(0xff);
+    assert_eq!(
+        PrefixDb::<u32>::new(&bad).err(),
+        Some(PrefixDbError::Length)
+    );
+
+    // Ranges are 0.0.0.0, 10.0.0.0 and 11.0.0.0, followed by their value indexes.
+    let mut bad = bytes.clone();
+    bad[24 + 4] = 12;
+    let db = PrefixDb::<u32>::new(&bad).unwrap();
+    assert_eq!(db.validate(), Err(PrefixDbError::Unsorted));
+    let mut bad = bytes.clone();
+    bad[24 + 12 + 4] = 9;
+    let db = PrefixDb::<u32>::new(&bad).unwrap();
+    assert_eq!(db.validate(), Err(PrefixDbError::ValueIndex));
+    assert_eq!(db.lookup_v4(ipv4!("10.0.0.0")), None);
+    // The largest index which is not `NO_VALUE`, whose byte offset overflows a 32-bit `usize`.
+    let mut bad = bytes.clone();
+    bad[24 + 12 + 4..24 + 12 + 8].copy_from_slice(&(u32::MAX - 1).to_le_bytes());
+    let db = PrefixDb::<u32>::new(&bad).unwrap();
+    assert_eq!(db.lookup_v4(ipv4!("10.0.0.0")), None);
+}
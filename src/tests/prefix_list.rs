@@ -0,0 +1,91 @@
+use crate::*;
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+const OLD: &str = "\
+# allowlist
+10.0.0.0/24
+10.0.1.0/24   # office
+
+192.0.2.1
+2001:db8::/32
+";
+const NEW: &str = "\
+10.0.0.0 255.255.254.0
+198.51.100.0/24
+2001:db8::/33
+";
+
+fn lines(prefixes: Vec<AnnotatedPrefix>) -> Vec<String> {
+    prefixes.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn parse_lists() {
+    let list = PrefixList::parse("old", OLD).unwrap();
+    assert_eq!(list.name(), "old");
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.entries()[1].line, 3);
+    assert_eq!(list.entries()[2].prefix, "192.0.2.1/32".parse().unwrap());
+    assert!(
+        PrefixList::parse("empty", "\n# nothing\n")
+            .unwrap()
+            .is_empty()
+    );
+    let err = PrefixList::parse("bad", "10.0.0.0/8\n10.0.0.0/33\n").unwrap_err();
+    assert_eq!(err.line, 2);
+    assert_eq!(err.to_string(), "bad:2: invalid prefix");
+}
+#[test]
+fn set_operations() {
+    let old = PrefixList::parse("old", OLD).unwrap();
+    let new = PrefixList::parse("new", NEW).unwrap();
+    assert_eq!(
+        lines(union([&old, &new])),
+        [
+            "10.0.0.0/23 # old:2, old:3, new:1",
+            "192.0.2.1/32 # old:5",
+            "198.51.100.0/24 # new:2",
+            "2001:db8::/32 # old:6, new:3",
+        ]
+    );
+    assert_eq!(
+        lines(difference(&old, &new)),
+        ["192.0.2.1/32 # old:5", "2001:db8:8000::/33 # old:6"]
+    );
+    assert_eq!(lines(difference(&new, &old)), ["198.51.100.0/24 # new:2"]);
+    assert_eq!(
+        lines(intersection(&old, &new)),
+        [
+            "10.0.0.0/23 # old:2, old:3, new:1",
+            "2001:db8::/33 # old:6, new:3"
+        ]
+    );
+    assert!(difference(&old, &old).is_empty());
+    assert_eq!(old.aggregate().len(), 3);
+}
+#[test]
+fn difference_splits_blocks() {
+    let mut a = PrefixList::new("a");
+    a.push("0.0.0.0/0".parse::<MaskedIpv4>().unwrap(), 1);
+    let mut b = PrefixList::new("b");
+    b.push("10.0.0.0/8".parse::<MaskedIpv4>().unwrap(), 1);
+    b.push("128.0.0.0/1".parse::<MaskedIpv4>().unwrap(), 2);
+    let prefixes: Vec<_> = difference(&a, &b)
+        .into_iter()
+        .map(|p| p.prefix.to_string())
+        .collect();
+    assert_eq!(
+        prefixes,
+        [
+            "0.0.0.0 248.0.0.0",
+            "8.0.0.0 254.0.0.0",
+            "11.0.0.0 255.0.0.0",
+            "12.0.0.0 252.0.0.0",
+            "16.0.0.0 240.0.0.0",
+            "32.0.0.0 224.0.0.0",
+            "64.0.0.0 192.0.0.0",
+        ]
+    );
+}
@@ -0,0 +1,148 @@
+use crate::*;
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+#[test]
+#[cfg(target_endian = "little")]
+fn ipv4_routes() {
+    let text = include_str!("fixtures/proc-net-route.txt");
+    let routes: Vec<_> = Ipv4Routes::new(text).collect::<Result<_, _>>().unwrap();
+    assert_eq!(routes.len(), 4);
+    assert_eq!(
+        routes[0],
+        Ipv4Route {
+            iface: "eth0",
+            destination: cidr4!("0.0.0.0/0"),
+            gateway: ipv4!("192.168.2.1"),
+            flags: RouteFlags(0x3),
+            metric: 100,
+            mtu: 0,
+            window: 0,
+            irtt: 0,
+        }
+    );
+    assert!(routes[0].flags.contains(RouteFlags::GATEWAY));
+    assert_eq!(routes[1].destination, cidr4!("192.168.2.0/24"));
+    assert!(!routes[1].flags.contains(RouteFlags::GATEWAY));
+    assert_eq!(routes[2].iface, "docker0");
+    assert_eq!(routes[2].destination, cidr4!("172.17.0.0/16"));
+    assert_eq!(routes[2].mtu, 1400);
+    assert_eq!(routes[3].destination, cidr4!("10.0.0.10/32"));
+    assert!(routes[3].flags.contains(RouteFlags::HOST));
+}
+
+#[test]
+#[cfg(target_endian = "little")]
+fn ipv4_route_errors() {
+    let line = "eth0\t0002A8C0\t00000000\t0001\t0\t0\t100\t00FF00FF\t0\t0\t0";
+    assert_eq!(Ipv4Route::parse(line), Err(ProcNetErrorKind::InvalidMask));
+    let line = "eth0\t0002A8C\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0";
+    assert_eq!(
+        Ipv4Route::parse(line),
+        Err(ProcNetErrorKind::InvalidAddress)
+    );
+    let line = "eth0\t0002A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF";
+    assert_eq!(Ipv4Route::parse(line), Err(ProcNetErrorKind::MissingField));
+    let text = "Iface\tDestination\n\neth0\t+002A8C0\t00000000\t0001\t0\t0\t100\t00FFFFFF\t0\t0\t0";
+    assert_eq!(
+        Ipv4Routes::new(text).next(),
+        Some(Err(ProcNetError {
+            kind: ProcNetErrorKind::InvalidAddress,
+            line: 3
+        }))
+    );
+}
+
+#[test]
+fn ipv6_routes() {
+    let text = include_str!("fixtures/proc-net-ipv6_route.txt");
+    let routes: Vec<_> = Ipv6Routes::new(text).collect::<Result<_, _>>().unwrap();
+    assert_eq!(routes.len(), 6);
+    assert_eq!(routes[0].destination, cidr6!("2001:db8:1::/64"));
+    assert_eq!(routes[0].source, cidr6!("::/0"));
+    assert_eq!(routes[0].metric, 256);
+    assert_eq!(routes[0].iface, "eth0");
+    let default = routes[2];
+    assert_eq!(default.destination, cidr6!("::/0"));
+    assert_eq!(default.next_hop, ipv6!("fe80::1"));
+    assert_eq!(default.metric, 1024);
+    assert!(default.flags.contains(RouteFlags::GATEWAY));
+    assert!(default.flags.contains(RouteFlags::DEFAULT));
+    assert!(default.flags.contains(RouteFlags::EXPIRES));
+    assert_eq!(routes[3].destination, cidr6!("::1/128"));
+    assert_eq!(routes[3].iface, "lo");
+    assert!(routes[5].flags.contains(RouteFlags::REJECT));
+    assert_eq!(routes[5].metric, u32::MAX);
+
+    let line = "20010db8000100000000000000000000 81 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001 eth0";
+    assert_eq!(Ipv6Route::parse(line), Err(ProcNetErrorKind::InvalidMask));
+}
+
+#[test]
+fn ipv6_addresses() {
+    let text = include_str!("fixtures/proc-net-if_inet6.txt");
+    let addrs: Vec<_> = Ipv6IfAddrs::new(text).collect::<Result<_, _>>().unwrap();
+    assert_eq!(addrs.len(), 6);
+    assert_eq!(
+        addrs[0],
+        Ipv6IfAddr {
+            iface: "lo",
+            if_index: 1,
+            address: cidr6!("::1/128"),
+            scope: Ipv6AddrScope::LOOPBACK,
+            flags: Ipv6AddrFlags::PERMANENT,
+        }
+    );
+    // The address keeps its host bits.
+    assert_eq!(addrs[1].address, cidr6!("2001:db8:1::10/64"));
+    assert_eq!(addrs[1].scope, Ipv6AddrScope::GLOBAL);
+    assert_eq!(addrs[2].flags, Ipv6AddrFlags(0));
+    assert!(addrs[3].flags.contains(Ipv6AddrFlags::TEMPORARY));
+    assert_eq!(addrs[4].scope, Ipv6AddrScope::LINK);
+    assert_eq!(addrs[5].if_index, 26);
+    assert!(addrs[5].flags.contains(Ipv6AddrFlags::TENTATIVE));
+}
+
+#[test]
+fn fib_trie() {
+    let text = include_str!("fixtures/proc-net-fib_trie.txt");
+    let routes: Vec<_> = FibRoutes::new(text).collect::<Result<_, _>>().unwrap();
+    assert_eq!(routes.len(), 10);
+    assert_eq!(
+        routes[0],
+        FibRoute {
+            table: "Main",
+            destination: cidr4!("0.0.0.0/0"),
+            scope: RouteScope::UNIVERSE,
+            kind: RouteType::UNICAST,
+            tos: 0,
+        }
+    );
+    assert_eq!(routes[1].destination, cidr4!("127.0.0.0/8"));
+    assert_eq!(routes[1].scope, RouteScope::HOST);
+    assert_eq!(routes[1].kind, RouteType::LOCAL);
+    assert_eq!(routes[3].kind, RouteType::BROADCAST);
+    assert_eq!(routes[3].scope, RouteScope::LINK);
+    assert_eq!(routes[5].destination, cidr4!("192.168.2.0/24"));
+    assert_eq!(routes[5].tos, 16);
+    assert_eq!(routes[6].destination, cidr4!("192.168.2.5/32"));
+    assert_eq!(routes[8].table, "Id 100");
+    assert_eq!(routes[8].kind, RouteType::BLACKHOLE);
+    assert_eq!(routes[9].scope, RouteScope::SITE);
+    assert_eq!(routes[9].kind, RouteType(12));
+    assert_eq!(routes[9].kind.to_string(), "type 12");
+    assert_eq!(routes[9].scope.to_string(), "site");
+
+    let errors = [
+        ("  |-- 10.0.0.0\n     /8 universe UNICAST", 2),
+        ("Main:\n     /8 universe UNICAST", 2),
+        ("Main:\n  |-- 10.0.0.0\n     /33 universe UNICAST", 3),
+        ("Main:\n  |-- 10.0.0.256", 2),
+        ("Main:\n  |-- 10.0.0.0\n     /8 universe FOO", 3),
+    ];
+    for (text, line) in errors {
+        let error = FibRoutes::new(text).find_map(Result::err);
+        assert_eq!(error.map(|e| e.line), Some(line), "{text}");
+    }
+}
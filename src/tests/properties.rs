@@ -0,0 +1,152 @@
+use crate::strategy::*;
+use crate::*;
+
+use alloc::format;
+use alloc::vec::Vec;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn addr_display_round_trip(ip in ip_addr()) {
+        prop_assert_eq!(format!("{}", ip).parse::<IpAddr>().unwrap(), ip);
+    }
+    #[test]
+    fn mask_bits_round_trip(v4 in ipv4_mask(), v6 in ipv6_mask()) {
+        prop_assert_eq!(Ipv4Mask::from_u32(v4.to_bits()), Some(v4));
+        prop_assert_eq!(Ipv4Mask::new(v4.len()), v4);
+        prop_assert_eq!(Ipv6Mask::from_u128(v6.to_bits()), Some(v6));
+        prop_assert_eq!(Ipv6Mask::new(v6.len()), v6);
+        prop_assert_eq!(format!("{}", v4).parse::<Ipv4Mask>().unwrap(), v4);
+        prop_assert_eq!(format!("{}", v6).parse::<Ipv6Mask>().unwrap(), v6);
+    }
+    #[test]
+    fn masked_ipv4_display_round_trip(masked in masked_ipv4()) {
+        prop_assert_eq!(format!("{}", masked).parse::<MaskedIpv4>().unwrap(), masked);
+        prop_assert_eq!(format!("{:#}", masked).parse::<MaskedIpv4>().unwrap(), masked);
+        prop_assert_eq!(MaskedIpv4::from_cidr_str(&format!("{:#}", masked)), Some(masked));
+        prop_assert_eq!(MaskedIpv4::from_network_str(&format!("{}", masked)), Some(masked));
+    }
+    #[test]
+    fn masked_ip_display_round_trip(masked in masked_ip()) {
+        prop_assert_eq!(format!("{:#}", masked).parse::<MaskedIp>().unwrap(), masked);
+        prop_assert_eq!(MaskedIp::from_cidr_str(&format!("{:#}", masked)), Some(masked));
+    }
+    #[test]
+    fn const_parsers_round_trip(ip in ip_addr(), v4 in masked_ipv4(), masked in masked_ip()) {
+        prop_assert_eq!(IpAddr::from_str_const(&format!("{}", ip)), Some(ip));
+        prop_assert_eq!(MaskedIpv4::from_str_const(&format!("{}", v4)), Some(v4));
+        prop_assert_eq!(MaskedIp::from_str_const(&format!("{:#}", masked)), Some(masked));
+    }
+    #[test]
+    fn ordering_and_relation(a in masked_ip(), b in masked_ip()) {
+        prop_assert_eq!(a.cmp(&b) == core::cmp::Ordering::Equal, a == b);
+        prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+        prop_assert_eq!(a.overlaps(&b), b.overlaps(&a));
+        prop_assert_eq!(a.is_subnet_of(&b), b.is_supernet_of(&a));
+        if a.is_subnet_of(&b) {
+            prop_assert!(b.network() <= a.network());
+        }
+    }
+    #[test]
+    fn masked_ipv4_network(masked in masked_ipv4()) {
+        let network = masked.network();
+        prop_assert_eq!(network.network(), network);
+        prop_assert!(masked.contains(masked.ip));
+        prop_assert!(masked.contains(masked.network_address()));
+        prop_assert!(masked.contains(masked.broadcast_address()));
+        prop_assert_eq!(masked.network_bits() + masked.host_bits(), 32);
+        if masked.network_bits() >= 31 {
+            prop_assert!(!masked.is_network_address() && !masked.is_broadcast_address());
+        }
+    }
+    #[test]
+    fn masked_ipv6_network(masked in masked_ipv6(), ip in ipv6_addr()) {
+        let network = masked.network();
+        prop_assert_eq!(network.network(), network);
+        prop_assert!(masked.contains(masked.ip));
+        prop_assert_eq!(masked.contains(ip), ip & masked.mask == masked.network_address());
+        prop_assert_eq!(masked.network_bits() + masked.host_bits(), 128);
+    }
+    #[test]
+    fn serde_round_trip(masked in masked_ip(), ip in ip_addr(), mask in ipv4_mask()) {
+        let json = serde_json::to_string(&masked).unwrap();
+        prop_assert_eq!(serde_json::from_str::<MaskedIp>(&json).unwrap(), masked);
+        let json = serde_json::to_string(&ip).unwrap();
+        prop_assert_eq!(serde_json::from_str::<IpAddr>(&json).unwrap(), ip);
+        let json = serde_json::to_string(&mask).unwrap();
+        prop_assert_eq!(serde_json::from_str::<Ipv4Mask>(&json).unwrap(), mask);
+    }
+    #[test]
+    fn truncate_is_network_address(ip in ipv6_addr(), mask in ipv6_mask()) {
+        let truncate = Truncate { v4: Ipv4Mask::new(0), v6: mask };
+        let anonymized = truncate.anonymize_ipv6(ip);
+        prop_assert_eq!(anonymized, MaskedIpv6::new(ip, mask).network_address());
+    }
+    #[test]
+    fn crypto_pan_preserves_prefixes(a in ipv4_addr(), b in ipv4_addr(), key in any::<u128>()) {
+        let pan = CryptoPan::new(move |block: [u8; 16]| {
+            let x = u128::from_be_bytes(block) ^ key;
+            x.wrapping_mul(0x2545_F491_4F6C_DD1D_9E37_79B9_7F4A_7C15).to_be_bytes()
+        }, [0; 16]);
+        let common = (a.to_bits() ^ b.to_bits()).leading_zeros();
+        let (x, y) = (pan.anonymize_ipv4(a), pan.anonymize_ipv4(b));
+        prop_assert_eq!((x.to_bits() ^ y.to_bits()).leading_zeros(), common);
+    }
+    #[test]
+    fn allocations_never_overlap(pool in ipv4_network(), lens in proptest::collection::vec(0..=32u8, 1..20)) {
+        let mut allocator = Ipv4SubnetAllocator::new(pool);
+        let mut allocated: Vec<MaskedIpv4> = Vec::new();
+        for len in lens {
+            if let Ok(subnet) = allocator.allocate(len) {
+                prop_assert!(pool.contains(subnet.ip));
+                prop_assert_eq!(subnet, subnet.network());
+                for other in &allocated {
+                    prop_assert!(!other.contains(subnet.ip) && !subnet.contains(other.ip));
+                }
+                allocated.push(subnet);
+            }
+        }
+        for subnet in allocated {
+            allocator.release(subnet).unwrap();
+        }
+        prop_assert_eq!(allocator.free_space(), [pool.network()]);
+    }
+    #[test]
+    fn shuffled_subnets_are_distinct(net in ipv6_network(), extra in 0..=10u8, key in any::<u64>()) {
+        let len = (net.network_bits() + extra).min(128);
+        let subnets: Vec<_> = net.shuffled_subnets(len, key).collect();
+        prop_assert_eq!(subnets.len() as u128, net.network_count(len));
+        let mut ips: Vec<_> = subnets.iter().map(|m| m.ip).collect();
+        ips.sort();
+        ips.dedup();
+        prop_assert_eq!(ips.len(), subnets.len());
+    }
+    #[test]
+    fn matcher_agrees_with_linear_scan(
+        nets in proptest::collection::vec(masked_ipv6(), 1..20),
+        extra in proptest::collection::vec(ipv6_addr(), 0..20),
+    ) {
+        let matcher = Ipv6Matcher::new(nets.iter().copied());
+        for addr in nets.iter().map(|net| net.ip).chain(extra) {
+            let mut expected: Option<usize> = None;
+            for (i, net) in nets.iter().enumerate() {
+                if net.contains(addr)
+                    && expected.is_none_or(|e| net.network_bits() > nets[e].network_bits())
+                {
+                    expected = Some(i);
+                }
+            }
+            prop_assert_eq!(matcher.matches(addr), expected);
+        }
+    }
+    #[test]
+    fn arbitrary_is_valid(data in proptest::collection::vec(any::<u8>(), 0..64)) {
+        let mut u = arbitrary::Unstructured::new(&data);
+        if let Ok(masked) = u.arbitrary::<MaskedIp>() {
+            prop_assert_eq!(format!("{:#}", masked).parse::<MaskedIp>().unwrap(), masked);
+        }
+        if let Ok(mask) = u.arbitrary::<Ipv6Mask>() {
+            prop_assert!(mask.len() <= 128);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use crate::*;
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+
+#[test]
+fn shuffled_visits_every_address_once() {
+    for len in [32, 31, 30, 27, 24, 19] {
+        let net = MaskedIpv4::cidr(Ipv4Addr::new(10, 20, 30, 40), len);
+        let seen: BTreeSet<_> = net.shuffled(0xDEAD_BEEF).collect();
+        assert_eq!(seen.len() as u64, 1 << (32 - len));
+        assert!(seen.iter().all(|&ip| net.contains(ip)));
+    }
+    let net: MaskedIpv6 = "2001:db8::/113".parse().unwrap();
+    let seen: BTreeSet<_> = net.shuffled(7).collect();
+    assert_eq!(seen.len(), 1 << 15);
+    assert!(seen.iter().all(|&ip| net.contains(ip)));
+}
+#[test]
+fn shuffled_is_keyed_and_scrambled() {
+    let net: MaskedIpv4 = "192.168.0.0/24".parse().unwrap();
+    let a: Vec<_> = net.shuffled(1).collect();
+    let b: Vec<_> = net.shuffled(2).collect();
+    assert_ne!(a, b);
+    assert_eq!(a, net.shuffled(1).collect::<Vec<_>>());
+    let in_order = a.windows(2).filter(|w| w[0] < w[1]).count();
+    assert!(in_order > 64 && in_order < 192);
+}
+#[test]
+fn shuffled_large_networks() {
+    let net: MaskedIpv6 = "::/0".parse().unwrap();
+    let first: BTreeSet<_> = net.shuffled(3).take(1000).collect();
+    assert_eq!(first.len(), 1000);
+    let net: MaskedIpv6 = "::1/128".parse().unwrap();
+    assert_eq!(net.shuffled(3).collect::<Vec<_>>(), [Ipv6Addr::LOCALHOST]);
+}
+#[test]
+fn shuffled_subnets_do_not_overlap() {
+    let net: MaskedIpv4 = "10.0.0.0/16".parse().unwrap();
+    let subnets: BTreeSet<_> = net.shuffled_subnets(22, 99).map(|m| m.ip).collect();
+    assert_eq!(subnets.len(), 64);
+    assert!(
+        subnets
+            .iter()
+            .all(|ip| ip.octets()[2] % 4 == 0 && ip.octets()[3] == 0)
+    );
+    let net: MaskedIpv6 = "2001:db8::/32".parse().unwrap();
+    let subnets: BTreeSet<_> = net.shuffled_subnets(40, 5).map(|m| m.ip).collect();
+    assert_eq!(subnets.len(), 256);
+    assert_eq!(net.shuffled_subnets(32, 5).count(), 1);
+}
+#[test]
+fn random_host_is_usable() {
+    let mut rng = SmallRng::seed_from_u64(42);
+    let net: MaskedIpv4 = "172.16.5.0/30".parse().unwrap();
+    let hosts: BTreeSet<_> = (0..100).map(|_| net.random_host(&mut rng)).collect();
+    assert_eq!(hosts.len(), 2);
+    assert!(
+        hosts
+            .iter()
+            .all(|ip| !MaskedIpv4::new(*ip, net.mask).is_network_address())
+    );
+    assert!(
+        hosts
+            .iter()
+            .all(|ip| !MaskedIpv4::new(*ip, net.mask).is_broadcast_address())
+    );
+    let net: MaskedIpv4 = "172.16.5.0/31".parse().unwrap();
+    let hosts: BTreeSet<_> = (0..100).map(|_| net.random_host(&mut rng)).collect();
+    assert_eq!(hosts.len(), 2);
+    let net: MaskedIpv6 = "fe80::/126".parse().unwrap();
+    let hosts: BTreeSet<_> = (0..100).map(|_| net.random_host(&mut rng)).collect();
+    assert_eq!(hosts.len(), 3);
+    let net: MaskedIp = "::/0".parse().unwrap();
+    assert!(net.random_host(&mut rng) != Ipv6Addr::UNSPECIFIED.into());
+}
+#[test]
+fn random_subnet() {
+    let mut rng = SmallRng::seed_from_u64(42);
+    let net: MaskedIpv4 = "10.0.0.0/8".parse().unwrap();
+    for _ in 0..100 {
+        let subnet = net.random_subnet(24, &mut rng).unwrap();
+        assert!(net.contains(subnet.ip));
+        assert_eq!(subnet, subnet.network());
+        assert_eq!(subnet.network_bits(), 24);
+    }
+    assert_eq!(net.random_subnet(8, &mut rng), Some(net));
+    assert_eq!(net.random_subnet(7, &mut rng), None);
+    assert_eq!(net.random_subnet(33, &mut rng), None);
+    let net: MaskedIp = "2001:db8::/32".parse().unwrap();
+    let subnet = net.random_subnet(128, &mut rng).unwrap();
+    assert!(net.contains(subnet.network_address()));
+    let net: MaskedIpv6 = "2001:db8::/32".parse().unwrap();
+    let all: BTreeSet<_> = net.random_subnets(36, &mut rng).map(|m| m.ip).collect();
+    assert_eq!(all.len(), 16);
+}
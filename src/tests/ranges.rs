@@ -0,0 +1,228 @@
+use crate::*;
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+fn v4(s: &str) -> MaskedIpv4 {
+    s.parse().unwrap()
+}
+fn v6(s: &str) -> MaskedIpv6 {
+    s.parse().unwrap()
+}
+
+#[test]
+fn subnets_in_order() {
+    let subnets: Vec<_> = v4("10.0.0.77/22").subnets(24).collect();
+    assert_eq!(
+        subnets,
+        ["10.0.0.0/24", "10.0.1.0/24", "10.0.2.0/24", "10.0.3.0/24"].map(v4)
+    );
+    assert_eq!(
+        v4("10.0.0.0/24").subnets(24).collect::<Vec<_>>(),
+        [v4("10.0.0.0/24")]
+    );
+    assert_eq!(v4("0.0.0.0/0").subnets(1).count(), 2);
+    assert_eq!(
+        v4("255.255.255.0/24").subnets(32).last(),
+        Some(v4("255.255.255.255/32"))
+    );
+    assert_eq!(v4("10.0.0.0/24").subnets(23).count(), 0);
+    assert_eq!(v4("10.0.0.0/24").subnets(33).count(), 0);
+
+    let subnets: Vec<_> = v6("2001:db8::/47").subnets(48).collect();
+    assert_eq!(subnets, ["2001:db8::/48", "2001:db8:1::/48"].map(v6));
+    assert_eq!(v6("::/0").subnets(1).last(), Some(v6("8000::/1")));
+    assert_eq!(v6("ffff::/16").subnets(128).nth(5), Some(v6("ffff::5/128")));
+    assert_eq!(v6("::/64").subnets(129).count(), 0);
+}
+#[test]
+fn aggregate_merges() {
+    let nets = [
+        "10.0.1.0/24",
+        "10.0.0.0/24",
+        "10.0.2.0/23",
+        "10.0.3.7/32",
+        "192.168.0.1/16",
+    ];
+    assert_eq!(
+        aggregate_ipv4(nets.map(v4)),
+        [v4("10.0.0.0/22"), v4("192.168.0.0/16")]
+    );
+    // Adjacent but misaligned ranges are split into aligned blocks.
+    assert_eq!(
+        aggregate_ipv4(["10.0.1.0/24", "10.0.2.0/24"].map(v4)),
+        ["10.0.1.0/24", "10.0.2.0/24"].map(v4)
+    );
+    assert_eq!(
+        aggregate_ipv4(["0.0.0.0/1", "128.0.0.0/1", "1.2.3.4/32"].map(v4)),
+        [v4("0.0.0.0/0")]
+    );
+    assert!(aggregate_ipv4([]).is_empty());
+
+    assert_eq!(aggregate_ipv6(["::/1", "8000::/1"].map(v6)), [v6("::/0")]);
+    assert_eq!(
+        aggregate_ipv6(["2001:db8::/33", "2001:db8:8000::/33", "2001:db8::1/128"].map(v6)),
+        [v6("2001:db8::/32")]
+    );
+
+    let mixed = [
+        MaskedIp::V6(v6("::/128")),
+        MaskedIp::V4(v4("10.0.0.0/9")),
+        MaskedIp::V4(v4("10.128.0.0/9")),
+    ];
+    let expected = [MaskedIp::V4(v4("10.0.0.0/8")), MaskedIp::V6(v6("::/128"))];
+    assert_eq!(aggregate(mixed), expected);
+}
+#[test]
+fn wildcard_masks() {
+    let mask = Ipv4Mask::from_wildcard([0, 0, 0, 255]).unwrap();
+    assert_eq!(mask.len(), 24);
+    assert_eq!(mask.wildcard(), [0, 0, 0, 255]);
+    assert_eq!(Ipv4Mask::from_wildcard([0, 0, 0, 0]).unwrap().len(), 32);
+    assert_eq!(Ipv4Mask::from_wildcard([255; 4]).unwrap().len(), 0);
+    assert_eq!(Ipv4Mask::from_wildcard([0, 0, 255, 0]), None);
+    for len in 0..=32 {
+        let mask = Ipv4Mask::new(len);
+        assert_eq!(Ipv4Mask::from_wildcard(mask.wildcard()), Some(mask));
+    }
+}
+#[test]
+fn reverse_zones() {
+    assert_eq!(
+        v4("192.168.1.77/24").reverse_zone().to_string(),
+        "1.168.192.in-addr.arpa"
+    );
+    assert_eq!(
+        v4("10.1.2.3/20").reverse_zone().to_string(),
+        "1.10.in-addr.arpa"
+    );
+    assert_eq!(
+        v4("10.1.2.3/32").reverse_zone().to_string(),
+        "3.2.1.10.in-addr.arpa"
+    );
+    assert_eq!(v4("10.1.2.3/0").reverse_zone().to_string(), "in-addr.arpa");
+    assert_eq!(
+        v6("2001:db8::1/32").reverse_zone().to_string(),
+        "8.b.d.0.1.0.0.2.ip6.arpa"
+    );
+    assert_eq!(
+        MaskedIp::V6(v6("2001:db8:abcd::/46"))
+            .reverse_zone()
+            .to_string(),
+        "c.b.a.8.b.d.0.1.0.0.2.ip6.arpa"
+    );
+}
+#[test]
+fn host_and_network_counts() {
+    assert_eq!(v4("10.0.0.0/24").host_count(), 254);
+    assert_eq!(v4("10.0.0.0/24").host_count_u64(), 254);
+    assert_eq!(v4("10.0.0.0/30").host_count(), 2);
+    assert_eq!(v4("10.0.0.0/31").host_count(), 2);
+    assert_eq!(v4("10.0.0.0/32").host_count(), 1);
+    assert_eq!(v4("0.0.0.0/0").host_count_u64(), (1 << 32) - 2);
+    assert_eq!(v4("10.0.0.0/8").network_count(16), 256);
+    assert_eq!(v4("10.0.0.0/8").network_count_u64(8), 1);
+    assert_eq!(v4("0.0.0.0/0").network_count_u64(32), 1 << 32);
+    assert_eq!(v6("2001:db8::/64").host_count(), 1 << 64);
+    assert_eq!(v6("::1/128").host_count(), 1);
+    assert_eq!(v6("2001:db8::/32").network_count(48), 1 << 16);
+}
+#[test]
+fn exclude_one() {
+    let remaining: Vec<_> = cidr4!("10.0.0.0/16")
+        .exclude(&cidr4!("10.0.5.0/24"))
+        .collect();
+    assert_eq!(
+        remaining,
+        [
+            "10.0.0.0/22",
+            "10.0.4.0/24",
+            "10.0.6.0/23",
+            "10.0.8.0/21",
+            "10.0.16.0/20",
+            "10.0.32.0/19",
+            "10.0.64.0/18",
+            "10.0.128.0/17",
+        ]
+        .map(v4)
+    );
+    // Disjoint networks leave the whole network, with host bits cleared.
+    let net = cidr4!("192.168.1.7/24");
+    assert_eq!(
+        net.exclude(&cidr4!("10.0.0.0/8")).collect::<Vec<_>>(),
+        [cidr4!("192.168.1.0/24")]
+    );
+    assert_eq!(net.exclude(&cidr4!("192.168.0.0/16")).count(), 0);
+    assert_eq!(net.exclude(&net).count(), 0);
+    assert_eq!(
+        cidr4!("0.0.0.0/0")
+            .exclude(&cidr4!("255.255.255.255/32"))
+            .last(),
+        Some(cidr4!("255.255.255.254/32"))
+    );
+    assert_eq!(
+        cidr4!("0.0.0.0/0").exclude(&cidr4!("0.0.0.0/32")).count(),
+        32
+    );
+
+    let remaining: Vec<_> = cidr6!("2001:db8::/32")
+        .exclude(&cidr6!("2001:db8::/34"))
+        .collect();
+    assert_eq!(
+        remaining,
+        [cidr6!("2001:db8:4000::/34"), cidr6!("2001:db8:8000::/33")]
+    );
+    assert_eq!(cidr6!("::/0").exclude(&cidr6!("::1/128")).count(), 128);
+
+    let remaining: Vec<_> = cidr!("10.0.0.0/30")
+        .exclude(&cidr!("10.0.0.1/32"))
+        .collect();
+    assert_eq!(remaining, [cidr!("10.0.0.0/32"), cidr!("10.0.0.2/31")]);
+    assert_eq!(
+        cidr!("10.0.0.0/8")
+            .exclude(&cidr!("::/0"))
+            .collect::<Vec<_>>(),
+        [cidr!("10.0.0.0/8")]
+    );
+}
+#[test]
+fn exclude_many() {
+    let mut excluded = [
+        cidr4!("10.0.128.0/17"),
+        cidr4!("10.0.1.0/24"),
+        cidr4!("9.0.0.0/8"),
+        cidr4!("10.0.0.0/23"),
+        cidr4!("10.0.64.0/18"),
+        cidr4!("11.0.0.0/8"),
+    ];
+    excluded.sort();
+    let remaining: Vec<_> = cidr4!("10.0.0.0/16").exclude_all(&excluded).collect();
+    assert_eq!(
+        remaining,
+        [
+            "10.0.2.0/23",
+            "10.0.4.0/22",
+            "10.0.8.0/21",
+            "10.0.16.0/20",
+            "10.0.32.0/19",
+        ]
+        .map(v4)
+    );
+    assert_eq!(
+        cidr4!("10.0.0.0/24").exclude_all(&[]).collect::<Vec<_>>(),
+        [cidr4!("10.0.0.0/24")]
+    );
+    assert_eq!(
+        cidr6!("::/126")
+            .exclude_all(&[cidr6!("::/128"), cidr6!("::3/128")])
+            .collect::<Vec<_>>(),
+        [cidr6!("::1/128"), cidr6!("::2/128")]
+    );
+    let mut mixed = [cidr!("10.0.0.0/25"), cidr!("::/0"), cidr!("10.0.0.192/26")];
+    mixed.sort();
+    assert_eq!(
+        cidr!("10.0.0.0/24").exclude_all(&mixed).collect::<Vec<_>>(),
+        [cidr!("10.0.0.128/26")]
+    );
+    assert_eq!(cidr!("2001:db8::/32").exclude_all(&mixed).count(), 0);
+}
@@ -0,0 +1,146 @@
+use crate::*;
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+#[test]
+fn ipv4_relations() {
+    use PrefixRelation::*;
+    let net = cidr4!("10.1.0.0/16");
+    assert_eq!(net.relation(&cidr4!("10.1.255.255/16")), Equal);
+    assert_eq!(net.relation(&cidr4!("10.1.2.0/24")), Contains);
+    assert_eq!(net.relation(&cidr4!("10.0.0.0/8")), ContainedBy);
+    assert_eq!(net.relation(&cidr4!("10.2.0.0/16")), Adjacent);
+    assert_eq!(net.relation(&cidr4!("10.0.255.255/32")), Adjacent);
+    assert_eq!(net.relation(&cidr4!("10.3.0.0/16")), Disjoint);
+    assert_eq!(cidr4!("0.0.0.0/0").relation(&net), Contains);
+    assert_eq!(
+        cidr4!("255.255.255.255/32").relation(&cidr4!("0.0.0.0/32")),
+        Disjoint
+    );
+    assert_eq!(
+        cidr4!("0.0.0.0/1").relation(&cidr4!("128.0.0.0/1")),
+        Adjacent
+    );
+
+    assert!(net.overlaps(&cidr4!("10.0.0.0/8")));
+    assert!(!net.overlaps(&cidr4!("10.2.0.0/16")));
+    assert!(net.is_subnet_of(&net));
+    assert!(net.is_subnet_of(&cidr4!("10.0.0.0/15")));
+    assert!(!net.is_subnet_of(&cidr4!("10.1.0.0/17")));
+    assert!(net.is_supernet_of(&cidr4!("10.1.0.0/17")));
+    assert!(net.contains_prefix(&cidr4!("10.1.128.7/32")));
+    assert!(!net.contains_prefix(&cidr4!("10.0.0.0/8")));
+}
+#[test]
+fn ipv6_and_mixed_relations() {
+    use PrefixRelation::*;
+    let net = cidr6!("2001:db8::/32");
+    assert_eq!(net.relation(&cidr6!("2001:db8:1::/48")), Contains);
+    assert_eq!(net.relation(&cidr6!("2001::/16")), ContainedBy);
+    assert_eq!(net.relation(&cidr6!("2001:db9::/32")), Adjacent);
+    assert_eq!(cidr6!("::/0").relation(&cidr6!("::/0")), Equal);
+    assert_eq!(
+        cidr6!("ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff/128").relation(&cidr6!("::/128")),
+        Disjoint
+    );
+
+    let v4 = cidr!("0.0.0.0/0");
+    let v6 = cidr!("::/0");
+    assert_eq!(v4.relation(&v6), Disjoint);
+    assert!(!v6.overlaps(&v4));
+    assert!(cidr!("2001:db8::/32").is_subnet_of(&v6));
+    assert!(v4.contains_prefix(&cidr!("192.0.2.0/24")));
+}
+#[test]
+fn ordering() {
+    let mut nets = [
+        cidr!("2001:db8::/32"),
+        cidr!("10.1.0.0/16"),
+        cidr!("10.0.0.0/8"),
+        cidr!("10.0.0.1/8"),
+        cidr!("9.255.255.255/32"),
+        cidr!("::/0"),
+        cidr!("10.0.0.0/16"),
+    ];
+    nets.sort();
+    assert_eq!(
+        nets,
+        [
+            cidr!("9.255.255.255/32"),
+            cidr!("10.0.0.0/8"),
+            cidr!("10.0.0.1/8"),
+            cidr!("10.0.0.0/16"),
+            cidr!("10.1.0.0/16"),
+            cidr!("::/0"),
+            cidr!("2001:db8::/32"),
+        ]
+    );
+    let set: BTreeSet<MaskedIpv6> = [cidr6!("::1/128"), cidr6!("::/127"), cidr6!("::1/128")]
+        .into_iter()
+        .collect();
+    assert_eq!(
+        set.into_iter().collect::<Vec<_>>(),
+        [cidr6!("::/127"), cidr6!("::1/128")]
+    );
+}
+#[test]
+fn common_prefix_len() {
+    let a = ipv4!("10.0.0.1");
+    assert_eq!(a.common_prefix_len(a), 32);
+    assert_eq!(a.common_prefix_len(ipv4!("10.0.0.0")), 31);
+    assert_eq!(a.common_prefix_len(ipv4!("10.128.0.1")), 8);
+    assert_eq!(a.common_prefix_len(ipv4!("138.0.0.1")), 0);
+    let b = ipv6!("2001:db8::1");
+    assert_eq!(b.common_prefix_len(b), 128);
+    assert_eq!(b.common_prefix_len(ipv6!("2001:db8:8000::")), 32);
+    assert_eq!(b.common_prefix_len(ipv6!("a001::")), 0);
+}
+#[test]
+fn covering() {
+    let hosts = [ipv4!("10.0.0.7"), ipv4!("10.0.1.200"), ipv4!("10.0.3.1")];
+    assert_eq!(MaskedIpv4::covering(hosts), Some(cidr4!("10.0.0.0/22")));
+    assert_eq!(
+        MaskedIpv4::covering([ipv4!("192.0.2.1")]),
+        Some(cidr4!("192.0.2.1/32"))
+    );
+    assert_eq!(
+        MaskedIpv4::covering([cidr4!("10.1.2.3/24"), cidr4!("10.1.2.128/25")]),
+        Some(cidr4!("10.1.2.0/24"))
+    );
+    assert_eq!(
+        MaskedIpv4::covering([cidr4!("10.0.0.0/8"), cidr4!("11.0.0.0/8")]),
+        Some(cidr4!("10.0.0.0/7"))
+    );
+    assert_eq!(
+        MaskedIpv4::covering([ipv4!("0.0.0.0"), ipv4!("255.255.255.255")]),
+        Some(cidr4!("0.0.0.0/0"))
+    );
+    assert_eq!(MaskedIpv4::covering(Vec::<Ipv4Addr>::new()), None);
+
+    assert_eq!(
+        MaskedIpv6::covering([ipv6!("2001:db8::1"), ipv6!("2001:db8:0:ff::")]),
+        Some(cidr6!("2001:db8::/56"))
+    );
+    assert_eq!(
+        MaskedIpv6::covering([cidr6!("fe80::1/64")]),
+        Some(cidr6!("fe80::/64"))
+    );
+
+    assert_eq!(
+        MaskedIp::covering([ip!("10.0.0.1"), ip!("10.0.0.2")]),
+        Ok(cidr!("10.0.0.0/30"))
+    );
+    assert_eq!(
+        MaskedIp::covering([cidr!("2001:db8::/48"), cidr!("2001:db8:1::/48")]),
+        Ok(cidr!("2001:db8::/47"))
+    );
+    assert_eq!(
+        MaskedIp::covering([ip!("10.0.0.1"), ip!("::1")]),
+        Err(CoveringError::MixedFamilies)
+    );
+    assert_eq!(
+        MaskedIp::covering(Vec::<MaskedIp>::new()),
+        Err(CoveringError::Empty)
+    );
+}
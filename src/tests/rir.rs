@@ -0,0 +1,138 @@
+use crate::*;
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+#[test]
+fn ipv4_ranges() {
+    assert_eq!(Ipv4Range::from_count(ipv4!("1.0.0.0"), u64::MAX), None);
+    let range = Ipv4Range::from_count(ipv4!("198.51.100.0"), 768).unwrap();
+    assert_eq!(range.end(), ipv4!("198.51.102.255"));
+    assert_eq!(range.count(), 768);
+    assert_eq!(range.to_masked(), None);
+    assert_eq!(
+        range.cidrs().collect::<Vec<_>>(),
+        [cidr4!("198.51.100.0/23"), cidr4!("198.51.102.0/24")]
+    );
+    assert_eq!(range.to_string(), "198.51.100.0-198.51.102.255");
+    assert_eq!(
+        "198.51.100.0-198.51.102.255".parse::<Ipv4Range>().ok(),
+        Some(range)
+    );
+    assert!(range.contains(ipv4!("198.51.101.7")));
+    assert!(!range.contains(ipv4!("198.51.103.0")));
+
+    let net = cidr4!("10.0.0.0/8");
+    assert_eq!(Ipv4Range::from(net).to_masked(), Some(net));
+    let all = Ipv4Range::from_count(ipv4!("0.0.0.0"), 1 << 32).unwrap();
+    assert_eq!(all.to_masked(), Some(cidr4!("0.0.0.0/0")));
+    assert_eq!(
+        Ipv4Range::from_count(ipv4!("255.255.255.255"), 1)
+            .unwrap()
+            .count(),
+        1
+    );
+    assert_eq!(Ipv4Range::from_count(ipv4!("255.255.255.255"), 2), None);
+    assert_eq!(Ipv4Range::from_count(ipv4!("10.0.0.0"), 0), None);
+    assert_eq!(Ipv4Range::new(ipv4!("10.0.0.2"), ipv4!("10.0.0.1")), None);
+    assert!("10.0.0.2-10.0.0.1".parse::<Ipv4Range>().is_err());
+}
+#[test]
+fn records() {
+    let text = include_str!("fixtures/delegated-extended.txt");
+    let records: Vec<_> = RirRecords::new(text).map(Result::unwrap).collect();
+    assert_eq!(records.len(), 8);
+    assert_eq!(
+        records[0],
+        RirRecord {
+            registry: "ripencc",
+            country: "FR",
+            resource: RirResource::Asn {
+                first: 2200,
+                count: 1
+            },
+            date: Some(RirDate {
+                year: 1993,
+                month: 9,
+                day: 1
+            }),
+            status: RirStatus::Allocated,
+            opaque_id: Some("a1b2c3d4-0001"),
+        }
+    );
+    assert_eq!(records[0].prefixes().count(), 0);
+    assert_eq!(records[2].date.unwrap().to_string(), "20100118");
+    assert_eq!(
+        records[2].prefixes().collect::<Vec<_>>(),
+        [cidr!("192.0.2.0/24")]
+    );
+    // 24 addresses starting at .8 are not one network
+    assert_eq!(
+        records[4].prefixes().collect::<Vec<_>>(),
+        [cidr!("203.0.113.8/29"), cidr!("203.0.113.16/28")]
+    );
+    assert_eq!(records[5].date, None);
+    assert_eq!(records[5].status, RirStatus::Available);
+    assert_eq!(records[5].opaque_id, Some(""));
+    assert_eq!(
+        records[6].resource,
+        RirResource::Ipv6(cidr6!("2001:db8::/32"))
+    );
+    assert_eq!(
+        records[7].prefixes().collect::<Vec<_>>(),
+        [cidr!("2001:db8:8000::/33")]
+    );
+    assert_eq!(records[7].country, "");
+    assert_eq!(records[7].status, RirStatus::Reserved);
+
+    // the standard format has no opaque ID
+    let record = RirRecord::parse("arin|US|ipv4|192.0.2.0|512|19990101|assigned").unwrap();
+    assert_eq!(record.opaque_id, None);
+}
+#[test]
+fn errors() {
+    use RirErrorKind::*;
+    assert_eq!(
+        RirRecord::parse("arin|US|ipv4|192.0.2.0|512"),
+        Err(MissingField)
+    );
+    assert_eq!(
+        RirRecord::parse("arin|US|ipx|1|1|19990101|assigned"),
+        Err(InvalidType)
+    );
+    assert_eq!(
+        RirRecord::parse("arin|US|ipv4|192.0.2|512|19990101|assigned"),
+        Err(InvalidStart)
+    );
+    assert_eq!(
+        RirRecord::parse("arin|US|ipv4|255.255.255.0|512|19990101|assigned"),
+        Err(InvalidValue)
+    );
+    assert_eq!(
+        RirRecord::parse("ripencc|NL|ipv4|1.0.0.0|18446744073709551615|19990101|assigned"),
+        Err(InvalidValue)
+    );
+    assert_eq!(
+        RirRecord::parse("arin|US|ipv6|2001:db8::|129|19990101|assigned"),
+        Err(InvalidValue)
+    );
+    assert_eq!(
+        RirRecord::parse("arin|US|asn|1|1|19991301|assigned"),
+        Err(InvalidDate)
+    );
+    assert_eq!(
+        RirRecord::parse("arin|US|asn|1|1|19990101|gone"),
+        Err(InvalidStatus)
+    );
+
+    let text = "2|arin|1|1|19830101|20261016|-0400\narin|US|asn|1|x|19990101|assigned\n";
+    let err = RirRecords::new(text).next().unwrap().unwrap_err();
+    assert_eq!(
+        err,
+        RirError {
+            kind: InvalidValue,
+            line: 2
+        }
+    );
+    assert_eq!(err.to_string(), "line 2: invalid value");
+}
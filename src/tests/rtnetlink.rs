@@ -0,0 +1,225 @@
+// The fixtures are route and address dumps recorded from a little-endian Linux host.
+
+use crate::*;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+const ROUTES_V4: &[u8] = include_bytes!("fixtures/rtnetlink-route-dump-ipv4.bin");
+const ROUTES_V6: &[u8] = include_bytes!("fixtures/rtnetlink-route-dump-ipv6.bin");
+const ADDRS_V4: &[u8] = include_bytes!("fixtures/rtnetlink-addr-dump-ipv4.bin");
+const ADDRS_V6: &[u8] = include_bytes!("fixtures/rtnetlink-addr-dump-ipv6.bin");
+
+fn routes(bytes: &[u8]) -> Vec<RouteMessage> {
+    RtnlMessages::new(bytes)
+        .map(|m| match m.unwrap() {
+            RtnlMessage::Route(route) => route,
+            m => panic!("expected a route, found {m:?}"),
+        })
+        .collect()
+}
+
+fn addresses(bytes: &[u8]) -> Vec<AddressMessage<'_>> {
+    RtnlMessages::new(bytes)
+        .map(|m| match m.unwrap() {
+            RtnlMessage::Address(address) => address,
+            m => panic!("expected an address, found {m:?}"),
+        })
+        .collect()
+}
+
+#[test]
+#[cfg(target_endian = "little")]
+fn ipv4_route_dump() {
+    let routes = routes(ROUTES_V4);
+    assert_eq!(routes.len(), 7);
+    let default = routes[0];
+    assert_eq!(default.header.kind, RtnlMessageType::NEWROUTE);
+    assert!(default.header.flags.contains(NetlinkFlags::MULTI));
+    assert_eq!(default.destination, cidr!("0.0.0.0/0"));
+    assert_eq!(default.gateway, Some(ip!("192.0.2.1")));
+    assert_eq!(default.output_interface, Some(4));
+    assert_eq!(default.table, RouteMessage::MAIN_TABLE);
+    assert_eq!(default.protocol, RouteProtocol::BOOT);
+    assert_eq!(default.kind, RouteType::UNICAST);
+
+    let connected = routes[1];
+    assert_eq!(connected.destination, cidr!("192.0.2.0/24"));
+    assert_eq!(connected.gateway, None);
+    assert_eq!(connected.preferred_source, Some(ip!("192.0.2.2")));
+    assert_eq!(connected.scope, RouteScope::LINK);
+    assert_eq!(connected.protocol, RouteProtocol::KERNEL);
+
+    let local = routes[3];
+    assert_eq!(local.destination, cidr!("127.0.0.1/32"));
+    assert_eq!(local.table, 255);
+    assert_eq!(local.scope, RouteScope::HOST);
+    assert_eq!(local.kind, RouteType::LOCAL);
+    assert_eq!(routes[6].kind, RouteType::BROADCAST);
+
+    // IPv4 dumps have no attributes which are skipped, so they encode to the same bytes.
+    let mut rest = ROUTES_V4;
+    let mut buf = [0; 64];
+    for route in routes {
+        let len = route.encode(&mut buf).unwrap();
+        assert_eq!(buf[..len], rest[..len]);
+        rest = &rest[len..];
+    }
+}
+
+#[test]
+#[cfg(target_endian = "little")]
+fn ipv6_route_dump() {
+    let routes = routes(ROUTES_V6);
+    assert_eq!(routes.len(), 7);
+    assert_eq!(routes[0].destination, cidr!("fd00::/64"));
+    assert_eq!(routes[0].priority, Some(256));
+    let default = routes[2];
+    assert_eq!(default.destination, cidr!("::/0"));
+    assert_eq!(default.gateway, Some(ip!("fd00::1")));
+    assert_eq!(default.priority, Some(1024));
+    assert_eq!(routes[6].destination, cidr!("ff00::/8"));
+    assert_eq!(routes[6].kind, RouteType::MULTICAST);
+
+    // RTA_CACHEINFO and RTA_PREF are skipped, so the encoding is shorter.
+    let mut buf = [0; 128];
+    for route in routes {
+        let len = route.encode(&mut buf).unwrap();
+        assert!(len < 0x74);
+        assert_eq!(RouteMessage::parse(&buf[..len]), Ok(route));
+    }
+}
+
+#[test]
+#[cfg(target_endian = "little")]
+fn address_dumps() {
+    let v4 = addresses(ADDRS_V4);
+    assert_eq!(v4.len(), 2);
+    assert_eq!(v4[1].index, 4);
+    assert_eq!(v4[1].address, cidr!("192.0.2.2/24"));
+    assert_eq!(v4[1].local, Some(ip!("192.0.2.2")));
+    assert_eq!(v4[1].broadcast, Some(ipv4!("192.0.2.255")));
+    assert_eq!(v4[1].label, Some("eth0"));
+    assert_eq!(v4[0].label, Some("lo"));
+    assert_eq!(v4[0].scope, RouteScope::HOST);
+
+    let v6 = addresses(ADDRS_V6);
+    assert_eq!(v6.len(), 3);
+    assert_eq!(v6[1].address, cidr!("fd00::2/64"));
+    assert_eq!(v6[1].local, None);
+    // IFA_F_NODAD and IFA_F_PERMANENT.
+    assert_eq!(v6[1].flags, 0x82);
+    assert_eq!(v6[2].scope, RouteScope::LINK);
+
+    let mut buf = [0; 128];
+    for address in v4.into_iter().chain(v6) {
+        let len = address.encode(&mut buf).unwrap();
+        assert_eq!(AddressMessage::parse(&buf[..len]), Ok(address));
+    }
+}
+
+#[test]
+fn new_requests() {
+    let mut route = RouteMessage::new(cidr!("198.51.100.0/24"));
+    route.gateway = Some(ip!("192.0.2.1"));
+    route.output_interface = Some(4);
+    route.table = 1000;
+    route.header.seq = 7;
+    let mut buf = [0; 128];
+    let len = route.encode(&mut buf).unwrap();
+    assert_eq!(len, 16 + 12 + 8 * 4);
+    let parsed = RouteMessage::parse(&buf[..len]).unwrap();
+    assert_eq!(parsed, route);
+    assert!(
+        parsed
+            .header
+            .flags
+            .contains(NetlinkFlags::CREATE | NetlinkFlags::EXCL)
+    );
+    // Tables above 255 are given only by RTA_TABLE.
+    assert_eq!(buf[16 + 4], 252);
+
+    let mut address = AddressMessage::new(4, cidr!("fd00::10/64"));
+    address.label = Some("eth0:long-label");
+    let len = address.encode(&mut buf).unwrap();
+    let parsed = AddressMessage::parse(&buf[..len]).unwrap();
+    assert_eq!(parsed, address);
+    assert_eq!(parsed.header.kind, RtnlMessageType::NEWADDR);
+    let v4 = AddressMessage::new(4, cidr!("192.0.2.9/24"));
+    assert_eq!(v4.local, Some(ip!("192.0.2.9")));
+}
+
+#[test]
+fn encode_errors() {
+    let mut route = RouteMessage::new(cidr!("198.51.100.0/24"));
+    route.gateway = Some(ip!("fd00::1"));
+    assert_eq!(
+        route.encode(&mut [0; 128]),
+        Err(NetlinkError::MixedFamilies)
+    );
+    route.gateway = None;
+    assert_eq!(
+        route.encode(&mut [0; 16]),
+        Err(NetlinkError::BufferTooSmall(16 + 12 + 8 + 8))
+    );
+    assert_eq!(route.encode(&mut [0; 44]), Ok(44));
+
+    // IFA_LABEL's length, including its header and terminating nul, must fit in 16 bits.
+    let label = "a".repeat(65531);
+    let mut address = AddressMessage::new(4, cidr!("192.0.2.9/24"));
+    let mut buf = vec![0; 70000];
+    address.label = Some(&label[1..]);
+    let len = address.encode(&mut buf).unwrap();
+    assert_eq!(AddressMessage::parse(&buf[..len]).unwrap(), address);
+    address.label = Some(&label);
+    assert_eq!(
+        address.encode(&mut buf),
+        Err(NetlinkError::InvalidAttribute(3))
+    );
+}
+
+#[test]
+#[cfg(target_endian = "little")]
+fn parse_errors() {
+    assert_eq!(RtnlMessage::parse(&[0; 8]), Err(NetlinkError::Truncated));
+    assert_eq!(
+        RtnlMessage::parse(&ROUTES_V4[..40]),
+        Err(NetlinkError::Truncated)
+    );
+    let mut bytes = ROUTES_V4[..0x34].to_vec();
+    bytes[0] = 8;
+    assert_eq!(RtnlMessage::parse(&bytes), Err(NetlinkError::InvalidLength));
+    // The route family.
+    let mut bytes = ROUTES_V4[..0x34].to_vec();
+    bytes[16] = 7;
+    assert_eq!(
+        RtnlMessage::parse(&bytes),
+        Err(NetlinkError::UnsupportedFamily(7))
+    );
+    // The destination length.
+    let mut bytes = ROUTES_V4[..0x34].to_vec();
+    bytes[17] = 33;
+    assert_eq!(
+        RtnlMessage::parse(&bytes),
+        Err(NetlinkError::InvalidPrefixLength(33))
+    );
+    // The length of RTA_GATEWAY.
+    let mut bytes = ROUTES_V4[..0x34].to_vec();
+    bytes[36] = 6;
+    assert_eq!(
+        RtnlMessage::parse(&bytes),
+        Err(NetlinkError::InvalidAttribute(5))
+    );
+    assert_eq!(
+        AddressMessage::parse(ROUTES_V4),
+        Err(NetlinkError::UnsupportedType(24))
+    );
+
+    // NLMSG_ERROR is reported and skipped, and NLMSG_DONE ends iteration.
+    let mut bytes = vec![20, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    bytes.extend_from_slice(ROUTES_V4);
+    bytes.extend_from_slice(&[0xff; 4]);
+    let messages: Vec<_> = RtnlMessages::new(&bytes).collect();
+    assert_eq!(messages.len(), 8);
+    assert_eq!(messages[0], Err(NetlinkError::UnsupportedType(2)));
+}
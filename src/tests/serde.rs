@@ -0,0 +1,22 @@
+use crate::*;
+
+#[test]
+fn serialize_as_strings() {
+    let masked: MaskedIp = "10.1.2.3 255.255.0.0".parse().unwrap();
+    assert_eq!(serde_json::to_string(&masked).unwrap(), r#""10.1.2.3/16""#);
+    let mask = Ipv4Mask::new(20);
+    assert_eq!(serde_json::to_string(&mask).unwrap(), r#""/20""#);
+    let ip = Ipv6Addr::LOCALHOST;
+    assert_eq!(serde_json::to_string(&ip).unwrap(), r#""::1""#);
+}
+#[test]
+fn deserialize_from_strings() {
+    let masked: MaskedIpv4 = serde_json::from_str(r#""10.1.2.3 255.255.0.0""#).unwrap();
+    assert_eq!(masked, MaskedIpv4::cidr(Ipv4Addr::new(10, 1, 2, 3), 16));
+    let masked: MaskedIpv6 = serde_json::from_str(r#""fe80::1/64""#).unwrap();
+    assert_eq!(masked.mask.len(), 64);
+    let ip: IpAddr = serde_json::from_str(r#""192.0.2.1""#).unwrap();
+    assert_eq!(ip, IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1)));
+    assert!(serde_json::from_str::<Ipv4Mask>(r#""255.0.255.0""#).is_err());
+    assert!(serde_json::from_str::<Ipv6Addr>("6").is_err());
+}
@@ -0,0 +1,126 @@
+//! Runs the `ipcalc` binary and checks its output and exit codes.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Runs ipcalc with `args` and `stdin`, returning the exit code and standard output.
+fn ipcalc(args: &[&str], stdin: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ipcalc"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn describe() {
+    let (code, out) = ipcalc(&["192.168.1.10/24"], "");
+    assert_eq!(code, 0);
+    let lines: Vec<_> = out.lines().collect();
+    assert_eq!(lines[0], "address:       192.168.1.10");
+    assert_eq!(lines[1], "network:       192.168.1.0/24");
+    assert_eq!(lines[5], "broadcast:     192.168.1.255");
+    assert_eq!(lines[8], "host count:    254");
+    assert_eq!(lines[9], "reverse zone:  1.168.192.in-addr.arpa");
+    assert_eq!(lines[10], "type:          private (RFC 1918)");
+
+    // Wildcard masks and bare addresses are accepted too.
+    let (_, out) = ipcalc(&["10.0.0.1", "0.0.255.255"], "");
+    assert!(out.contains("network:       10.0.0.0/16\n"));
+    let (_, out) = ipcalc(&["2001:db8::1"], "");
+    assert!(out.contains("network:       2001:db8::1/128\n"));
+
+    assert_eq!(ipcalc(&["10.0.0.0/33"], "").0, 2);
+}
+
+#[test]
+fn describe_json() {
+    let (code, out) = ipcalc(&["--json", "2001:db8::1/64"], "");
+    assert_eq!(code, 0);
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json["network"], "2001:db8::/64");
+    assert_eq!(json["prefix_length"], 64);
+    assert_eq!(json["last_address"], "2001:db8::ffff:ffff:ffff:ffff");
+    assert_eq!(json["host_count"], u64::MAX);
+    assert_eq!(json["type"], "documentation");
+}
+
+#[test]
+fn split() {
+    let (code, out) = ipcalc(&["split", "10.0.0.0/24", "26"], "");
+    assert_eq!(code, 0);
+    assert_eq!(
+        out,
+        "10.0.0.0/26\n10.0.0.64/26\n10.0.0.128/26\n10.0.0.192/26\n"
+    );
+    let (_, out) = ipcalc(&["-j", "split", "10.0.0.0/24", "/25"], "");
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json, serde_json::json!(["10.0.0.0/25", "10.0.0.128/25"]));
+
+    assert_eq!(ipcalc(&["split", "10.0.0.0/24", "23"], "").0, 2);
+    assert_eq!(ipcalc(&["split", "10.0.0.0/24"], "").0, 2);
+}
+
+#[test]
+fn aggregate() {
+    let input = "10.0.0.0/25\n10.0.0.128/25 # second half\n\n192.0.2.1\n";
+    let (code, out) = ipcalc(&["aggregate"], input);
+    assert_eq!(code, 0);
+    assert_eq!(out, "10.0.0.0/24\n192.0.2.1/32\n");
+    let (_, out) = ipcalc(&["--json", "aggregate"], input);
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(json, serde_json::json!(["10.0.0.0/24", "192.0.2.1/32"]));
+
+    assert_eq!(ipcalc(&["aggregate"], "10.0.0.0/8\nbogus\n").0, 2);
+}
+
+#[test]
+fn contains_exit_codes() {
+    let (code, out) = ipcalc(&["contains", "10.0.0.0/8", "10.1.1.1"], "");
+    assert_eq!((code, out.as_str()), (0, "10.1.1.1 is in 10.0.0.0/8\n"));
+    let (code, out) = ipcalc(&["contains", "10.0.0.0/8", "10.1.1.1", "11.0.0.1"], "");
+    assert_eq!(code, 1);
+    assert!(out.ends_with("11.0.0.1 is not in 10.0.0.0/8\n"));
+    let (code, out) = ipcalc(
+        &["--json", "contains", "10.0.0.0/8", "10.1.1.1", "11.0.0.1"],
+        "",
+    );
+    assert_eq!(code, 1);
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {"ip": "10.1.1.1", "contained": true},
+            {"ip": "11.0.0.1", "contained": false},
+        ])
+    );
+    let (code, out) = ipcalc(
+        &["--json", "contains", "10.0.0.0/8", "10.0.0.1", "10.0.0.1"],
+        "",
+    );
+    assert_eq!(code, 0);
+    let json: serde_json::Value = serde_json::from_str(&out).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!([
+            {"ip": "10.0.0.1", "contained": true},
+            {"ip": "10.0.0.1", "contained": true},
+        ])
+    );
+
+    assert_eq!(ipcalc(&["contains", "10.0.0.0/8", "bogus"], "").0, 2);
+    assert_eq!(ipcalc(&["contains", "10.0.0.0/8"], "").0, 2);
+}
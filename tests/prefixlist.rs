@@ -0,0 +1,120 @@
+//! Runs the `prefixlist` binary and checks its output and exit codes.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Writes `text` to a file named `name` in a scratch directory, returning its path.
+fn file(name: &str, text: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, text).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+/// Runs prefixlist with `args` and `stdin`, returning the exit code and standard output.
+fn prefixlist(args: &[&str], stdin: &str) -> (i32, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prefixlist"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status.code().unwrap(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn aggregate_and_union() {
+    let a = file(
+        "aggregate-a.txt",
+        "10.0.0.0/25\n10.0.0.128/25 # second half\n",
+    );
+    let b = file("aggregate-b.txt", "# comment\n\n192.0.2.0/24\n");
+    for command in ["aggregate", "union"] {
+        let (code, out) = prefixlist(&[command, &a, &b], "");
+        assert_eq!(code, 0);
+        assert_eq!(
+            out,
+            format!("10.0.0.0/24 # {a}:1, {a}:2\n192.0.2.0/24 # {b}:3\n")
+        );
+    }
+    assert_eq!(prefixlist(&["aggregate"], "").0, 2);
+}
+
+#[test]
+fn intersect_and_subtract() {
+    let a = file("intersect-a.txt", "10.0.0.0/24\n192.0.2.0/24\n");
+    let b = file("intersect-b.txt", "10.0.0.128/25\n");
+    let (code, out) = prefixlist(&["intersect", &a, &b], "");
+    assert_eq!(code, 0);
+    assert_eq!(out, format!("10.0.0.128/25 # {a}:1, {b}:1\n"));
+    let (code, out) = prefixlist(&["subtract", &a, &b], "");
+    assert_eq!(code, 0);
+    assert_eq!(out, format!("10.0.0.0/25 # {a}:1\n192.0.2.0/24 # {a}:2\n"));
+    assert_eq!(prefixlist(&["intersect", &a], "").0, 2);
+    assert_eq!(prefixlist(&["subtract", &a, &b, &b], "").0, 2);
+}
+
+#[test]
+fn diff_exit_codes() {
+    let old = file("diff-old.txt", "10.0.0.0/24\n192.0.2.0/24\n");
+    let new = file("diff-new.txt", "10.0.0.0/24\n198.51.100.0/24\n");
+    let (code, out) = prefixlist(&["diff", &old, &new], "");
+    assert_eq!(code, 1);
+    assert_eq!(
+        out,
+        format!("- 192.0.2.0/24 # {old}:2\n+ 198.51.100.0/24 # {new}:2\n")
+    );
+    assert_eq!(prefixlist(&["diff", &old, &old], ""), (0, String::new()));
+}
+
+#[test]
+fn stdin() {
+    let a = file("stdin-a.txt", "10.0.0.0/25\n");
+    let (code, out) = prefixlist(&["union", &a, "-"], "10.0.0.128/25\n");
+    assert_eq!(code, 0);
+    assert_eq!(out, format!("10.0.0.0/24 # {a}:1, <stdin>:1\n"));
+    assert_eq!(prefixlist(&["aggregate", "-"], "bogus\n").0, 2);
+}
+
+#[test]
+fn usage() {
+    let (code, out) = prefixlist(&["-h", "missing.txt"], "");
+    assert_eq!(code, 0);
+    assert!(out.starts_with("usage: prefixlist"));
+    assert_eq!(prefixlist(&["--help"], "").0, 0);
+    assert_eq!(prefixlist(&["aggregate", "missing.txt"], "").0, 2);
+
+    // A bad command fails without reading standard input, which is left open here.
+    let mut child = Command::new(env!("CARGO_BIN_EXE_prefixlist"))
+        .args(["frobnicate", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if started.elapsed() > Duration::from_secs(10) {
+            child.kill().unwrap();
+            panic!("prefixlist waited on standard input");
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    assert_eq!(status.code(), Some(2));
+}